@@ -0,0 +1,283 @@
+use anyhow::{anyhow, bail, Result};
+use std::path::PathBuf;
+use std::time::Duration;
+
+pub const USAGE: &str = "Usage: supavoice-cli [--json] <command>
+
+Commands:
+  transcribe <file>                      Transcribe a WAV file with the app's preferences
+  format --template <id> [<file>]        Format a transcript from a file or stdin
+                                         (templates: email, notes, action_items)
+  models list                            List models and their status
+  models download <id>                   Download a model
+  models delete <id>                     Delete a downloaded model
+  record [--until-silence [<secs>]]      Record from the default microphone
+         [--max <secs>] [--output <file>]
+         [--transcribe]
+  serve [--port <port>] [--token <token>]
+                                         Serve the local HTTP/WebSocket API until Ctrl-C
+                                         (token also read from SUPAVOICE_API_TOKEN)
+
+Options:
+  --json                                 Print machine-readable JSON
+  -h, --help                             Show this help";
+
+// Seconds of quiet that end a `record --until-silence`
+const DEFAULT_SILENCE_SECS: f32 = 2.0;
+
+#[derive(Debug, PartialEq)]
+pub enum ModelsCommand {
+    List,
+    Download(String),
+    Delete(String),
+}
+
+#[derive(Debug, PartialEq)]
+pub enum Command {
+    Transcribe {
+        file: PathBuf,
+    },
+    Format {
+        template: String,
+        // None reads stdin
+        file: Option<PathBuf>,
+    },
+    Models(ModelsCommand),
+    Record {
+        until_silence: Option<Duration>,
+        max_secs: Option<u64>,
+        output: Option<PathBuf>,
+        transcribe: bool,
+    },
+    Serve {
+        // None uses the port from preferences
+        port: Option<u16>,
+        token: Option<String>,
+    },
+    Help,
+}
+
+#[derive(Debug, PartialEq)]
+pub struct Args {
+    pub json: bool,
+    pub command: Command,
+}
+
+pub fn parse(args: impl IntoIterator<Item = String>) -> Result<Args> {
+    let mut json = false;
+    let mut rest = Vec::new();
+    for arg in args {
+        match arg.as_str() {
+            "--json" => json = true,
+            "-h" | "--help" => {
+                return Ok(Args {
+                    json,
+                    command: Command::Help,
+                })
+            }
+            _ => rest.push(arg),
+        }
+    }
+
+    let mut rest = rest.into_iter().peekable();
+    let command = match rest.next().as_deref() {
+        None | Some("help") => Command::Help,
+        Some("transcribe") => Command::Transcribe {
+            file: rest.next().ok_or_else(|| anyhow!("transcribe needs an audio file"))?.into(),
+        },
+        Some("format") => {
+            let mut template = None;
+            let mut file = None;
+            while let Some(arg) = rest.next() {
+                match arg.as_str() {
+                    "--template" | "-t" => template = rest.next(),
+                    "-" => file = None,
+                    _ if arg.starts_with('-') => bail!("Unknown option for format: {}", arg),
+                    _ => file = Some(PathBuf::from(arg)),
+                }
+            }
+            Command::Format {
+                template: template.ok_or_else(|| anyhow!("format needs --template <id>"))?,
+                file,
+            }
+        }
+        Some("models") => {
+            let models = match rest.next().as_deref() {
+                Some("list") | None => ModelsCommand::List,
+                Some("download") => {
+                    ModelsCommand::Download(rest.next().ok_or_else(|| anyhow!("models download needs a model id"))?)
+                }
+                Some("delete") => {
+                    ModelsCommand::Delete(rest.next().ok_or_else(|| anyhow!("models delete needs a model id"))?)
+                }
+                Some(other) => bail!("Unknown models command: {}", other),
+            };
+            Command::Models(models)
+        }
+        Some("record") => {
+            let mut until_silence = None;
+            let mut max_secs = None;
+            let mut output = None;
+            let mut transcribe = false;
+            while let Some(arg) = rest.next() {
+                match arg.as_str() {
+                    "--until-silence" => {
+                        // The number of seconds is optional
+                        let secs = match rest.peek().and_then(|next| next.parse::<f32>().ok()) {
+                            Some(secs) => {
+                                rest.next();
+                                secs
+                            }
+                            None => DEFAULT_SILENCE_SECS,
+                        };
+                        // "inf" and "nan" parse as numbers but aren't a duration
+                        let duration = Some(secs)
+                            .filter(|secs| secs.is_finite())
+                            .and_then(|secs| Duration::try_from_secs_f32(secs.max(0.1)).ok())
+                            .ok_or_else(|| anyhow!("Invalid --until-silence: {}", secs))?;
+                        until_silence = Some(duration);
+                    }
+                    "--max" => {
+                        let secs = rest.next().ok_or_else(|| anyhow!("--max needs a number of seconds"))?;
+                        max_secs = Some(secs.parse().map_err(|_| anyhow!("Invalid --max: {}", secs))?);
+                    }
+                    "--output" | "-o" => output = rest.next().map(PathBuf::from),
+                    "--transcribe" => transcribe = true,
+                    _ => bail!("Unknown option for record: {}", arg),
+                }
+            }
+            if until_silence.is_none() && max_secs.is_none() {
+                bail!("record needs --until-silence or --max <secs>");
+            }
+            Command::Record {
+                until_silence,
+                max_secs,
+                output,
+                transcribe,
+            }
+        }
+        Some("serve") => {
+            let mut port = None;
+            let mut token = None;
+            while let Some(arg) = rest.next() {
+                match arg.as_str() {
+                    "--port" | "-p" => {
+                        let value = rest.next().ok_or_else(|| anyhow!("--port needs a number"))?;
+                        port = Some(value.parse().map_err(|_| anyhow!("Invalid --port: {}", value))?);
+                    }
+                    "--token" => token = rest.next(),
+                    _ => bail!("Unknown option for serve: {}", arg),
+                }
+            }
+            Command::Serve { port, token }
+        }
+        Some(other) => bail!("Unknown command: {}\n\n{}", other, USAGE),
+    };
+
+    Ok(Args { json, command })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse_line(line: &str) -> Result<Args> {
+        parse(line.split_whitespace().map(str::to_string))
+    }
+
+    fn command(line: &str) -> Command {
+        parse_line(line).unwrap().command
+    }
+
+    #[test]
+    fn commands() {
+        let cases: &[(&str, Command)] = &[
+            ("", Command::Help),
+            ("transcribe --help", Command::Help),
+            ("transcribe note.wav", Command::Transcribe { file: "note.wav".into() }),
+            (
+                "format -t email notes.txt",
+                Command::Format {
+                    template: "email".to_string(),
+                    file: Some("notes.txt".into()),
+                },
+            ),
+            (
+                "format --template notes -",
+                Command::Format {
+                    template: "notes".to_string(),
+                    file: None,
+                },
+            ),
+            ("models", Command::Models(ModelsCommand::List)),
+            ("models download base.en", Command::Models(ModelsCommand::Download("base.en".to_string()))),
+            ("serve", Command::Serve { port: None, token: None }),
+            (
+                "serve --port 9000 --token secret",
+                Command::Serve {
+                    port: Some(9000),
+                    token: Some("secret".to_string()),
+                },
+            ),
+        ];
+
+        for (line, expected) in cases {
+            assert_eq!(command(line), *expected, "args: {:?}", line);
+        }
+    }
+
+    #[test]
+    fn json_flag_anywhere() {
+        assert!(parse_line("--json models list").unwrap().json);
+        assert!(parse_line("models list --json").unwrap().json);
+        assert!(!parse_line("models list").unwrap().json);
+    }
+
+    #[test]
+    fn record() {
+        let cases: &[(&str, Option<Duration>, Option<u64>)] = &[
+            ("record --until-silence", Some(Duration::from_secs(2)), None),
+            ("record --until-silence 3.5", Some(Duration::from_secs_f32(3.5)), None),
+            ("record --until-silence --max 60", Some(Duration::from_secs(2)), Some(60)),
+            ("record --until-silence 0", Some(Duration::from_secs_f32(0.1)), None),
+            ("record --max 30", None, Some(30)),
+        ];
+
+        for (line, silence, max) in cases {
+            match command(line) {
+                Command::Record {
+                    until_silence,
+                    max_secs,
+                    ..
+                } => {
+                    assert_eq!(until_silence, *silence, "args: {:?}", line);
+                    assert_eq!(max_secs, *max, "args: {:?}", line);
+                }
+                other => panic!("args: {:?} parsed as {:?}", line, other),
+            }
+        }
+    }
+
+    #[test]
+    fn errors() {
+        let cases = [
+            "transcribe",
+            "format notes.txt",
+            "format -t email --bogus",
+            "models rename",
+            "models download",
+            "record",
+            "record --max soon",
+            "record --until-silence inf",
+            "record --until-silence -inf",
+            "record --until-silence NaN",
+            "record --until-silence 1e30",
+            "serve --port 70000",
+            "dance",
+        ];
+
+        for line in cases {
+            assert!(parse_line(line).is_err(), "args: {:?}", line);
+        }
+    }
+}
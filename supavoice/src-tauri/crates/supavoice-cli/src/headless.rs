@@ -0,0 +1,110 @@
+use anyhow::{anyhow, Result};
+use async_trait::async_trait;
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex};
+#[cfg(feature = "llama-inprocess")]
+use supavoice_core::formatting::{InProcessLlama, InProcessLlamaBackend};
+use supavoice_core::formatting::{FormattingBackend, LlmFormatter, LocalLlamaBackend, OpenAiCompatibleBackend};
+use supavoice_core::models::{ModelRecord, ModelRegistry};
+use supavoice_core::pipeline;
+use supavoice_core::preferences::{AppPreferences, FormattingBackendPreference, PreferencesManager};
+use supavoice_core::secrets;
+use supavoice_core::server::ServerBackend;
+use supavoice_core::transcription::WhisperTranscriber;
+
+// The app's preferences and models without the app. Models load on first use and
+// stay loaded, so `serve` answers later requests without reloading them.
+pub struct Headless {
+    pub registry: Arc<ModelRegistry>,
+    pub preferences: PreferencesManager,
+    // Keyed by model path, so changing the model in preferences loads the new one.
+    // An async lock, so concurrent requests wait for one load instead of each loading.
+    transcriber: tokio::sync::Mutex<Option<(PathBuf, Arc<WhisperTranscriber>)>>,
+    formatter: Mutex<Option<Arc<LlmFormatter>>>,
+    #[cfg(feature = "llama-inprocess")]
+    inprocess_llama: Arc<InProcessLlama>,
+}
+
+impl Headless {
+    pub fn new() -> Result<Self> {
+        Ok(Self {
+            registry: Arc::new(ModelRegistry::new()?),
+            preferences: PreferencesManager::new()?,
+            transcriber: tokio::sync::Mutex::new(None),
+            formatter: Mutex::new(None),
+            #[cfg(feature = "llama-inprocess")]
+            inprocess_llama: Arc::new(InProcessLlama::new()),
+        })
+    }
+
+    #[cfg(feature = "llama-inprocess")]
+    fn in_process_formatting_backend(&self, model_path: PathBuf) -> Result<Box<dyn FormattingBackend>> {
+        Ok(Box::new(InProcessLlamaBackend::new(self.inprocess_llama.clone(), model_path)))
+    }
+
+    #[cfg(not(feature = "llama-inprocess"))]
+    fn in_process_formatting_backend(&self, _model_path: PathBuf) -> Result<Box<dyn FormattingBackend>> {
+        Err(anyhow!(
+            "llama-server is not available and this build does not include in-process llama.cpp (llama-inprocess feature)"
+        ))
+    }
+}
+
+#[async_trait]
+impl ServerBackend for Headless {
+    async fn preferences(&self) -> AppPreferences {
+        self.preferences.get_preferences().await
+    }
+
+    async fn transcriber(&self, prefs: &AppPreferences) -> Result<Arc<WhisperTranscriber>> {
+        let model_path = pipeline::whisper_model_path(&self.registry, prefs).await?;
+
+        let mut cache = self.transcriber.lock().await;
+        if let Some((cached_path, transcriber)) = cache.as_ref() {
+            if *cached_path == model_path {
+                return Ok(transcriber.clone());
+            }
+        }
+
+        eprintln!("🔄 Loading Whisper model from {}", model_path.display());
+        let path = model_path.clone();
+        let transcriber = Arc::new(tokio::task::spawn_blocking(move || WhisperTranscriber::new(path)).await??);
+        *cache = Some((model_path, transcriber.clone()));
+        Ok(transcriber)
+    }
+
+    async fn formatting_backend(&self, prefs: &AppPreferences) -> Result<Box<dyn FormattingBackend>> {
+        let force_in_process = match &prefs.formatting_backend {
+            FormattingBackendPreference::OpenAiCompatible { base_url, model, context_size } => {
+                let api_key = secrets::get_api_key(secrets::FORMATTING_API_KEY)?;
+                let backend = OpenAiCompatibleBackend::new(base_url, api_key, model).with_context_size(*context_size);
+                return Ok(Box::new(backend));
+            }
+            FormattingBackendPreference::Local => false,
+            FormattingBackendPreference::InProcess => true,
+        };
+
+        let model_path = pipeline::llm_model_path(&self.registry, prefs).await?;
+        let formatter = if force_in_process {
+            None
+        } else {
+            let mut cache = self.formatter.lock().unwrap();
+            if cache.is_none() {
+                match LlmFormatter::new() {
+                    Ok(formatter) => *cache = Some(Arc::new(formatter)),
+                    Err(e) => eprintln!("⚠️  llama-server unavailable: {}", e),
+                }
+            }
+            cache.clone()
+        };
+
+        match formatter {
+            Some(formatter) => Ok(Box::new(LocalLlamaBackend::new(formatter, model_path))),
+            None => self.in_process_formatting_backend(model_path),
+        }
+    }
+
+    async fn models(&self) -> Result<Vec<ModelRecord>> {
+        self.registry.list_models().await
+    }
+}
@@ -0,0 +1,270 @@
+mod args;
+mod headless;
+
+use anyhow::{anyhow, Context, Result};
+use args::{Command, ModelsCommand, USAGE};
+use headless::Headless;
+use serde::Serialize;
+use std::io::Read;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use supavoice_core::audio::AudioRecorder;
+use supavoice_core::models::{DownloadEvent, ModelDownloader, ModelRecord, ModelStatus};
+use supavoice_core::pipeline;
+use supavoice_core::postprocess::Substitution;
+use supavoice_core::secrets;
+use supavoice_core::server::{generate_token, ApiServer, ServerBackend, ServerConfig};
+use supavoice_core::transcription::Segment;
+use supavoice_core::vocabulary::vocabulary_prompt;
+
+#[derive(Serialize)]
+struct TranscriptOutput {
+    file: PathBuf,
+    text: String,
+    segments: Vec<Segment>,
+    substitutions: Vec<Substitution>,
+}
+
+#[derive(Serialize)]
+struct FormatOutput {
+    template: String,
+    text: String,
+}
+
+#[derive(Serialize)]
+struct RecordOutput {
+    file: PathBuf,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    transcript: Option<TranscriptOutput>,
+}
+
+#[tokio::main]
+async fn main() {
+    let args = match args::parse(std::env::args().skip(1)) {
+        Ok(args) => args,
+        Err(e) => {
+            eprintln!("{}", e);
+            std::process::exit(2);
+        }
+    };
+
+    if let Err(e) = run(args.command, args.json).await {
+        eprintln!("❌ {:#}", e);
+        std::process::exit(1);
+    }
+}
+
+async fn run(command: Command, json: bool) -> Result<()> {
+    if command == Command::Help {
+        println!("{}", USAGE);
+        return Ok(());
+    }
+
+    let headless = Headless::new()?;
+    match command {
+        Command::Help => {}
+        Command::Transcribe { file } => {
+            let transcript = transcribe(&headless, &file).await?;
+            if json {
+                print_json(&transcript)?;
+            } else {
+                println!("{}", transcript.text);
+            }
+        }
+        Command::Format { template, file } => {
+            let transcript = match file {
+                Some(path) => std::fs::read_to_string(&path).with_context(|| format!("Reading {}", path.display()))?,
+                None => {
+                    let mut input = String::new();
+                    std::io::stdin().read_to_string(&mut input)?;
+                    input
+                }
+            };
+            let text = format(&headless, &template, transcript.trim()).await?;
+            if json {
+                print_json(&FormatOutput { template, text })?;
+            } else {
+                println!("{}", text);
+            }
+        }
+        Command::Models(command) => models(&headless, command, json).await?,
+        Command::Record {
+            until_silence,
+            max_secs,
+            output,
+            transcribe: should_transcribe,
+        } => {
+            let path = output.unwrap_or_else(default_recording_path);
+            record(&path, until_silence, max_secs).await?;
+
+            let transcript = if should_transcribe {
+                Some(transcribe(&headless, &path).await?)
+            } else {
+                None
+            };
+
+            if json {
+                print_json(&RecordOutput {
+                    file: path,
+                    transcript,
+                })?;
+            } else {
+                match transcript {
+                    Some(transcript) => println!("{}", transcript.text),
+                    None => println!("{}", path.display()),
+                }
+            }
+        }
+        Command::Serve { port, token } => serve(headless, port, token).await?,
+    }
+
+    Ok(())
+}
+
+fn print_json<T: Serialize>(value: &T) -> Result<()> {
+    println!("{}", serde_json::to_string_pretty(value)?);
+    Ok(())
+}
+
+async fn transcribe(headless: &Headless, file: &Path) -> Result<TranscriptOutput> {
+    let prefs = headless.preferences().await;
+
+    let audio_path = file
+        .to_str()
+        .ok_or_else(|| anyhow!("Audio path is not valid UTF-8: {}", file.display()))?;
+    let prompt = vocabulary_prompt(&prefs.custom_vocabulary);
+
+    eprintln!("🎤 Transcribing {}", file.display());
+    let segments =
+        pipeline::transcribe_segments(&prefs, audio_path, prompt.as_deref(), || headless.transcriber(&prefs)).await?;
+
+    let processed = pipeline::postprocess_segments(&prefs, &prefs.custom_vocabulary, &segments);
+
+    Ok(TranscriptOutput {
+        file: file.to_path_buf(),
+        text: processed.text,
+        segments,
+        substitutions: processed.corrected.substitutions,
+    })
+}
+
+async fn format(headless: &Headless, template_id: &str, transcript: &str) -> Result<String> {
+    let prefs = headless.preferences().await;
+
+    let template = pipeline::format_template(&prefs, template_id)?;
+    pipeline::format_or_clean(&prefs, &template, transcript, || headless.formatting_backend(&prefs)).await
+}
+
+async fn serve(headless: Headless, port: Option<u16>, token: Option<String>) -> Result<()> {
+    let prefs = headless.preferences().await;
+
+    // Build boxes often have no keyring, so the token can come from the command line
+    // or environment instead of the one the app generated
+    let token = match token.or_else(|| std::env::var("SUPAVOICE_API_TOKEN").ok()) {
+        Some(token) => token,
+        None => match secrets::get_api_key(secrets::API_SERVER_TOKEN).ok().flatten() {
+            Some(token) => token,
+            None => {
+                let token = generate_token();
+                eprintln!("🔑 Generated API token: {}", token);
+                token
+            }
+        },
+    };
+
+    let server = ApiServer::start(
+        Arc::new(headless),
+        ServerConfig {
+            port: port.unwrap_or(prefs.api_server.port),
+            token,
+        },
+    )
+    .await?;
+    println!("http://{}", server.addr());
+
+    tokio::signal::ctrl_c().await?;
+    server.stop();
+    Ok(())
+}
+
+async fn models(headless: &Headless, command: ModelsCommand, json: bool) -> Result<()> {
+    let registry = headless.registry.clone();
+
+    match command {
+        ModelsCommand::List => {
+            let models = registry.list_models().await?;
+            if json {
+                print_json(&models)?;
+            } else {
+                for model in &models {
+                    println!("{:<24} {:>6} MB  {:<14} {}", model.id, model.size_mb, status_label(model), model.name);
+                }
+            }
+        }
+        ModelsCommand::Download(model_id) => {
+            let downloader = ModelDownloader::new(registry.clone());
+            downloader
+                .download_model(model_id.clone(), |event| match &event {
+                    DownloadEvent::Progress { progress, bytes, total, .. } => {
+                        eprint!("\r⬇️  {:5.1}% ({} / {} MB)", progress, bytes / 1_048_576, total / 1_048_576);
+                    }
+                    DownloadEvent::Complete { .. } => eprintln!(),
+                })
+                .await?;
+
+            let model = registry.get_model(&model_id).await?;
+            if json {
+                print_json(&model)?;
+            } else {
+                println!("{}", model.path.as_deref().unwrap_or(Path::new("")).display());
+            }
+        }
+        ModelsCommand::Delete(model_id) => {
+            let downloader = ModelDownloader::new(registry.clone());
+            downloader.delete_model(model_id.clone()).await?;
+            if json {
+                print_json(&registry.get_model(&model_id).await?)?;
+            } else {
+                eprintln!("🗑️  Deleted {}", model_id);
+            }
+        }
+    }
+
+    Ok(())
+}
+
+fn status_label(model: &ModelRecord) -> String {
+    match &model.status {
+        ModelStatus::NotInstalled => "not installed".to_string(),
+        ModelStatus::Downloading { progress, .. } => format!("{:.0}%", progress),
+        ModelStatus::Installed => "installed".to_string(),
+        ModelStatus::Failed { .. } => "failed".to_string(),
+    }
+}
+
+fn default_recording_path() -> PathBuf {
+    let timestamp = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|elapsed| elapsed.as_secs())
+        .unwrap_or_default();
+    std::env::temp_dir().join(format!("supavoice_recording_{}.wav", timestamp))
+}
+
+async fn record(path: &Path, until_silence: Option<Duration>, max_secs: Option<u64>) -> Result<()> {
+    let path = path.to_path_buf();
+
+    eprintln!("🎙️  Recording to {}", path.display());
+    tokio::task::spawn_blocking(move || {
+        let recorder = AudioRecorder::new();
+        match (until_silence, max_secs) {
+            (Some(silence), max_secs) => recorder.record_until_silence(path, silence, max_secs),
+            (None, Some(secs)) => recorder.record_to_file(path, secs),
+            (None, None) => Err(anyhow!("record needs --until-silence or --max <secs>")),
+        }
+    })
+    .await??;
+    eprintln!("✅ Recording finished");
+
+    Ok(())
+}
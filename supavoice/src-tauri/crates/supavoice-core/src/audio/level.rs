@@ -0,0 +1,210 @@
+use serde::Serialize;
+use std::sync::atomic::{AtomicU32, AtomicU64, Ordering};
+use std::time::{Duration, Instant};
+
+// Samples this close to full scale count as clipped
+const CLIP_LEVEL: f32 = 0.99;
+// Peak level below which input counts as near-silent (about -60 dBFS)
+const NEAR_SILENCE: f32 = 0.001;
+// How long input must stay near-silent before the mic looks muted
+const MUTED_AFTER: Duration = Duration::from_secs(3);
+
+// Input level since the previous report, for a meter in the UI
+#[derive(Serialize, Clone, Copy, Debug, PartialEq)]
+pub struct AudioLevel {
+    // 0-1 of full scale
+    pub rms: f32,
+    pub peak: f32,
+    // Some samples hit full scale; the input gain is too high
+    pub clipping: bool,
+    // Input has been near-silent for a while; the mic may be muted
+    pub silent: bool,
+    // Callback buffers that didn't fit in the capture queue since recording started,
+    // and the samples lost with them
+    pub overruns: u64,
+    pub dropped_samples: u64,
+}
+
+// Written from the audio callback without locking, read and reset by the recording loop
+#[derive(Default)]
+pub(super) struct LevelMeter {
+    // f32 bits; non-negative floats order the same as their bits
+    peak: AtomicU32,
+    // f64 bits
+    sum_squares: AtomicU64,
+    samples: AtomicU64,
+    clipped: AtomicU64,
+    overruns: AtomicU64,
+    dropped_samples: AtomicU64,
+}
+
+impl LevelMeter {
+    pub(super) fn add_block(&self, block: &BlockLevel) {
+        self.peak.fetch_max(block.peak.to_bits(), Ordering::Relaxed);
+        let _ = self.sum_squares.fetch_update(Ordering::Relaxed, Ordering::Relaxed, |bits| {
+            Some((f64::from_bits(bits) + block.sum_squares).to_bits())
+        });
+        self.samples.fetch_add(block.samples, Ordering::Relaxed);
+        self.clipped.fetch_add(block.clipped, Ordering::Relaxed);
+    }
+
+    pub(super) fn add_overrun(&self, dropped_samples: u64) {
+        self.overruns.fetch_add(1, Ordering::Relaxed);
+        self.dropped_samples.fetch_add(dropped_samples, Ordering::Relaxed);
+    }
+
+    // Overruns and dropped samples since recording started
+    pub(super) fn overruns(&self) -> (u64, u64) {
+        (
+            self.overruns.load(Ordering::Relaxed),
+            self.dropped_samples.load(Ordering::Relaxed),
+        )
+    }
+
+    // Levels since the previous call
+    fn take(&self) -> (f32, f32, u64) {
+        let peak = f32::from_bits(self.peak.swap(0, Ordering::Relaxed));
+        let sum_squares = f64::from_bits(self.sum_squares.swap(0, Ordering::Relaxed));
+        let samples = self.samples.swap(0, Ordering::Relaxed);
+        let clipped = self.clipped.swap(0, Ordering::Relaxed);
+        let rms = if samples > 0 { (sum_squares / samples as f64).sqrt() as f32 } else { 0.0 };
+        (rms, peak, clipped)
+    }
+}
+
+// Levels of one callback buffer, summed up before touching the shared meter
+#[derive(Default)]
+pub(super) struct BlockLevel {
+    peak: f32,
+    sum_squares: f64,
+    samples: u64,
+    clipped: u64,
+}
+
+impl BlockLevel {
+    pub(super) fn add(&mut self, sample: f32) {
+        let level = sample.abs();
+        self.peak = self.peak.max(level);
+        self.sum_squares += (sample * sample) as f64;
+        self.samples += 1;
+        if level >= CLIP_LEVEL {
+            self.clipped += 1;
+        }
+    }
+}
+
+// Turns periodic meter readings into reports, tracking how long input has been quiet
+pub(super) struct LevelReporter {
+    quiet_since: Option<Instant>,
+}
+
+impl LevelReporter {
+    pub(super) fn new() -> Self {
+        Self { quiet_since: None }
+    }
+
+    pub(super) fn read(&mut self, meter: &LevelMeter) -> AudioLevel {
+        self.read_at(meter, Instant::now())
+    }
+
+    fn read_at(&mut self, meter: &LevelMeter, now: Instant) -> AudioLevel {
+        let (rms, peak, clipped) = meter.take();
+        let (overruns, dropped_samples) = meter.overruns();
+        if peak >= NEAR_SILENCE {
+            self.quiet_since = None;
+        } else if self.quiet_since.is_none() {
+            self.quiet_since = Some(now);
+        }
+
+        AudioLevel {
+            rms,
+            peak,
+            clipping: clipped > 0,
+            silent: self.quiet_since.is_some_and(|since| now - since >= MUTED_AFTER),
+            overruns,
+            dropped_samples,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn meter_with(samples: &[f32]) -> LevelMeter {
+        let meter = LevelMeter::default();
+        let mut block = BlockLevel::default();
+        for &sample in samples {
+            block.add(sample);
+        }
+        meter.add_block(&block);
+        meter
+    }
+
+    #[test]
+    fn levels() {
+        let cases = [
+            (vec![0.5, -0.5, 0.5, -0.5], (0.5, 0.5, false)),
+            (vec![0.0, 0.0, 0.0, 0.6], (0.3, 0.6, false)),
+            (vec![0.98, -0.98], (0.98, 0.98, false)),
+            // Clipping starts at 0.99 of full scale, either polarity
+            (vec![0.99, 0.99], (0.99, 0.99, true)),
+            (vec![0.0, -1.0], (0.707, 1.0, true)),
+            (vec![], (0.0, 0.0, false)),
+        ];
+
+        for (samples, expected) in cases {
+            let level = LevelReporter::new().read(&meter_with(&samples));
+            let rms = (level.rms * 1000.0).round() / 1000.0;
+            assert_eq!((rms, level.peak, level.clipping), expected, "input: {:?}", samples);
+        }
+    }
+
+    #[test]
+    fn reading_resets_levels() {
+        let meter = meter_with(&[1.0, -1.0]);
+        let mut reporter = LevelReporter::new();
+        assert!(reporter.read(&meter).clipping);
+
+        let level = reporter.read(&meter);
+        assert_eq!((level.rms, level.peak, level.clipping), (0.0, 0.0, false));
+    }
+
+    #[test]
+    fn silent_after_a_while() {
+        let start = Instant::now();
+        let at = |millis| start + Duration::from_millis(millis);
+        let cases = [
+            (0.0005, at(0), false),
+            (0.0005, at(2_900), false),
+            (0.0005, at(3_000), true),
+            (0.0005, at(5_000), true),
+            // Any sound restarts the wait
+            (0.2, at(5_100), false),
+            (0.0, at(5_200), false),
+            (0.0, at(8_100), false),
+            (0.0, at(8_200), true),
+        ];
+
+        let mut reporter = LevelReporter::new();
+        for (peak, now, expected) in cases {
+            let level = reporter.read_at(&meter_with(&[peak]), now);
+            assert_eq!(level.silent, expected, "input: {:?}", (peak, now - start));
+        }
+    }
+
+    #[test]
+    fn overruns_accumulate() {
+        let meter = LevelMeter::default();
+        let mut reporter = LevelReporter::new();
+        let cases = [(Some(480), (1, 480)), (None, (1, 480)), (Some(960), (2, 1440)), (Some(480), (3, 1920))];
+
+        for (dropped, expected) in cases {
+            if let Some(dropped) = dropped {
+                meter.add_overrun(dropped);
+            }
+            let level = reporter.read(&meter);
+            assert_eq!((level.overruns, level.dropped_samples), expected, "input: {:?}", dropped);
+        }
+    }
+}
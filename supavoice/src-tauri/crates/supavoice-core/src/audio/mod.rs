@@ -0,0 +1,7 @@
+pub mod level;
+pub mod recorder;
+pub mod wav;
+
+pub use level::AudioLevel;
+pub use recorder::AudioRecorder;
+pub use wav::{load_wav_at, load_wav_mono, read_wav_slice};
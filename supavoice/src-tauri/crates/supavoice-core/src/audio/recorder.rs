@@ -0,0 +1,249 @@
+use super::level::{AudioLevel, BlockLevel, LevelMeter, LevelReporter};
+use anyhow::{Context, Result};
+use cpal::traits::{DeviceTrait, HostTrait, StreamTrait};
+use cpal::{FromSample, Sample};
+use crossbeam_queue::ArrayQueue;
+use hound::{WavSpec, WavWriter};
+use std::fs::File;
+use std::io::BufWriter;
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::thread::JoinHandle;
+use std::time::{Duration, Instant};
+
+// Peak level above which a block counts as speech for `record_until_silence`
+const SPEECH_THRESHOLD: f32 = 0.02;
+// Audio the capture queue holds while the writer thread is busy, e.g. on a slow disk
+const RING_SECONDS: usize = 5;
+// How often the writer thread drains the capture queue
+const WRITER_POLL: Duration = Duration::from_millis(10);
+
+pub struct AudioRecorder {
+    sample_rate: u32,
+    // Called about ten times a second while recording
+    on_level: Option<Arc<dyn Fn(AudioLevel) + Send + Sync>>,
+}
+
+impl AudioRecorder {
+    pub fn new() -> Self {
+        Self {
+            sample_rate: 16000, // Whisper requires 16kHz
+            on_level: None,
+        }
+    }
+
+    pub fn with_level_callback(mut self, on_level: impl Fn(AudioLevel) + Send + Sync + 'static) -> Self {
+        self.on_level = Some(Arc::new(on_level));
+        self
+    }
+
+    pub fn record_to_file(&self, output_path: PathBuf, duration_secs: u64) -> Result<()> {
+        let stop_flag = Arc::new(AtomicBool::new(false));
+        self.record_to_file_cancellable(output_path, Some(duration_secs), stop_flag)
+    }
+
+    pub fn record_to_file_cancellable(&self, output_path: PathBuf, max_duration_secs: Option<u64>, stop_flag: Arc<AtomicBool>) -> Result<()> {
+        self.record(output_path, max_duration_secs, stop_flag, None)
+    }
+
+    // Records until speech is followed by `silence` of quiet, or the max duration is reached
+    pub fn record_until_silence(&self, output_path: PathBuf, silence: Duration, max_duration_secs: Option<u64>) -> Result<()> {
+        let stop_flag = Arc::new(AtomicBool::new(false));
+        self.record(output_path, max_duration_secs, stop_flag, Some(silence))
+    }
+
+    fn record(
+        &self,
+        output_path: PathBuf,
+        max_duration_secs: Option<u64>,
+        stop_flag: Arc<AtomicBool>,
+        until_silence: Option<Duration>,
+    ) -> Result<()> {
+        let host = cpal::default_host();
+        let device = host
+            .default_input_device()
+            .ok_or_else(|| anyhow::anyhow!("No input device available"))?;
+
+        let config = device.default_input_config()?;
+        let device_sample_rate = config.sample_rate().0;
+
+        let spec = WavSpec {
+            channels: 1, // Mono
+            sample_rate: self.sample_rate,
+            bits_per_sample: 16,
+            sample_format: hound::SampleFormat::Int,
+        };
+
+        let writer = WavWriter::create(&output_path, spec)?;
+
+        // Mono samples at the device rate, from the callback to the writer thread
+        let capacity = device_sample_rate as usize * RING_SECONDS;
+        let ring = Arc::new(ArrayQueue::new(capacity));
+        let ring_clone = ring.clone();
+        let meter = Arc::new(LevelMeter::default());
+        let meter_clone = meter.clone();
+        let err_fn = move |err| {
+            eprintln!("Stream error: {}", err);
+        };
+
+        let stream = match config.sample_format() {
+            cpal::SampleFormat::I8 => self.build_input_stream::<i8>(&device, &config.into(), ring_clone, meter_clone, err_fn)?,
+            cpal::SampleFormat::I16 => self.build_input_stream::<i16>(&device, &config.into(), ring_clone, meter_clone, err_fn)?,
+            cpal::SampleFormat::I32 => self.build_input_stream::<i32>(&device, &config.into(), ring_clone, meter_clone, err_fn)?,
+            cpal::SampleFormat::F32 => self.build_input_stream::<f32>(&device, &config.into(), ring_clone, meter_clone, err_fn)?,
+            _ => return Err(anyhow::anyhow!("Unsupported sample format")),
+        };
+
+        let finished = Arc::new(AtomicBool::new(false));
+        let resample_ratio = device_sample_rate as f64 / self.sample_rate as f64;
+        let writer_thread = spawn_writer(writer, ring, resample_ratio, finished.clone());
+
+        if let Err(e) = stream.play() {
+            finished.store(true, Ordering::Release);
+            let _ = writer_thread.join();
+            return Err(e.into());
+        }
+
+        // Record until stop flag is set or max duration is reached
+        let start = Instant::now();
+        let mut last_speech: Option<Instant> = None;
+        let mut reporter = LevelReporter::new();
+        loop {
+            std::thread::sleep(Duration::from_millis(100));
+
+            if stop_flag.load(Ordering::Relaxed) {
+                break;
+            }
+            // The writer only exits early when writing failed (e.g. the disk filled up);
+            // its error is returned below instead of counting the rest as overruns
+            if writer_thread.is_finished() {
+                break;
+            }
+
+            // Read here rather than in the callback, which must not block on the UI
+            let level = reporter.read(&meter);
+            if let Some(on_level) = &self.on_level {
+                on_level(level);
+            }
+
+            if let Some(silence) = until_silence {
+                if level.peak > SPEECH_THRESHOLD {
+                    last_speech = Some(Instant::now());
+                }
+                // Only stop after something was said
+                if last_speech.is_some_and(|at| at.elapsed() >= silence) {
+                    break;
+                }
+            }
+
+            if let Some(max_duration) = max_duration_secs {
+                if start.elapsed().as_secs() >= max_duration {
+                    break;
+                }
+            }
+        }
+
+        // No more callbacks after this; the writer drains what's left and finalizes the WAV file
+        drop(stream);
+        finished.store(true, Ordering::Release);
+        let written = writer_thread
+            .join()
+            .map_err(|_| anyhow::anyhow!("WAV writer thread panicked"))?
+            .context("Failed to write the recording");
+
+        let (overruns, dropped_samples) = meter.overruns();
+        if overruns > 0 {
+            eprintln!(
+                "⚠️  Capture overran {} times, dropping {:.2}s of audio",
+                overruns,
+                dropped_samples as f64 / device_sample_rate as f64
+            );
+        }
+
+        written
+    }
+
+    // The callback only converts, meters and queues samples: no locks, allocation or I/O
+    fn build_input_stream<T>(
+        &self,
+        device: &cpal::Device,
+        config: &cpal::StreamConfig,
+        ring: Arc<ArrayQueue<f32>>,
+        meter: Arc<LevelMeter>,
+        err_fn: impl FnMut(cpal::StreamError) + Send + 'static,
+    ) -> Result<cpal::Stream>
+    where
+        T: Sample + FromSample<f32> + cpal::SizedSample,
+    {
+        let channels = config.channels as usize;
+
+        let stream = device.build_input_stream(
+            config,
+            move |data: &[T], _: &cpal::InputCallbackInfo| {
+                let mut block = BlockLevel::default();
+                let mut dropped = 0;
+
+                for frame in data.chunks(channels) {
+                    // Average channels to mono
+                    let mono_sample: f32 = frame.iter()
+                        .map(|s| s.to_float_sample().to_sample::<f32>())
+                        .sum::<f32>() / channels as f32;
+                    block.add(mono_sample);
+
+                    // Full means the writer thread has fallen RING_SECONDS behind
+                    if ring.push(mono_sample).is_err() {
+                        dropped += 1;
+                    }
+                }
+
+                meter.add_block(&block);
+                if dropped > 0 {
+                    meter.add_overrun(dropped);
+                }
+            },
+            err_fn,
+            None,
+        )?;
+
+        Ok(stream)
+    }
+}
+
+// Resamples queued audio to the WAV's rate and writes it, until `finished` is set and
+// the queue is drained
+fn spawn_writer(
+    mut writer: WavWriter<BufWriter<File>>,
+    ring: Arc<ArrayQueue<f32>>,
+    resample_ratio: f64,
+    finished: Arc<AtomicBool>,
+) -> JoinHandle<Result<()>> {
+    std::thread::spawn(move || {
+        let mut index = 0.0f64;
+        loop {
+            // Checked before draining, so samples queued before the stop are still written
+            let done = finished.load(Ordering::Acquire);
+
+            while let Some(mono_sample) = ring.pop() {
+                // Only write sample when we've accumulated enough input samples
+                if index >= 1.0 {
+                    index -= 1.0;
+
+                    // Convert to i16 and write
+                    let sample: i16 = (mono_sample.clamp(-1.0, 1.0) * i16::MAX as f32) as i16;
+                    writer.write_sample(sample)?;
+                }
+
+                index += 1.0 / resample_ratio;
+            }
+
+            if done {
+                break;
+            }
+            std::thread::sleep(WRITER_POLL);
+        }
+
+        writer.finalize()?;
+        Ok(())
+    })
+}
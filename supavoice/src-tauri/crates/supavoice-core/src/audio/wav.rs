@@ -0,0 +1,182 @@
+use anyhow::{Context, Result};
+
+// Reads a WAV file as mono f32 samples, returning them with the sample rate
+pub fn load_wav_mono(audio_path: &str) -> Result<(Vec<f32>, u32)> {
+    let mut reader = hound::WavReader::open(audio_path)
+        .context("Failed to open audio file")?;
+
+    let spec = reader.spec();
+
+    // Read samples and convert to f32
+    let samples: Vec<f32> = match spec.sample_format {
+        hound::SampleFormat::Float => {
+            reader.samples::<f32>()
+                .collect::<Result<Vec<f32>, _>>()
+                .context("Failed to read float samples")?
+        }
+        hound::SampleFormat::Int => {
+            let bits = spec.bits_per_sample;
+            reader.samples::<i32>()
+                .map(|s| {
+                    let sample = s.context("Failed to read sample")?;
+                    let max_val = 2_i32.pow(bits as u32 - 1) as f32;
+                    Ok(sample as f32 / max_val)
+                })
+                .collect::<Result<Vec<f32>, anyhow::Error>>()?
+        }
+    };
+
+    // Convert to mono if stereo
+    let mono_samples = if spec.channels == 2 {
+        samples
+            .chunks(2)
+            .map(|chunk| (chunk[0] + chunk[1]) / 2.0)
+            .collect()
+    } else {
+        samples
+    };
+
+    Ok((mono_samples, spec.sample_rate))
+}
+
+// Reads a WAV file as mono f32 samples at `sample_rate`, resampling recordings made at
+// another rate (e.g. a 48 kHz import for Whisper's 16 kHz)
+pub fn load_wav_at(audio_path: &str, sample_rate: u32) -> Result<Vec<f32>> {
+    let (samples, source_rate) = load_wav_mono(audio_path)?;
+    Ok(resample(&samples, source_rate, sample_rate))
+}
+
+// Linear interpolation is enough for speech; Whisper is not sensitive to the aliasing
+pub fn resample(samples: &[f32], from: u32, to: u32) -> Vec<f32> {
+    if from == to || samples.is_empty() {
+        return samples.to_vec();
+    }
+
+    let step = from as f64 / to as f64;
+    let count = (samples.len() as u64 * to as u64 / from as u64) as usize;
+    (0..count)
+        .map(|i| {
+            let position = i as f64 * step;
+            let index = position as usize;
+            let fraction = (position - index as f64) as f32;
+            let next = samples.get(index + 1).copied().unwrap_or(samples[index]);
+            samples[index] + (next - samples[index]) * fraction
+        })
+        .collect()
+}
+
+// Cuts `start..end` (seconds) out of a recording as a standalone WAV file in memory,
+// in the recording's own format, e.g. to play back a single word
+pub fn read_wav_slice(audio_path: &str, start: f64, end: f64) -> Result<Vec<u8>> {
+    let mut reader = hound::WavReader::open(audio_path)
+        .context("Failed to open audio file")?;
+
+    let spec = reader.spec();
+    let frames = reader.duration();
+    let first = ((start.max(0.0) * spec.sample_rate as f64) as u32).min(frames);
+    let last = ((end * spec.sample_rate as f64) as u32).clamp(first, frames);
+    if first == last {
+        anyhow::bail!("Range {:.2}s-{:.2}s is outside the recording", start, end);
+    }
+    reader.seek(first).context("Failed to seek in audio file")?;
+    let count = ((last - first) * spec.channels as u32) as usize;
+
+    let mut slice = std::io::Cursor::new(Vec::new());
+    let mut writer = hound::WavWriter::new(&mut slice, spec)
+        .context("Failed to create WAV writer")?;
+    match spec.sample_format {
+        hound::SampleFormat::Float => {
+            for sample in reader.samples::<f32>().take(count) {
+                writer.write_sample(sample.context("Failed to read sample")?)?;
+            }
+        }
+        hound::SampleFormat::Int => {
+            for sample in reader.samples::<i32>().take(count) {
+                writer.write_sample(sample.context("Failed to read sample")?)?;
+            }
+        }
+    }
+    writer.finalize().context("Failed to finalize WAV slice")?;
+
+    Ok(slice.into_inner())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::f32::consts::TAU;
+
+    const TONE_HZ: f32 = 440.0;
+
+    fn fixture(name: &str, channels: u16, sample_rate: u32, samples: impl IntoIterator<Item = i16>) -> String {
+        let path = std::env::temp_dir().join(format!("supavoice-wav-{}-{}.wav", std::process::id(), name));
+        let spec = hound::WavSpec {
+            channels,
+            sample_rate,
+            bits_per_sample: 16,
+            sample_format: hound::SampleFormat::Int,
+        };
+        let mut writer = hound::WavWriter::create(&path, spec).unwrap();
+        for sample in samples {
+            writer.write_sample(sample).unwrap();
+        }
+        writer.finalize().unwrap();
+        path.to_string_lossy().to_string()
+    }
+
+    // One second of a 440 Hz tone as 16-bit mono
+    fn tone_fixture(name: &str, sample_rate: u32) -> String {
+        let samples = (0..sample_rate).map(|i| {
+            let value = (TAU * TONE_HZ * i as f32 / sample_rate as f32).sin() * 0.5;
+            (value * i16::MAX as f32) as i16
+        });
+        fixture(name, 1, sample_rate, samples)
+    }
+
+    #[test]
+    fn loads_at_16k() {
+        let cases = [16000, 44100, 48000];
+
+        for source_rate in cases {
+            let path = tone_fixture(&format!("tone-{}", source_rate), source_rate);
+            let samples = load_wav_at(&path, 16000).unwrap();
+            std::fs::remove_file(&path).unwrap();
+
+            assert_eq!(samples.len(), 16000, "input: {:?}", source_rate);
+            let worst = samples
+                .iter()
+                .enumerate()
+                .map(|(i, sample)| (sample - (TAU * TONE_HZ * i as f32 / 16000.0).sin() * 0.5).abs())
+                .fold(0.0, f32::max);
+            assert!(worst < 0.01, "input: {:?}, off by {}", source_rate, worst);
+        }
+    }
+
+    #[test]
+    fn slices() {
+        // Two seconds of stereo at 1 kHz, each frame holding its own index and its negation
+        let path = fixture("slice", 2, 1000, (0..2000i16).flat_map(|frame| [frame, -frame]));
+        let cases = [
+            ((0.5, 0.75), Some((500, 750))),
+            ((0.0, 0.002), Some((0, 2))),
+            // Clamped to the end of the recording
+            ((1.5, 5.0), Some((1500, 2000))),
+            ((-1.0, 0.001), Some((0, 1))),
+            ((3.0, 4.0), None),
+            ((2.0, 2.5), None),
+            ((1.0, 0.5), None),
+        ];
+
+        for ((start, end), expected) in cases {
+            let slice = read_wav_slice(&path, start, end).ok().map(|bytes| {
+                let reader = hound::WavReader::new(std::io::Cursor::new(bytes)).unwrap();
+                assert_eq!(reader.spec().channels, 2, "input: {:?}", (start, end));
+                assert_eq!(reader.spec().sample_rate, 1000, "input: {:?}", (start, end));
+                reader.into_samples::<i16>().map(Result::unwrap).collect::<Vec<i16>>()
+            });
+            let expected = expected.map(|(first, last)| (first..last).flat_map(|frame| [frame, -frame]).collect());
+            assert_eq!(slice, expected, "input: {:?}", (start, end));
+        }
+        std::fs::remove_file(&path).unwrap();
+    }
+}
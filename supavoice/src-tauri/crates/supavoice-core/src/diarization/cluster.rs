@@ -0,0 +1,89 @@
+// Agglomerative clustering with average linkage over cosine distance. The closest
+// clusters merge while they're nearer than `threshold`, and past that until at most
+// `max_clusters` remain. Returns a cluster per embedding, numbered in order of
+// first appearance.
+pub fn cluster(embeddings: &[Vec<f32>], threshold: f32, max_clusters: Option<usize>) -> Vec<usize> {
+    let n = embeddings.len();
+    let mut distances = vec![vec![0.0; n]; n];
+    for i in 0..n {
+        for j in i + 1..n {
+            let distance = cosine_distance(&embeddings[i], &embeddings[j]);
+            distances[i][j] = distance;
+            distances[j][i] = distance;
+        }
+    }
+
+    // members[c] is empty once cluster c has been merged into another
+    let mut members: Vec<Vec<usize>> = (0..n).map(|i| vec![i]).collect();
+    let mut remaining = n;
+    let max_clusters = max_clusters.unwrap_or(n).max(1);
+
+    while remaining > 1 {
+        let mut closest: Option<(usize, usize, f32)> = None;
+        for i in 0..n {
+            if members[i].is_empty() {
+                continue;
+            }
+            for j in i + 1..n {
+                if members[j].is_empty() {
+                    continue;
+                }
+                if closest.is_none_or(|(_, _, best)| distances[i][j] < best) {
+                    closest = Some((i, j, distances[i][j]));
+                }
+            }
+        }
+
+        let Some((i, j, distance)) = closest else {
+            break;
+        };
+        if distance >= threshold && remaining <= max_clusters {
+            break;
+        }
+
+        // Average linkage: the merged cluster's distance to each other cluster is the
+        // size-weighted mean of the two it replaces
+        let (size_i, size_j) = (members[i].len() as f32, members[j].len() as f32);
+        for k in 0..n {
+            if k == i || k == j || members[k].is_empty() {
+                continue;
+            }
+            let merged = (distances[i][k] * size_i + distances[j][k] * size_j) / (size_i + size_j);
+            distances[i][k] = merged;
+            distances[k][i] = merged;
+        }
+        let moved = std::mem::take(&mut members[j]);
+        members[i].extend(moved);
+        remaining -= 1;
+    }
+
+    let mut cluster_of = vec![0; n];
+    for (cluster, indices) in members.iter().enumerate() {
+        for &index in indices {
+            cluster_of[index] = cluster;
+        }
+    }
+
+    // Renumber so the first voice heard is 0, the next new one 1, ...
+    let mut order: Vec<usize> = Vec::new();
+    cluster_of
+        .iter()
+        .map(|cluster| match order.iter().position(|seen| seen == cluster) {
+            Some(position) => position,
+            None => {
+                order.push(*cluster);
+                order.len() - 1
+            }
+        })
+        .collect()
+}
+
+fn cosine_distance(a: &[f32], b: &[f32]) -> f32 {
+    let dot: f32 = a.iter().zip(b).map(|(x, y)| x * y).sum();
+    let norm_a = a.iter().map(|x| x * x).sum::<f32>().sqrt();
+    let norm_b = b.iter().map(|x| x * x).sum::<f32>().sqrt();
+    if norm_a == 0.0 || norm_b == 0.0 {
+        return 1.0;
+    }
+    1.0 - dot / (norm_a * norm_b)
+}
@@ -0,0 +1,167 @@
+use rustfft::num_complex::Complex;
+use rustfft::{Fft, FftPlanner};
+use std::f32::consts::PI;
+use std::sync::Arc;
+
+const FRAME_SECS: f32 = 0.025;
+const HOP_SECS: f32 = 0.010;
+const MEL_BANDS: usize = 26;
+const MEL_LOW_HZ: f32 = 80.0;
+const MEL_HIGH_HZ: f32 = 7600.0;
+// c1..c12; c0 is loudness, which says nothing about who is talking
+const CEPSTRA: usize = 12;
+// Frames this far below the loudest one are pauses between words
+const VOICED_RANGE_DB: f32 = 30.0;
+// About a fifth of a second of speech
+const MIN_VOICED_FRAMES: usize = 20;
+
+// Turns a stretch of speech into the MFCCs of its voiced frames, which `pool`
+// reduces to a fixed-length voice fingerprint. Built once per recording.
+pub struct VoiceEmbedder {
+    frame_len: usize,
+    hop: usize,
+    fft: Arc<dyn Fft<f32>>,
+    window: Vec<f32>,
+    // One weight vector over the power spectrum per mel band
+    filterbank: Vec<Vec<f32>>,
+}
+
+impl VoiceEmbedder {
+    pub fn new(sample_rate: u32) -> Self {
+        let frame_len = (FRAME_SECS * sample_rate as f32) as usize;
+        let hop = (HOP_SECS * sample_rate as f32) as usize;
+        let fft_len = frame_len.next_power_of_two();
+
+        let window = (0..frame_len)
+            .map(|i| 0.54 - 0.46 * (2.0 * PI * i as f32 / (frame_len - 1) as f32).cos())
+            .collect();
+
+        Self {
+            frame_len,
+            hop,
+            fft: FftPlanner::new().plan_fft_forward(fft_len),
+            window,
+            filterbank: mel_filterbank(sample_rate, fft_len),
+        }
+    }
+
+    // None when the audio holds too little speech to say anything about the voice
+    pub fn voiced_frames(&self, samples: &[f32]) -> Option<Vec<Vec<f32>>> {
+        if samples.len() < self.frame_len {
+            return None;
+        }
+
+        let mut frames = Vec::new();
+        let mut buffer = vec![Complex::new(0.0, 0.0); self.fft.len()];
+        for start in (0..=samples.len() - self.frame_len).step_by(self.hop) {
+            for (i, value) in buffer.iter_mut().enumerate() {
+                let sample = if i < self.frame_len {
+                    samples[start + i] * self.window[i]
+                } else {
+                    0.0
+                };
+                *value = Complex::new(sample, 0.0);
+            }
+            self.fft.process(&mut buffer);
+
+            let power: Vec<f32> = buffer[..self.fft.len() / 2 + 1].iter().map(|bin| bin.norm_sqr()).collect();
+            let energy: f32 = power.iter().sum();
+            frames.push((energy, self.cepstrum(&power)));
+        }
+
+        let loudest = frames.iter().map(|(energy, _)| *energy).fold(0.0, f32::max);
+        let floor = loudest * 10f32.powf(-VOICED_RANGE_DB / 10.0);
+        let voiced: Vec<Vec<f32>> = frames
+            .into_iter()
+            .filter(|(energy, _)| *energy > 0.0 && *energy >= floor)
+            .map(|(_, cepstrum)| cepstrum)
+            .collect();
+        if voiced.len() < MIN_VOICED_FRAMES {
+            return None;
+        }
+        Some(voiced)
+    }
+
+    // Log mel energies decorrelated with a DCT-II
+    fn cepstrum(&self, power: &[f32]) -> Vec<f32> {
+        let log_mel: Vec<f32> = self
+            .filterbank
+            .iter()
+            .map(|weights| {
+                let energy: f32 = weights.iter().zip(power).map(|(weight, power)| weight * power).sum();
+                energy.max(1e-10).ln()
+            })
+            .collect();
+
+        (1..=CEPSTRA)
+            .map(|k| {
+                log_mel
+                    .iter()
+                    .enumerate()
+                    .map(|(m, value)| value * (PI * k as f32 * (m as f32 + 0.5) / MEL_BANDS as f32).cos())
+                    .sum()
+            })
+            .collect()
+    }
+}
+
+// The mean and spread of each coefficient over a stretch's frames
+pub fn pool(frames: &[Vec<f32>]) -> Vec<f32> {
+    let count = frames.len() as f32;
+    let mut mean = vec![0.0; CEPSTRA];
+    for cepstrum in frames {
+        for (sum, value) in mean.iter_mut().zip(cepstrum) {
+            *sum += value / count;
+        }
+    }
+    let mut spread = vec![0.0; CEPSTRA];
+    for cepstrum in frames {
+        for ((sum, value), mean) in spread.iter_mut().zip(cepstrum).zip(&mean) {
+            *sum += (value - mean).powi(2) / count;
+        }
+    }
+
+    mean.extend(spread.into_iter().map(f32::sqrt));
+    mean
+}
+
+// Triangular filters spaced evenly on the mel scale
+fn mel_filterbank(sample_rate: u32, fft_len: usize) -> Vec<Vec<f32>> {
+    let bins = fft_len / 2 + 1;
+    let high = MEL_HIGH_HZ.min(sample_rate as f32 / 2.0);
+    let (low_mel, high_mel) = (hz_to_mel(MEL_LOW_HZ), hz_to_mel(high));
+
+    // Band edges as fractional FFT bins
+    let edges: Vec<f32> = (0..MEL_BANDS + 2)
+        .map(|i| {
+            let mel = low_mel + (high_mel - low_mel) * i as f32 / (MEL_BANDS + 1) as f32;
+            mel_to_hz(mel) * fft_len as f32 / sample_rate as f32
+        })
+        .collect();
+
+    (0..MEL_BANDS)
+        .map(|band| {
+            let (left, center, right) = (edges[band], edges[band + 1], edges[band + 2]);
+            (0..bins)
+                .map(|bin| {
+                    let bin = bin as f32;
+                    if bin <= left || bin >= right {
+                        0.0
+                    } else if bin <= center {
+                        (bin - left) / (center - left)
+                    } else {
+                        (right - bin) / (right - center)
+                    }
+                })
+                .collect()
+        })
+        .collect()
+}
+
+fn hz_to_mel(hz: f32) -> f32 {
+    2595.0 * (1.0 + hz / 700.0).log10()
+}
+
+fn mel_to_hz(mel: f32) -> f32 {
+    700.0 * (10f32.powf(mel / 2595.0) - 1.0)
+}
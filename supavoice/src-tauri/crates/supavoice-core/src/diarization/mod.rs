@@ -0,0 +1,7 @@
+pub mod cluster;
+pub mod embedding;
+pub mod speakers;
+
+pub use cluster::cluster;
+pub use embedding::VoiceEmbedder;
+pub use speakers::{diarize, speaker_label, speaker_labels, speaker_turns, SpeakerTurn};
@@ -0,0 +1,314 @@
+use super::cluster::cluster;
+use super::embedding::{pool, VoiceEmbedder};
+use crate::preferences::DiarizationPreferences;
+use crate::transcription::Segment;
+use std::ops::Range;
+
+// Shorter segments hold too little speech for a reliable fingerprint and take the
+// speaker of the nearest segment instead
+const MIN_EMBED_SECS: f64 = 1.0;
+
+// Labels each segment with a speaker index by clustering voice fingerprints.
+// Segments are left unlabelled when there isn't enough speech to compare.
+pub fn diarize(samples: &[f32], sample_rate: u32, segments: &mut [Segment], prefs: &DiarizationPreferences) {
+    let embedder = VoiceEmbedder::new(sample_rate);
+    let rate = sample_rate as f64;
+
+    let mut indices = Vec::new();
+    let mut segment_frames = Vec::new();
+    for (index, segment) in segments.iter().enumerate() {
+        if segment.end - segment.start < MIN_EMBED_SECS {
+            continue;
+        }
+        let start = ((segment.start * rate) as usize).min(samples.len());
+        let end = ((segment.end * rate) as usize).min(samples.len());
+        if let Some(frames) = embedder.voiced_frames(&samples[start..end]) {
+            indices.push(index);
+            segment_frames.push(frames);
+        }
+    }
+    if segment_frames.is_empty() {
+        return;
+    }
+
+    normalize_frames(&mut segment_frames);
+    let embeddings: Vec<Vec<f32>> = segment_frames.iter().map(|frames| pool(frames)).collect();
+    let speakers = cluster(&embeddings, prefs.threshold, prefs.max_speakers);
+    for (&index, speaker) in indices.iter().zip(speakers) {
+        segments[index].speaker = Some(speaker);
+    }
+
+    // Short segments are usually interjections within or between turns
+    for index in 0..segments.len() {
+        if segments[index].speaker.is_some() {
+            continue;
+        }
+        let nearest = indices
+            .iter()
+            .min_by(|a, b| {
+                let distance = |other: usize| (segments[other].start - segments[index].start).abs();
+                distance(**a).total_cmp(&distance(**b))
+            })
+            .copied();
+        segments[index].speaker = nearest.and_then(|nearest| segments[nearest].speaker);
+    }
+}
+
+// Cepstral mean and variance normalization over every voiced frame in the
+// recording, so the room and microphone cancel out and no coefficient dominates
+// the distance. Done per frame rather than per fingerprint, which would stretch
+// the small differences within a single voice into apparent speakers.
+fn normalize_frames(segment_frames: &mut [Vec<Vec<f32>>]) {
+    let frames = || segment_frames.iter().flatten();
+    let count = frames().count() as f32;
+    let dims = segment_frames[0][0].len();
+    let mut mean = vec![0.0; dims];
+    for frame in frames() {
+        for (sum, value) in mean.iter_mut().zip(frame) {
+            *sum += value / count;
+        }
+    }
+    let mut deviation = vec![0.0f32; dims];
+    for frame in frames() {
+        for ((sum, value), mean) in deviation.iter_mut().zip(frame).zip(&mean) {
+            *sum += (value - mean).powi(2) / count;
+        }
+    }
+    let deviation: Vec<f32> = deviation
+        .into_iter()
+        .map(|variance| variance.sqrt().max(1e-6))
+        .collect();
+
+    for frame in segment_frames.iter_mut().flatten() {
+        for ((value, mean), deviation) in frame.iter_mut().zip(&mean).zip(&deviation) {
+            *value = (*value - mean) / deviation;
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct SpeakerTurn {
+    pub speaker: usize,
+    pub segments: Range<usize>,
+}
+
+// Runs of consecutive segments by the same speaker. Empty unless at least two
+// speakers were found, so single-voice recordings read as before.
+pub fn speaker_turns(segments: &[Segment]) -> Vec<SpeakerTurn> {
+    let mut turns: Vec<SpeakerTurn> = Vec::new();
+    for (index, segment) in segments.iter().enumerate() {
+        match (turns.last_mut(), segment.speaker) {
+            (Some(turn), Some(speaker)) if turn.speaker != speaker => turns.push(SpeakerTurn {
+                speaker,
+                segments: index..index + 1,
+            }),
+            // Unlabelled segments continue the current turn
+            (Some(turn), _) => turn.segments.end = index + 1,
+            (None, Some(speaker)) => turns.push(SpeakerTurn {
+                speaker,
+                segments: index..index + 1,
+            }),
+            (None, None) => {}
+        }
+    }
+
+    let first = turns.first().map(|turn| turn.speaker);
+    if turns.iter().all(|turn| Some(turn.speaker) == first) {
+        return Vec::new();
+    }
+    turns
+}
+
+// `names` renames speakers by index; the rest stay "Speaker N"
+pub fn speaker_label(speaker: usize, names: &[String]) -> String {
+    names
+        .get(speaker)
+        .map(|name| name.trim())
+        .filter(|name| !name.is_empty())
+        .map(str::to_string)
+        .unwrap_or_else(|| format!("Speaker {}", speaker + 1))
+}
+
+// Labels of everyone who speaks, by index; empty for single-voice recordings
+pub fn speaker_labels(segments: &[Segment], names: &[String]) -> Vec<String> {
+    let turns = speaker_turns(segments);
+    let count = turns.iter().map(|turn| turn.speaker + 1).max().unwrap_or(0);
+    (0..count).map(|speaker| speaker_label(speaker, names)).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::f32::consts::TAU;
+
+    const SAMPLE_RATE: u32 = 16000;
+    // Formants (Hz) of the vowels a synthetic voice cycles through
+    const VOWELS: [[f32; 3]; 3] = [[730.0, 1090.0, 2440.0], [270.0, 2290.0, 3010.0], [300.0, 870.0, 2240.0]];
+
+    fn segment(start: f64, end: f64, speaker: Option<usize>) -> Segment {
+        Segment {
+            start,
+            end,
+            text: String::new(),
+            speaker,
+            confidence: None,
+            no_speech_prob: None,
+            words: Vec::new(),
+        }
+    }
+
+    fn speakers(segments: &[Segment]) -> Vec<Option<usize>> {
+        segments.iter().map(|segment| segment.speaker).collect()
+    }
+
+    // A voice as harmonics of `pitch` shaped by vowel formants scaled by `tract`,
+    // changing vowel every 200 ms, with a little noise
+    fn voice(pitch: f32, tract: f32, secs: f32, seed: u32) -> Vec<f32> {
+        let mut noise = seed.wrapping_mul(2_654_435_761).max(1);
+        let mut phase = 0.0f32;
+        (0..(secs * SAMPLE_RATE as f32) as usize)
+            .map(|i| {
+                let t = i as f32 / SAMPLE_RATE as f32;
+                let vowel = VOWELS[(t / 0.2) as usize % VOWELS.len()];
+                let f0 = pitch * (1.0 + 0.03 * (TAU * 4.0 * t).sin());
+                phase = (phase + f0 / SAMPLE_RATE as f32) % 1.0;
+
+                let mut value = 0.0;
+                let mut harmonic = 1;
+                while harmonic as f32 * f0 < 7000.0 {
+                    let frequency = harmonic as f32 * f0;
+                    let gain: f32 = vowel
+                        .iter()
+                        .map(|formant| 1.0 / (1.0 + ((frequency - formant * tract) / 80.0).powi(2)))
+                        .sum();
+                    value += gain * (TAU * phase * harmonic as f32).sin();
+                    harmonic += 1;
+                }
+
+                noise ^= noise << 13;
+                noise ^= noise >> 17;
+                noise ^= noise << 5;
+                value * 0.05 + (noise as f32 / u32::MAX as f32 - 0.5) * 0.002
+            })
+            .collect()
+    }
+
+    #[test]
+    fn clusters() {
+        let cases = [
+            // Well separated
+            (
+                vec![vec![1.0, 0.0], vec![0.9, 0.1], vec![0.0, 1.0], vec![0.1, 0.9]],
+                None,
+                vec![0, 0, 1, 1],
+            ),
+            (
+                vec![vec![0.0, 1.0], vec![1.0, 0.0], vec![0.1, 0.9]],
+                None,
+                vec![0, 1, 0],
+            ),
+            // Identical
+            (vec![vec![0.3, 0.7]; 3], None, vec![0, 0, 0]),
+            // Merged past the threshold down to `max_clusters`
+            (
+                vec![vec![1.0, 0.0], vec![-0.6, -0.8], vec![0.0, 1.0]],
+                Some(2),
+                vec![0, 1, 0],
+            ),
+            (
+                vec![vec![1.0, 0.0], vec![-0.6, -0.8], vec![0.0, 1.0]],
+                Some(1),
+                vec![0, 0, 0],
+            ),
+            (
+                vec![vec![1.0, 0.0], vec![-0.6, -0.8], vec![0.0, 1.0]],
+                None,
+                vec![0, 1, 2],
+            ),
+        ];
+
+        for (embeddings, max_clusters, expected) in cases {
+            let clusters = cluster(&embeddings, 0.5, max_clusters);
+            assert_eq!(clusters, expected, "input: {:?}", (embeddings, max_clusters));
+        }
+    }
+
+    #[test]
+    fn turns() {
+        let cases = [
+            (vec![], vec![]),
+            // A single voice reads as no turns at all
+            (vec![Some(0), None, Some(0)], vec![]),
+            (vec![None, None], vec![]),
+            (
+                vec![Some(0), Some(0), Some(1), Some(0)],
+                vec![(0, 0..2), (1, 2..3), (0, 3..4)],
+            ),
+            // Unlabelled segments join the current turn
+            (vec![None, Some(1), None, Some(0), None], vec![(1, 1..3), (0, 3..5)]),
+        ];
+
+        for (labels, expected) in cases {
+            let segments: Vec<Segment> = labels
+                .iter()
+                .enumerate()
+                .map(|(i, speaker)| segment(i as f64, i as f64 + 1.0, *speaker))
+                .collect();
+            let expected: Vec<SpeakerTurn> = expected
+                .into_iter()
+                .map(|(speaker, segments)| SpeakerTurn { speaker, segments })
+                .collect();
+            assert_eq!(speaker_turns(&segments), expected, "input: {:?}", labels);
+        }
+    }
+
+    #[test]
+    fn labels() {
+        let names = vec!["Ada".to_string(), "  ".to_string()];
+        let cases = [(0, "Ada"), (1, "Speaker 2"), (2, "Speaker 3")];
+
+        for (speaker, expected) in cases {
+            assert_eq!(speaker_label(speaker, &names), expected, "input: {:?}", speaker);
+        }
+        assert_eq!(speaker_label(0, &[]), "Speaker 1");
+    }
+
+    #[test]
+    fn one_voice_is_one_speaker() {
+        // The same voice at slightly different pitches and loudness, with a short
+        // interjection that takes its neighbour's label
+        let mut samples = Vec::new();
+        let mut segments = Vec::new();
+        for (i, (pitch, loudness)) in [(118.0, 1.0), (124.0, 0.6), (115.0, 1.4), (121.0, 0.8), (119.0, 1.1)]
+            .into_iter()
+            .enumerate()
+        {
+            let start = samples.len() as f64 / SAMPLE_RATE as f64;
+            samples.extend(voice(pitch, 1.0, 2.0, i as u32).iter().map(|sample| sample * loudness));
+            segments.push(segment(start, start + 2.0, None));
+        }
+        segments.push(segment(10.0, 10.5, None));
+        samples.extend(voice(120.0, 1.0, 0.5, 9));
+
+        diarize(&samples, SAMPLE_RATE, &mut segments, &DiarizationPreferences::default());
+        assert_eq!(speakers(&segments), vec![Some(0); 6]);
+        assert!(speaker_turns(&segments).is_empty());
+    }
+
+    #[test]
+    fn two_voices() {
+        let mut samples = Vec::new();
+        let mut segments = Vec::new();
+        for (i, (pitch, tract)) in [(120.0, 1.0), (220.0, 1.2), (118.0, 1.0), (225.0, 1.2)]
+            .into_iter()
+            .enumerate()
+        {
+            let start = samples.len() as f64 / SAMPLE_RATE as f64;
+            samples.extend(voice(pitch, tract, 2.0, i as u32));
+            segments.push(segment(start, start + 2.0, None));
+        }
+
+        diarize(&samples, SAMPLE_RATE, &mut segments, &DiarizationPreferences::default());
+        assert_eq!(speakers(&segments), vec![Some(0), Some(1), Some(0), Some(1)]);
+    }
+}
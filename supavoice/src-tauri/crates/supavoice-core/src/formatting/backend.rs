@@ -0,0 +1,46 @@
+use super::templates::TemplateLimits;
+use anyhow::Result;
+use async_trait::async_trait;
+
+pub struct ChatPrompt {
+    pub system: String,
+    pub user: String,
+    pub limits: TemplateLimits,
+    // Constrains output to JSON matching this schema, where the backend supports it
+    pub json_schema: Option<serde_json::Value>,
+}
+
+impl ChatPrompt {
+    // Raw prompt for llama-server's /completion endpoint
+    pub fn to_chatml(&self) -> String {
+        format!(
+            "<|im_start|>system\n{}<|im_end|>\n\
+            <|im_start|>user\n{}<|im_end|>\n\
+            <|im_start|>assistant\n",
+            self.system, self.user
+        )
+    }
+}
+
+// Rough token estimate (~4 characters per token for English) for backends
+// that can't tokenize
+pub fn estimate_tokens(text: &str) -> usize {
+    text.chars().count().div_ceil(4)
+}
+
+#[async_trait]
+pub trait FormattingBackend: Send + Sync {
+    fn name(&self) -> &str;
+
+    async fn complete(&self, prompt: &ChatPrompt) -> Result<String>;
+
+    // Context window of a model the app doesn't size itself; None means the
+    // template's limit, which is sized for the local model, applies
+    fn context_size(&self) -> Option<u32> {
+        None
+    }
+
+    async fn count_tokens(&self, text: &str) -> Result<usize> {
+        Ok(estimate_tokens(text))
+    }
+}
@@ -0,0 +1,263 @@
+use super::backend::{ChatPrompt, FormattingBackend};
+use super::templates::FormatTemplate;
+use anyhow::Result;
+
+// Slack for chat markup differences and the chars-per-token estimate used when splitting
+const SAFETY_MARGIN_TOKENS: usize = 128;
+// Each round condenses the previous one; more than a few means the output isn't shrinking
+const MAX_CONDENSE_ROUNDS: usize = 4;
+
+// Formats a transcript with a template. Transcripts that don't fit the template's
+// context are split into parts, each part is condensed (map), and the template is
+// applied to the condensed parts (reduce).
+pub async fn format_with_template(
+    backend: &dyn FormattingBackend,
+    template: &FormatTemplate,
+    transcript: &str,
+) -> Result<String> {
+    let template = &match backend.context_size() {
+        Some(context_size) => template.clone().with_context_size(context_size),
+        None => template.clone(),
+    };
+    let prompt = template.prompt(transcript);
+    if fits_context(backend, &prompt).await? {
+        return backend.complete(&prompt).await;
+    }
+
+    let context_size = template.limits.context_size as usize;
+    let overhead = backend
+        .count_tokens(&template.chunk_prompt("", 1, 1, 0).to_chatml())
+        .await?
+        + SAFETY_MARGIN_TOKENS;
+    let available = context_size.saturating_sub(overhead);
+
+    // Split what's left of the context between a part and its condensed output
+    let chunk_output_tokens = (available / 3).min(template.limits.max_output_tokens as usize);
+    let chunk_input_tokens = available - chunk_output_tokens;
+    if chunk_input_tokens < 256 {
+        return Err(anyhow::anyhow!(
+            "Context size {} is too small for template '{}'",
+            context_size,
+            template.id
+        ));
+    }
+
+    eprintln!(
+        "📏 Transcript exceeds the {} token context, formatting in parts with {}",
+        context_size,
+        backend.name()
+    );
+
+    let mut text = transcript.to_string();
+    let mut parts = 0;
+
+    for _ in 0..MAX_CONDENSE_ROUNDS {
+        let text_tokens = backend.count_tokens(&text).await?;
+        let chunks = split_into_chunks(&text, text_tokens, chunk_input_tokens);
+        if parts == 0 {
+            parts = chunks.len();
+        }
+
+        let mut condensed = Vec::with_capacity(chunks.len());
+        for (i, chunk) in chunks.iter().enumerate() {
+            eprintln!("🧩 Condensing part {}/{}", i + 1, chunks.len());
+            let prompt = template.chunk_prompt(chunk, i + 1, chunks.len(), chunk_output_tokens as u32);
+            condensed.push(backend.complete(&prompt).await?);
+        }
+        let condensed = condensed.join("\n\n");
+
+        if backend.count_tokens(&condensed).await? >= text_tokens {
+            return Err(anyhow::anyhow!("Transcript could not be condensed to fit the model context"));
+        }
+        text = condensed;
+
+        let merge_prompt = template.merge_prompt(&text, parts);
+        if fits_context(backend, &merge_prompt).await? {
+            eprintln!("🧩 Merging {} condensed parts", parts);
+            return backend.complete(&merge_prompt).await;
+        }
+    }
+
+    Err(anyhow::anyhow!(
+        "Transcript is too long for template '{}' even after condensing",
+        template.id
+    ))
+}
+
+async fn fits_context(backend: &dyn FormattingBackend, prompt: &ChatPrompt) -> Result<bool> {
+    let prompt_tokens = backend.count_tokens(&prompt.to_chatml()).await?;
+    Ok(prompt_tokens + prompt.limits.max_output_tokens as usize + SAFETY_MARGIN_TOKENS
+        <= prompt.limits.context_size as usize)
+}
+
+// Splits on sentence boundaries (falling back to words for run-on sentences) so
+// each chunk stays under `max_tokens`, using the measured chars-per-token ratio
+fn split_into_chunks(text: &str, text_tokens: usize, max_tokens: usize) -> Vec<String> {
+    let chars = text.chars().count().max(1);
+    let chars_per_token = chars as f64 / text_tokens.max(1) as f64;
+    let max_chars = ((max_tokens as f64 * chars_per_token * 0.9) as usize).max(1);
+
+    let mut pieces: Vec<&str> = Vec::new();
+    for sentence in split_sentences(text) {
+        if sentence.chars().count() > max_chars {
+            pieces.extend(sentence.split_inclusive(char::is_whitespace));
+        } else {
+            pieces.push(sentence);
+        }
+    }
+
+    let mut chunks = Vec::new();
+    let mut current = String::new();
+    let mut current_chars = 0;
+    for piece in pieces {
+        let piece_chars = piece.chars().count();
+        if current_chars + piece_chars > max_chars && !current.trim().is_empty() {
+            chunks.push(current.trim().to_string());
+            current.clear();
+            current_chars = 0;
+        }
+        current.push_str(piece);
+        current_chars += piece_chars;
+    }
+    if !current.trim().is_empty() {
+        chunks.push(current.trim().to_string());
+    }
+
+    chunks
+}
+
+// Sentences keep their terminator and trailing whitespace so chunks rejoin losslessly
+fn split_sentences(text: &str) -> Vec<&str> {
+    let mut sentences = Vec::new();
+    let mut start = 0;
+    let mut chars = text.char_indices().peekable();
+
+    while let Some((i, c)) = chars.next() {
+        let boundary = match c {
+            '\n' => true,
+            '.' | '?' | '!' => matches!(chars.peek(), Some((_, next)) if next.is_whitespace()),
+            _ => false,
+        };
+        if boundary {
+            // Include following whitespace in this sentence
+            let mut end = i + c.len_utf8();
+            while let Some(&(j, next)) = chars.peek() {
+                if !next.is_whitespace() {
+                    break;
+                }
+                end = j + next.len_utf8();
+                chars.next();
+            }
+            sentences.push(&text[start..end]);
+            start = end;
+        }
+    }
+    if start < text.len() {
+        sentences.push(&text[start..]);
+    }
+
+    sentences
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use async_trait::async_trait;
+    use std::sync::Mutex;
+
+    // Echoes prompts back, recording the context size each was sent with
+    struct FakeBackend {
+        context_size: Option<u32>,
+        sent: Mutex<Vec<u32>>,
+    }
+
+    #[async_trait]
+    impl FormattingBackend for FakeBackend {
+        fn name(&self) -> &str {
+            "fake"
+        }
+
+        async fn complete(&self, prompt: &ChatPrompt) -> Result<String> {
+            self.sent.lock().unwrap().push(prompt.limits.context_size);
+            Ok("condensed".to_string())
+        }
+
+        fn context_size(&self) -> Option<u32> {
+            self.context_size
+        }
+    }
+
+    #[test]
+    fn sentences() {
+        let cases: &[(&str, &[&str])] = &[
+            ("", &[]),
+            ("no terminator", &["no terminator"]),
+            ("One. Two? Three!", &["One. ", "Two? ", "Three!"]),
+            ("First.  Second.\n\nThird", &["First.  ", "Second.\n\n", "Third"]),
+            ("line one\nline two", &["line one\n", "line two"]),
+            // Decimals and abbreviations without a following space don't split
+            ("It costs 3.50 today. e.g.this", &["It costs 3.50 today. ", "e.g.this"]),
+            ("Grüße. Ça va?", &["Grüße. ", "Ça va?"]),
+        ];
+        for (input, expected) in cases {
+            assert_eq!(split_sentences(input), *expected, "input: {:?}", input);
+        }
+    }
+
+    #[test]
+    fn chunks_stay_under_the_limit() {
+        let text = "One two three. Four five six. Seven eight nine. Ten eleven twelve.";
+        // One char per token: 35 tokens is 31 chars after the 0.9 margin
+        let chunks = split_into_chunks(text, text.len(), 35);
+        assert_eq!(
+            chunks,
+            ["One two three. Four five six.", "Seven eight nine.", "Ten eleven twelve."]
+        );
+        for chunk in &chunks {
+            assert!(chunk.chars().count() <= 31, "chunk: {:?}", chunk);
+        }
+    }
+
+    #[test]
+    fn chunks_keep_every_word() {
+        let cases: &[(&str, usize)] = &[
+            ("Short. Sentences. Only.", 3),
+            ("a run on sentence with no end that is longer than any chunk", 4),
+            ("Mixed lengths. Then a much longer sentence that needs splitting on words.\nDone.", 5),
+            ("One chunk fits everything.", 1000),
+        ];
+        for (input, max_tokens) in cases {
+            let chunks = split_into_chunks(input, input.len() / 4, *max_tokens);
+            assert!(chunks.iter().all(|chunk| !chunk.is_empty()), "input: {:?}", input);
+            let words: Vec<&str> = chunks.iter().flat_map(|chunk| chunk.split_whitespace()).collect();
+            assert_eq!(words, input.split_whitespace().collect::<Vec<_>>(), "input: {:?}", input);
+        }
+    }
+
+    #[test]
+    fn short_text_is_one_chunk() {
+        assert_eq!(split_into_chunks("Hello there.", 3, 100), ["Hello there."]);
+        assert!(split_into_chunks("   ", 1, 100).is_empty());
+    }
+
+    #[tokio::test]
+    async fn backend_context_overrides_template() {
+        let template = FormatTemplate::builtin("email").unwrap();
+        // ~4000 tokens: over the email template's 4096 once its output is reserved
+        let transcript = "This sentence is repeated to make a long transcript. ".repeat(300);
+
+        // Two condensed parts and the merge, or the whole transcript at once
+        let cases: &[(Option<u32>, &[u32])] = &[
+            (None, &[4096, 4096, 4096]),
+            (Some(32_000), &[32_000]),
+        ];
+        for (context_size, expected) in cases {
+            let backend = FakeBackend {
+                context_size: *context_size,
+                sent: Mutex::default(),
+            };
+            format_with_template(&backend, &template, &transcript).await.unwrap();
+            assert_eq!(*backend.sent.lock().unwrap(), *expected, "context: {:?}", context_size);
+        }
+    }
+}
@@ -0,0 +1,192 @@
+use super::backend::{ChatPrompt, FormattingBackend};
+use super::templates::TemplateLimits;
+use anyhow::{Context, Result};
+use async_trait::async_trait;
+use llama_cpp_2::context::params::LlamaContextParams;
+use llama_cpp_2::llama_backend::LlamaBackend;
+use llama_cpp_2::llama_batch::LlamaBatch;
+use llama_cpp_2::model::params::LlamaModelParams;
+use llama_cpp_2::model::{AddBos, LlamaModel, Special};
+use llama_cpp_2::sampling::LlamaSampler;
+use std::num::NonZeroU32;
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex, OnceLock};
+
+// llama.cpp's backend may only be initialized once per process
+static LLAMA_BACKEND: OnceLock<LlamaBackend> = OnceLock::new();
+// Serializes initialization so concurrent first calls don't both init; a failed
+// init leaves the backend unset for the next call to retry
+static LLAMA_BACKEND_INIT: Mutex<()> = Mutex::new(());
+
+fn llama_backend() -> Result<&'static LlamaBackend> {
+    if let Some(backend) = LLAMA_BACKEND.get() {
+        return Ok(backend);
+    }
+    let _init = LLAMA_BACKEND_INIT.lock().unwrap();
+    if let Some(backend) = LLAMA_BACKEND.get() {
+        return Ok(backend);
+    }
+    let backend = LlamaBackend::init().context("Failed to initialize llama.cpp backend")?;
+    Ok(LLAMA_BACKEND.get_or_init(|| backend))
+}
+
+// Keeps the most recently used model loaded, mirroring how LlmFormatter keeps
+// one llama-server alive
+pub struct InProcessLlama {
+    loaded: Mutex<Option<(PathBuf, Arc<LlamaModel>)>>,
+}
+
+impl InProcessLlama {
+    pub fn new() -> Self {
+        Self {
+            loaded: Mutex::new(None),
+        }
+    }
+
+    fn load(&self, model_path: &PathBuf) -> Result<Arc<LlamaModel>> {
+        let mut loaded = self.loaded.lock().unwrap();
+
+        if let Some((path, model)) = loaded.as_ref() {
+            if path == model_path {
+                return Ok(model.clone());
+            }
+        }
+
+        eprintln!("📦 Loading LLM in-process: {:?}", model_path);
+
+        // CPU only; platforms with GPU acceleration ship llama-server instead
+        let model_params = LlamaModelParams::default().with_n_gpu_layers(0);
+        let model = LlamaModel::load_from_file(llama_backend()?, model_path, &model_params)
+            .context("Failed to load LLM model")?;
+        let model = Arc::new(model);
+
+        *loaded = Some((model_path.clone(), model.clone()));
+
+        eprintln!("✅ LLM loaded in-process");
+
+        Ok(model)
+    }
+
+    fn generate(model: &LlamaModel, prompt: &str, limits: TemplateLimits) -> Result<String> {
+        let backend = llama_backend()?;
+        let max_new_tokens = limits.max_output_tokens as usize;
+
+        let ctx_params = LlamaContextParams::default().with_n_ctx(NonZeroU32::new(limits.context_size));
+        let mut ctx = model
+            .new_context(backend, ctx_params)
+            .context("Failed to create llama.cpp context")?;
+
+        let tokens = model
+            .str_to_token(prompt, AddBos::Always)
+            .context("Failed to tokenize prompt")?;
+
+        if tokens.len() + max_new_tokens > limits.context_size as usize {
+            return Err(anyhow::anyhow!(
+                "Transcript is too long for the model context ({} tokens)",
+                tokens.len()
+            ));
+        }
+
+        // Feed the prompt, only requesting logits for the last token
+        let mut batch = LlamaBatch::new(tokens.len().max(1), 1);
+        let last_index = tokens.len() as i32 - 1;
+        for (i, token) in (0_i32..).zip(tokens.into_iter()) {
+            batch.add(token, i, &[0], i == last_index)?;
+        }
+        ctx.decode(&mut batch).context("Failed to evaluate prompt")?;
+
+        let mut sampler = LlamaSampler::chain_simple([
+            LlamaSampler::temp(0.7),
+            LlamaSampler::dist(rand::random::<u32>()),
+        ]);
+
+        let mut output = String::new();
+        let mut n_cur = batch.n_tokens();
+
+        for _ in 0..max_new_tokens {
+            let token = sampler.sample(&ctx, batch.n_tokens() - 1);
+            sampler.accept(token);
+
+            if model.is_eog_token(token) {
+                break;
+            }
+
+            output.push_str(&model.token_to_str(token, Special::Tokenize)?);
+
+            // Same stop sequences as the llama-server request
+            if output.ends_with("<|im_end|>") || output.ends_with("</s>") {
+                break;
+            }
+
+            batch.clear();
+            batch.add(token, n_cur, &[0], true)?;
+            n_cur += 1;
+
+            ctx.decode(&mut batch).context("Failed to decode token")?;
+        }
+
+        let output = output
+            .trim_end_matches("<|im_end|>")
+            .trim_end_matches("</s>");
+
+        Ok(output.trim().to_string())
+    }
+}
+
+// Formats with llama.cpp linked into the app, no llama-server process or port
+pub struct InProcessLlamaBackend {
+    llama: Arc<InProcessLlama>,
+    model_path: PathBuf,
+}
+
+impl InProcessLlamaBackend {
+    pub fn new(llama: Arc<InProcessLlama>, model_path: PathBuf) -> Self {
+        Self { llama, model_path }
+    }
+}
+
+#[async_trait]
+impl FormattingBackend for InProcessLlamaBackend {
+    fn name(&self) -> &str {
+        "llama.cpp (in-process)"
+    }
+
+    async fn complete(&self, prompt: &ChatPrompt) -> Result<String> {
+        let llama = self.llama.clone();
+        let model_path = self.model_path.clone();
+        // No grammar-constrained sampling here; JSON templates rely on the prompt
+        // and on validation by the caller
+        let limits = prompt.limits;
+        let prompt = prompt.to_chatml();
+
+        eprintln!("🔄 Generating in-process...");
+
+        // Generation is CPU bound, keep it off the async runtime
+        let content = tokio::task::spawn_blocking(move || {
+            let model = llama.load(&model_path)?;
+            InProcessLlama::generate(&model, &prompt, limits)
+        })
+        .await
+        .context("In-process generation panicked")??;
+
+        eprintln!("✅ Generated {} characters", content.len());
+
+        Ok(content)
+    }
+
+    async fn count_tokens(&self, text: &str) -> Result<usize> {
+        let llama = self.llama.clone();
+        let model_path = self.model_path.clone();
+        let text = text.to_string();
+
+        tokio::task::spawn_blocking(move || {
+            let model = llama.load(&model_path)?;
+            let tokens = model
+                .str_to_token(&text, AddBos::Never)
+                .context("Failed to tokenize text")?;
+            Ok(tokens.len())
+        })
+        .await
+        .context("In-process tokenization panicked")?
+    }
+}
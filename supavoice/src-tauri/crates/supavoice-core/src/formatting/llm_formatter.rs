@@ -0,0 +1,388 @@
+use super::backend::{ChatPrompt, FormattingBackend};
+use anyhow::{Context, Result};
+use async_trait::async_trait;
+use rand::distributions::{Alphanumeric, DistString};
+use serde::Serialize;
+use std::io::{Read, Write};
+use std::net::{Ipv4Addr, SocketAddr, TcpListener, TcpStream};
+use std::path::PathBuf;
+use std::process::{Child, Command, Stdio};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+// Context size llama-server is started with unless a template needs more
+const DEFAULT_CONTEXT_SIZE: u32 = 8192;
+// Loading a large model from a cold disk can take a while
+const STARTUP_TIMEOUT: Duration = Duration::from_secs(60);
+
+struct ServerProcess {
+    child: Child,
+    port: u16,
+    model_path: PathBuf,
+    context_size: u32,
+}
+
+impl ServerProcess {
+    fn stop(&mut self) {
+        let _ = self.child.kill();
+        let _ = self.child.wait();
+    }
+}
+
+#[derive(Serialize, Clone, Debug)]
+pub struct FormatterStatus {
+    pub running: bool,
+    pub port: Option<u16>,
+    pub model_path: Option<PathBuf>,
+    pub context_size: Option<u32>,
+}
+
+pub struct LlmFormatter {
+    llama_server_path: PathBuf,
+    server_process: Arc<Mutex<Option<ServerProcess>>>,
+    // Per-session secret passed to llama-server via --api-key, so other local
+    // processes can't use our model server
+    api_key: String,
+}
+
+impl LlmFormatter {
+    pub fn new() -> Result<Self> {
+        // Try multiple locations for llama-server binary
+        let exe_dir = std::env::current_exe()?
+            .parent()
+            .ok_or_else(|| anyhow::anyhow!("Failed to get parent directory"))?
+            .to_path_buf();
+
+        // Possible locations (dev vs production)
+        let possible_paths = vec![
+            // Production: macOS app bundle
+            exe_dir.join("../Resources/llama-server"),
+            // Dev: src-tauri/resources
+            exe_dir.join("../../resources/llama-server"),
+            // Dev: alternative
+            exe_dir.join("../../../src-tauri/resources/llama-server"),
+        ];
+
+        let llama_server_path = possible_paths
+            .iter()
+            .find(|path| path.exists())
+            .ok_or_else(|| {
+                anyhow::anyhow!(
+                    "llama-server binary not found. Tried:\n{}",
+                    possible_paths
+                        .iter()
+                        .map(|p| format!("  - {:?}", p))
+                        .collect::<Vec<_>>()
+                        .join("\n")
+                )
+            })?
+            .clone();
+
+        eprintln!("✅ Found llama-server at: {:?}", llama_server_path);
+
+        Ok(Self {
+            llama_server_path,
+            server_process: Arc::new(Mutex::new(None)),
+            api_key: Alphanumeric.sample_string(&mut rand::thread_rng(), 32),
+        })
+    }
+
+    fn find_free_port() -> Result<u16> {
+        // Let the OS pick an ephemeral port, then release it for llama-server
+        let listener = TcpListener::bind((Ipv4Addr::LOCALHOST, 0))
+            .context("Failed to find a free port for llama-server")?;
+        Ok(listener.local_addr()?.port())
+    }
+
+    pub fn start_server_if_needed(&self, model_path: &PathBuf) -> Result<()> {
+        self.start_server_with_context(model_path, DEFAULT_CONTEXT_SIZE)
+    }
+
+    pub fn start_server_with_context(&self, model_path: &PathBuf, context_size: u32) -> Result<()> {
+        let mut process_guard = self.server_process.lock().unwrap();
+
+        // Check if server is already running with the requested model
+        if let Some(server) = process_guard.as_mut() {
+            if server.child.try_wait()?.is_some() {
+                eprintln!("⚠️  llama-server exited, restarting...");
+            } else if &server.model_path != model_path {
+                // llama-server holds a single model, so swapping means a restart
+                eprintln!(
+                    "🔄 Switching llama-server model: {:?} -> {:?}",
+                    server.model_path, model_path
+                );
+                server.stop();
+            } else if server.context_size < context_size {
+                eprintln!(
+                    "🔄 Restarting llama-server with a larger context: {} -> {}",
+                    server.context_size, context_size
+                );
+                server.stop();
+            } else {
+                eprintln!("⚡ Server already running");
+                return Ok(());
+            }
+            *process_guard = None;
+        }
+
+        let port = Self::find_free_port()?;
+        let context_size = context_size.max(DEFAULT_CONTEXT_SIZE);
+
+        eprintln!("🚀 Starting llama-server with model: {:?}", model_path);
+
+        // Start llama-server with the model loaded
+        let mut child = Command::new(&self.llama_server_path)
+            .arg("-m")
+            .arg(model_path)
+            .arg("--host")
+            .arg("127.0.0.1")
+            .arg("--port")
+            .arg(port.to_string())
+            .arg("--api-key")
+            .arg(&self.api_key)
+            .arg("-ngl")
+            .arg("99") // GPU layers
+            .arg("-c")
+            .arg(context_size.to_string())
+            .arg("--log-disable") // Disable logging for cleaner output
+            .stdin(Stdio::null())
+            .stdout(Stdio::null())
+            .stderr(Stdio::null())
+            .spawn()
+            .context("Failed to start llama-server")?;
+
+        if let Err(e) = Self::wait_until_ready(&mut child, port) {
+            let _ = child.kill();
+            let _ = child.wait();
+            return Err(e);
+        }
+
+        *process_guard = Some(ServerProcess {
+            child,
+            port,
+            model_path: model_path.clone(),
+            context_size,
+        });
+
+        eprintln!("✅ Server started on port {}", port);
+
+        Ok(())
+    }
+
+    // Polls /health until the model is loaded; llama-server answers 503 while loading
+    fn wait_until_ready(child: &mut Child, port: u16) -> Result<()> {
+        let deadline = Instant::now() + STARTUP_TIMEOUT;
+
+        loop {
+            // If another process grabbed the port in the meantime, llama-server exits immediately
+            if let Some(status) = child.try_wait()? {
+                return Err(anyhow::anyhow!(
+                    "llama-server exited during startup ({}) on port {}",
+                    status,
+                    port
+                ));
+            }
+            if Self::health_ok(port) {
+                return Ok(());
+            }
+            if Instant::now() >= deadline {
+                return Err(anyhow::anyhow!(
+                    "llama-server did not become ready within {}s",
+                    STARTUP_TIMEOUT.as_secs()
+                ));
+            }
+            std::thread::sleep(Duration::from_millis(250));
+        }
+    }
+
+    fn health_ok(port: u16) -> bool {
+        let addr = SocketAddr::from((Ipv4Addr::LOCALHOST, port));
+        let Ok(mut stream) = TcpStream::connect_timeout(&addr, Duration::from_secs(1)) else {
+            return false;
+        };
+        let _ = stream.set_read_timeout(Some(Duration::from_secs(2)));
+        let request = format!(
+            "GET /health HTTP/1.1\r\nHost: 127.0.0.1:{}\r\nConnection: close\r\n\r\n",
+            port
+        );
+        if stream.write_all(request.as_bytes()).is_err() {
+            return false;
+        }
+        let mut status_line = [0u8; 12];
+        stream.read_exact(&mut status_line).is_ok() && status_line.ends_with(b" 200")
+    }
+
+    pub fn status(&self) -> FormatterStatus {
+        let mut process_guard = self.server_process.lock().unwrap();
+
+        if let Some(server) = process_guard.as_mut() {
+            if let Ok(None) = server.child.try_wait() {
+                return FormatterStatus {
+                    running: true,
+                    port: Some(server.port),
+                    model_path: Some(server.model_path.clone()),
+                    context_size: Some(server.context_size),
+                };
+            }
+        }
+
+        FormatterStatus {
+            running: false,
+            port: None,
+            model_path: None,
+            context_size: None,
+        }
+    }
+
+    fn running_port(&self) -> Result<u16> {
+        self.server_process
+            .lock()
+            .unwrap()
+            .as_ref()
+            .map(|server| server.port)
+            .ok_or_else(|| anyhow::anyhow!("llama-server is not running"))
+    }
+
+    async fn count_tokens(&self, model_path: &PathBuf, text: &str) -> Result<usize> {
+        self.start_server_if_needed(model_path)?;
+        let port = self.running_port()?;
+
+        let client = reqwest::Client::new();
+        let response = client
+            .post(format!("http://127.0.0.1:{}/tokenize", port))
+            .bearer_auth(&self.api_key)
+            .json(&serde_json::json!({ "content": text }))
+            .timeout(std::time::Duration::from_secs(30))
+            .send()
+            .await
+            .context("Failed to send tokenize request to llama-server")?;
+
+        if !response.status().is_success() {
+            return Err(anyhow::anyhow!(
+                "Server returned error: {}",
+                response.status()
+            ));
+        }
+
+        let json: serde_json::Value = response.json().await?;
+        let tokens = json["tokens"]
+            .as_array()
+            .ok_or_else(|| anyhow::anyhow!("No tokens in response"))?;
+
+        Ok(tokens.len())
+    }
+
+    async fn generate(&self, model_path: &PathBuf, prompt: &ChatPrompt) -> Result<String> {
+        // Start server if not running, or restart it if the model or context changed
+        self.start_server_with_context(model_path, prompt.limits.context_size)?;
+        let port = self.running_port()?;
+
+        eprintln!("🔄 Sending completion request to llama-server...");
+
+        // Make HTTP request to llama-server (async)
+        let mut body = serde_json::json!({
+            "prompt": prompt.to_chatml(),
+            "n_predict": prompt.limits.max_output_tokens,
+            "temperature": 0.7,
+            "stop": ["<|im_end|>", "</s>"],
+            "cache_prompt": true, // Cache the prompt for faster subsequent requests
+        });
+        if let Some(schema) = &prompt.json_schema {
+            // llama-server turns the schema into a GBNF grammar that constrains sampling
+            body["json_schema"] = schema.clone();
+        }
+
+        let client = reqwest::Client::new();
+        let response = client
+            .post(format!("http://127.0.0.1:{}/completion", port))
+            .bearer_auth(&self.api_key)
+            .json(&body)
+            .timeout(std::time::Duration::from_secs(120))
+            .send()
+            .await
+            .context("Failed to send request to llama-server")?;
+
+        if !response.status().is_success() {
+            return Err(anyhow::anyhow!(
+                "Server returned error: {}",
+                response.status()
+            ));
+        }
+
+        let json: serde_json::Value = response.json().await?;
+        let content = json["content"]
+            .as_str()
+            .ok_or_else(|| anyhow::anyhow!("No content in response"))?;
+
+        eprintln!("✅ Generated {} characters", content.len());
+
+        Ok(content.trim().to_string())
+    }
+}
+
+impl Drop for LlmFormatter {
+    fn drop(&mut self) {
+        // Don't leave an orphaned llama-server holding the model in memory
+        if let Some(mut server) = self.server_process.lock().unwrap().take() {
+            server.stop();
+        }
+    }
+}
+
+// Formats through the bundled llama-server with a specific local model
+pub struct LocalLlamaBackend {
+    formatter: Arc<LlmFormatter>,
+    model_path: PathBuf,
+}
+
+impl LocalLlamaBackend {
+    pub fn new(formatter: Arc<LlmFormatter>, model_path: PathBuf) -> Self {
+        Self {
+            formatter,
+            model_path,
+        }
+    }
+}
+
+#[async_trait]
+impl FormattingBackend for LocalLlamaBackend {
+    fn name(&self) -> &str {
+        "llama-server"
+    }
+
+    async fn complete(&self, prompt: &ChatPrompt) -> Result<String> {
+        self.formatter.generate(&self.model_path, prompt).await
+    }
+
+    async fn count_tokens(&self, text: &str) -> Result<usize> {
+        self.formatter.count_tokens(&self.model_path, text).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Answers one connection with `status_line` and returns the port
+    fn serve_once(status_line: &'static str) -> u16 {
+        let listener = TcpListener::bind((Ipv4Addr::LOCALHOST, 0)).unwrap();
+        let port = listener.local_addr().unwrap().port();
+        std::thread::spawn(move || {
+            let (mut stream, _) = listener.accept().unwrap();
+            let mut request = [0u8; 1024];
+            let _ = stream.read(&mut request);
+            let _ = write!(stream, "{}\r\nContent-Length: 0\r\n\r\n", status_line);
+        });
+        port
+    }
+
+    #[test]
+    fn health_check() {
+        assert!(LlmFormatter::health_ok(serve_once("HTTP/1.1 200 OK")));
+        // Still loading the model
+        assert!(!LlmFormatter::health_ok(serve_once("HTTP/1.1 503 Service Unavailable")));
+        // Nothing listening
+        let port = LlmFormatter::find_free_port().unwrap();
+        assert!(!LlmFormatter::health_ok(port));
+    }
+}
@@ -0,0 +1,17 @@
+mod backend;
+mod chunking;
+#[cfg(feature = "llama-inprocess")]
+mod inprocess;
+mod llm_formatter;
+mod openai;
+mod structured;
+mod templates;
+
+pub use backend::{ChatPrompt, FormattingBackend};
+pub use chunking::format_with_template;
+#[cfg(feature = "llama-inprocess")]
+pub use inprocess::{InProcessLlama, InProcessLlamaBackend};
+pub use llm_formatter::{FormatterStatus, LlmFormatter, LocalLlamaBackend};
+pub use openai::OpenAiCompatibleBackend;
+pub use structured::{parse_structured_notes, ActionItem, StructuredNotes};
+pub use templates::{FormatTemplate, TemplateLimits};
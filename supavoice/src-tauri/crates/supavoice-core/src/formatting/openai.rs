@@ -0,0 +1,210 @@
+use super::backend::{ChatPrompt, FormattingBackend};
+use anyhow::{Context, Result};
+use async_trait::async_trait;
+use reqwest::Client;
+
+// Any server speaking OpenAI's /v1/chat/completions API (OpenAI, vLLM, LM Studio, Ollama, ...)
+pub struct OpenAiCompatibleBackend {
+    client: Client,
+    base_url: String,
+    api_key: Option<String>,
+    model: String,
+    // The model's context window; None holds it to the template's limits
+    context_size: Option<u32>,
+}
+
+impl OpenAiCompatibleBackend {
+    pub fn new(base_url: &str, api_key: Option<String>, model: &str) -> Self {
+        Self {
+            client: Client::new(),
+            // Accept both "https://host/v1" and "https://host/v1/"
+            base_url: base_url.trim_end_matches('/').to_string(),
+            api_key,
+            model: model.to_string(),
+            context_size: None,
+        }
+    }
+
+    pub fn with_context_size(mut self, context_size: Option<u32>) -> Self {
+        self.context_size = context_size;
+        self
+    }
+}
+
+#[async_trait]
+impl FormattingBackend for OpenAiCompatibleBackend {
+    fn name(&self) -> &str {
+        "openai-compatible"
+    }
+
+    fn context_size(&self) -> Option<u32> {
+        self.context_size
+    }
+
+    async fn complete(&self, prompt: &ChatPrompt) -> Result<String> {
+        eprintln!("🔄 Sending chat completion request to {}...", self.base_url);
+
+        let mut body = serde_json::json!({
+            "model": self.model,
+            "messages": [
+                { "role": "system", "content": prompt.system },
+                { "role": "user", "content": prompt.user },
+            ],
+            "temperature": 0.7,
+            "max_tokens": prompt.limits.max_output_tokens,
+        });
+        if let Some(schema) = &prompt.json_schema {
+            body["response_format"] = serde_json::json!({
+                "type": "json_schema",
+                "json_schema": { "name": "structured_output", "schema": schema, "strict": true },
+            });
+        }
+
+        let mut request = self
+            .client
+            .post(format!("{}/chat/completions", self.base_url))
+            .json(&body)
+            .timeout(std::time::Duration::from_secs(120));
+
+        if let Some(api_key) = &self.api_key {
+            request = request.bearer_auth(api_key);
+        }
+
+        let response = request
+            .send()
+            .await
+            .context("Failed to send request to formatting server")?;
+
+        let status = response.status();
+        if !status.is_success() {
+            // OpenAI-style servers put a readable message in error.message
+            let body = response.text().await.unwrap_or_default();
+            let message = serde_json::from_str::<serde_json::Value>(&body)
+                .ok()
+                .and_then(|json| json["error"]["message"].as_str().map(str::to_string))
+                .unwrap_or(body);
+            return Err(anyhow::anyhow!("Server returned error {}: {}", status, message));
+        }
+
+        let json: serde_json::Value = response.json().await?;
+        let content = json["choices"][0]["message"]["content"]
+            .as_str()
+            .ok_or_else(|| anyhow::anyhow!("No content in response"))?;
+
+        eprintln!("✅ Generated {} characters", content.len());
+
+        Ok(content.trim().to_string())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::formatting::TemplateLimits;
+    use axum::extract::State;
+    use axum::http::HeaderMap;
+    use axum::routing::post;
+    use axum::{Json, Router};
+    use std::sync::{Arc, Mutex};
+
+    #[derive(Debug)]
+    struct Request {
+        authorization: Option<String>,
+        body: serde_json::Value,
+    }
+
+    type Requests = Arc<Mutex<Vec<Request>>>;
+
+    // Answers every chat completion with `status` and `body`, recording the requests
+    async fn mock_server(status: u16, body: &'static str) -> (String, Requests) {
+        let requests: Requests = Arc::default();
+        let handler = move |State(requests): State<Requests>, headers: HeaderMap, Json(request): Json<serde_json::Value>| async move {
+            let authorization = headers
+                .get("authorization")
+                .map(|value| value.to_str().unwrap().to_string());
+            requests.lock().unwrap().push(Request {
+                authorization,
+                body: request,
+            });
+            (axum::http::StatusCode::from_u16(status).unwrap(), body)
+        };
+        let app = Router::new()
+            .route("/v1/chat/completions", post(handler))
+            .with_state(requests.clone());
+
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let address = listener.local_addr().unwrap();
+        tokio::spawn(async move { axum::serve(listener, app).await.unwrap() });
+        (format!("http://{}/v1/", address), requests)
+    }
+
+    fn prompt(json_schema: Option<serde_json::Value>) -> ChatPrompt {
+        ChatPrompt {
+            system: "Be brief.".to_string(),
+            user: "Transcript: hello".to_string(),
+            limits: TemplateLimits {
+                context_size: 4096,
+                max_output_tokens: 512,
+            },
+            json_schema,
+        }
+    }
+
+    const REPLY: &str = r#"{"choices": [{"message": {"role": "assistant", "content": "  Hello.  "}}]}"#;
+
+    #[tokio::test]
+    async fn sends_chat_request() {
+        let (base_url, requests) = mock_server(200, REPLY).await;
+        let backend = OpenAiCompatibleBackend::new(&base_url, Some("sk-test".to_string()), "gpt-test");
+
+        assert_eq!(backend.complete(&prompt(None)).await.unwrap(), "Hello.");
+
+        let requests = requests.lock().unwrap();
+        assert_eq!(requests.len(), 1);
+        assert_eq!(requests[0].authorization.as_deref(), Some("Bearer sk-test"));
+        let body = &requests[0].body;
+        assert_eq!(body["model"], "gpt-test");
+        assert_eq!(body["max_tokens"], 512);
+        assert_eq!(
+            body["messages"],
+            serde_json::json!([
+                { "role": "system", "content": "Be brief." },
+                { "role": "user", "content": "Transcript: hello" },
+            ])
+        );
+        assert!(body.get("response_format").is_none());
+    }
+
+    #[tokio::test]
+    async fn sends_schema_without_key() {
+        let (base_url, requests) = mock_server(200, REPLY).await;
+        let backend = OpenAiCompatibleBackend::new(&base_url, None, "local");
+        let schema = serde_json::json!({ "type": "object" });
+
+        backend.complete(&prompt(Some(schema.clone()))).await.unwrap();
+
+        let requests = requests.lock().unwrap();
+        assert_eq!(requests[0].authorization, None);
+        let format = &requests[0].body["response_format"];
+        assert_eq!(format["type"], "json_schema");
+        assert_eq!(format["json_schema"]["schema"], schema);
+        assert_eq!(format["json_schema"]["strict"], true);
+    }
+
+    #[tokio::test]
+    async fn maps_errors() {
+        let cases: &[(u16, &'static str, &str)] = &[
+            (401, r#"{"error": {"message": "Invalid API key"}}"#, "401 Unauthorized: Invalid API key"),
+            (500, "boom", "500 Internal Server Error: boom"),
+            (200, r#"{"choices": []}"#, "No content in response"),
+            (200, r#"{"id": "missing choices"}"#, "No content in response"),
+        ];
+
+        for (status, body, expected) in cases {
+            let (base_url, _) = mock_server(*status, body).await;
+            let backend = OpenAiCompatibleBackend::new(&base_url, None, "local");
+            let error = backend.complete(&prompt(None)).await.unwrap_err().to_string();
+            assert!(error.contains(expected), "status {}: {}", status, error);
+        }
+    }
+}
@@ -0,0 +1,251 @@
+use anyhow::{Context, Result};
+use chrono::NaiveDate;
+use serde::{Deserialize, Serialize};
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[serde(deny_unknown_fields)]
+pub struct ActionItem {
+    pub owner: Option<String>,
+    pub task: String,
+    // ISO 8601 date (YYYY-MM-DD)
+    pub due: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Default)]
+#[serde(deny_unknown_fields)]
+pub struct StructuredNotes {
+    pub action_items: Vec<ActionItem>,
+    pub decisions: Vec<String>,
+    pub questions: Vec<String>,
+}
+
+impl StructuredNotes {
+    // For files written beside recordings, where JSON would be unreadable
+    pub fn to_markdown(&self) -> String {
+        let mut out = String::new();
+
+        if !self.action_items.is_empty() {
+            out.push_str("## Action items\n\n");
+            for item in &self.action_items {
+                out.push_str("- [ ] ");
+                if let Some(owner) = &item.owner {
+                    out.push_str(&format!("**{}**: ", owner));
+                }
+                out.push_str(&item.task);
+                if let Some(due) = &item.due {
+                    out.push_str(&format!(" (due {})", due));
+                }
+                out.push('\n');
+            }
+            out.push('\n');
+        }
+
+        for (heading, entries) in [("Decisions", &self.decisions), ("Questions", &self.questions)] {
+            if entries.is_empty() {
+                continue;
+            }
+            out.push_str(&format!("## {}\n\n", heading));
+            for entry in entries {
+                out.push_str(&format!("- {}\n", entry));
+            }
+            out.push('\n');
+        }
+
+        out.trim_end().to_string()
+    }
+}
+
+// Sent to llama-server as `json_schema` (converted to a GBNF grammar server-side)
+// and to OpenAI-compatible servers as a strict `response_format`
+pub fn structured_notes_schema() -> serde_json::Value {
+    serde_json::json!({
+        "type": "object",
+        "properties": {
+            "action_items": {
+                "type": "array",
+                "items": {
+                    "type": "object",
+                    "properties": {
+                        "owner": { "type": ["string", "null"] },
+                        "task": { "type": "string", "minLength": 1 },
+                        "due": {
+                            "type": ["string", "null"],
+                            "pattern": "^[0-9]{4}-[0-9]{2}-[0-9]{2}$"
+                        }
+                    },
+                    "required": ["owner", "task", "due"],
+                    "additionalProperties": false
+                }
+            },
+            "decisions": { "type": "array", "items": { "type": "string" } },
+            "questions": { "type": "array", "items": { "type": "string" } }
+        },
+        "required": ["action_items", "decisions", "questions"],
+        "additionalProperties": false
+    })
+}
+
+// Parses model output and checks the constraints serde can't express, so a
+// backend without grammar support can't slip malformed items through
+pub fn parse_structured_notes(output: &str) -> Result<StructuredNotes> {
+    let json = strip_code_fence(output);
+
+    let mut notes: StructuredNotes = serde_json::from_str(json)
+        .context("Model output does not match the structured notes schema")?;
+
+    for (i, item) in notes.action_items.iter_mut().enumerate() {
+        item.task = item.task.trim().to_string();
+        if item.task.is_empty() {
+            return Err(anyhow::anyhow!("Action item {} has an empty task", i + 1));
+        }
+
+        // Models often emit "" instead of null for unknown values
+        item.owner = item
+            .owner
+            .take()
+            .map(|owner| owner.trim().to_string())
+            .filter(|owner| !owner.is_empty());
+        item.due = item
+            .due
+            .take()
+            .map(|due| due.trim().to_string())
+            .filter(|due| !due.is_empty());
+
+        if let Some(due) = &item.due {
+            if !is_iso_date(due) {
+                return Err(anyhow::anyhow!(
+                    "Action item {} has an invalid due date '{}' (expected YYYY-MM-DD)",
+                    i + 1,
+                    due
+                ));
+            }
+        }
+    }
+
+    for list in [&mut notes.decisions, &mut notes.questions] {
+        *list = list
+            .iter()
+            .map(|entry| entry.trim().to_string())
+            .filter(|entry| !entry.is_empty())
+            .collect();
+    }
+
+    Ok(notes)
+}
+
+// Hosted models sometimes wrap JSON in ```json fences despite response_format
+fn strip_code_fence(output: &str) -> &str {
+    let trimmed = output.trim();
+    match trimmed.strip_prefix("```") {
+        Some(rest) => {
+            let rest = rest.strip_prefix("json").unwrap_or(rest);
+            rest.strip_suffix("```").unwrap_or(rest).trim()
+        }
+        None => trimmed,
+    }
+}
+
+fn is_iso_date(value: &str) -> bool {
+    // chrono also accepts unpadded months and days, which the schema pattern doesn't
+    value.len() == 10 && NaiveDate::parse_from_str(value, "%Y-%m-%d").is_ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_output() {
+        let cases: &[(&str, StructuredNotes)] = &[
+            (
+                r#"{"action_items": [], "decisions": [], "questions": []}"#,
+                StructuredNotes::default(),
+            ),
+            (
+                "```json\n{\"action_items\": [{\"owner\": \"Sam\", \"task\": \" Send the deck \", \"due\": \"2024-02-29\"}], \"decisions\": [\"Ship Friday\", \"  \"], \"questions\": []}\n```",
+                StructuredNotes {
+                    action_items: vec![ActionItem {
+                        owner: Some("Sam".to_string()),
+                        task: "Send the deck".to_string(),
+                        due: Some("2024-02-29".to_string()),
+                    }],
+                    decisions: vec!["Ship Friday".to_string()],
+                    questions: Vec::new(),
+                },
+            ),
+            (
+                "```\n{\"action_items\": [{\"owner\": \"\", \"task\": \"Book a room\", \"due\": \"\"}], \"decisions\": [], \"questions\": [\"Who pays?\"]}\n```",
+                StructuredNotes {
+                    action_items: vec![ActionItem {
+                        owner: None,
+                        task: "Book a room".to_string(),
+                        due: None,
+                    }],
+                    decisions: Vec::new(),
+                    questions: vec!["Who pays?".to_string()],
+                },
+            ),
+        ];
+        for (output, expected) in cases {
+            assert_eq!(parse_structured_notes(output).unwrap(), *expected, "output: {:?}", output);
+        }
+    }
+
+    #[test]
+    fn rejects_bad_output() {
+        let cases = [
+            // Missing field
+            r#"{"action_items": [], "decisions": []}"#,
+            r#"{"action_items": [{"owner": null, "due": null}], "decisions": [], "questions": []}"#,
+            // Unknown field
+            r#"{"action_items": [], "decisions": [], "questions": [], "summary": "x"}"#,
+            r#"{"action_items": [{"owner": null, "task": "  ", "due": null}], "decisions": [], "questions": []}"#,
+            // Impossible, unpadded and non-ISO dates
+            r#"{"action_items": [{"owner": null, "task": "Pay", "due": "2024-02-31"}], "decisions": [], "questions": []}"#,
+            r#"{"action_items": [{"owner": null, "task": "Pay", "due": "2023-02-29"}], "decisions": [], "questions": []}"#,
+            r#"{"action_items": [{"owner": null, "task": "Pay", "due": "2024-2-3"}], "decisions": [], "questions": []}"#,
+            r#"{"action_items": [{"owner": null, "task": "Pay", "due": "next Friday"}], "decisions": [], "questions": []}"#,
+            "Here are the notes.",
+        ];
+        for output in cases {
+            assert!(parse_structured_notes(output).is_err(), "output: {:?}", output);
+        }
+    }
+
+    #[test]
+    fn schema_matches_struct() {
+        let schema = structured_notes_schema();
+        let notes = serde_json::to_value(StructuredNotes {
+            action_items: vec![ActionItem {
+                owner: None,
+                task: "Task".to_string(),
+                due: None,
+            }],
+            ..Default::default()
+        })
+        .unwrap();
+
+        let keys = |value: &serde_json::Value| {
+            let mut keys: Vec<String> = value.as_object().unwrap().keys().cloned().collect();
+            keys.sort();
+            keys
+        };
+        let required = |value: &serde_json::Value| {
+            let mut keys: Vec<String> = value
+                .as_array()
+                .unwrap()
+                .iter()
+                .map(|key| key.as_str().unwrap().to_string())
+                .collect();
+            keys.sort();
+            keys
+        };
+
+        // Strict mode needs every property listed as required
+        assert_eq!(keys(&schema["properties"]), keys(&notes));
+        assert_eq!(required(&schema["required"]), keys(&notes));
+        let item = &schema["properties"]["action_items"]["items"];
+        assert_eq!(keys(&item["properties"]), keys(&notes["action_items"][0]));
+        assert_eq!(required(&item["required"]), keys(&notes["action_items"][0]));
+    }
+}
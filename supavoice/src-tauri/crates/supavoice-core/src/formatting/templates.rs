@@ -0,0 +1,147 @@
+use super::backend::ChatPrompt;
+use super::structured::structured_notes_schema;
+use serde::{Deserialize, Serialize};
+
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq)]
+pub struct TemplateLimits {
+    // Total tokens the model sees: prompt + transcript + output
+    pub context_size: u32,
+    pub max_output_tokens: u32,
+}
+
+#[derive(Clone, Debug)]
+pub struct FormatTemplate {
+    pub id: String,
+    pub system: String,
+    pub instruction: String,
+    pub limits: TemplateLimits,
+    // Set for templates whose output is JSON rather than prose
+    pub json_schema: Option<serde_json::Value>,
+    // Names of the speakers when the transcript is labelled with speaker turns
+    pub speakers: Vec<String>,
+}
+
+impl FormatTemplate {
+    pub fn builtin(id: &str) -> Option<Self> {
+        match id {
+            "email" => Some(Self {
+                id: id.to_string(),
+                system: "You are a helpful assistant that rewrites voice transcripts as professional emails.".to_string(),
+                instruction: "Rewrite the following voice transcript as a professional email. \
+                    Make it clear, concise, and well-structured with proper greeting and closing."
+                    .to_string(),
+                limits: TemplateLimits {
+                    context_size: 4096,
+                    max_output_tokens: 768,
+                },
+                json_schema: None,
+                speakers: Vec::new(),
+            }),
+            "notes" => Some(Self {
+                id: id.to_string(),
+                system: "You are a helpful assistant that converts voice transcripts into organized notes.".to_string(),
+                instruction: "Convert the following voice transcript into clear, organized notes. \
+                    Use bullet points and organize by topic where appropriate."
+                    .to_string(),
+                limits: TemplateLimits {
+                    context_size: 8192,
+                    max_output_tokens: 1024,
+                },
+                json_schema: None,
+                speakers: Vec::new(),
+            }),
+            "action_items" => Some(Self {
+                id: id.to_string(),
+                system: "You are a helpful assistant that extracts action items, decisions and open questions from voice transcripts. You reply with JSON only.".to_string(),
+                instruction: "Extract from the following voice transcript: every action item \
+                    (the task, who owns it if stated, and the due date as YYYY-MM-DD if stated, otherwise null), \
+                    every decision that was made, and every open question. \
+                    Reply with a JSON object with the keys \"action_items\", \"decisions\" and \"questions\"."
+                    .to_string(),
+                limits: TemplateLimits {
+                    context_size: 8192,
+                    max_output_tokens: 1024,
+                },
+                json_schema: Some(structured_notes_schema()),
+                speakers: Vec::new(),
+            }),
+            _ => None,
+        }
+    }
+
+    // For diarized transcripts, whose turns start with "Name: "
+    pub fn with_speakers(mut self, speakers: Vec<String>) -> Self {
+        self.speakers = speakers;
+        self
+    }
+
+    // For backends whose model has its own context window
+    pub fn with_context_size(mut self, context_size: u32) -> Self {
+        self.limits.context_size = context_size;
+        self
+    }
+
+    fn instruction(&self) -> String {
+        if self.speakers.is_empty() {
+            return self.instruction.clone();
+        }
+        format!(
+            "{} The transcript is a conversation between {}. Each turn starts with the \
+            speaker's name and a colon; attribute statements, decisions and action items \
+            to the speaker they came from.",
+            self.instruction,
+            self.speakers.join(", ")
+        )
+    }
+
+    pub fn prompt(&self, transcript: &str) -> ChatPrompt {
+        ChatPrompt {
+            system: self.system.clone(),
+            user: format!("{}\n\nTranscript: {}", self.instruction(), transcript),
+            limits: self.limits,
+            json_schema: self.json_schema.clone(),
+        }
+    }
+
+    // Map step for transcripts that don't fit the context: condense one part
+    // without losing anything the final template pass needs
+    pub fn chunk_prompt(&self, chunk: &str, part: usize, parts: usize, max_output_tokens: u32) -> ChatPrompt {
+        let attribution = if self.speakers.is_empty() {
+            ""
+        } else {
+            " Keep each statement attributed to the speaker named at the start of its turn."
+        };
+        ChatPrompt {
+            system: "You are a helpful assistant that condenses parts of long voice transcripts.".to_string(),
+            user: format!(
+                "The following is part {} of {} of a longer voice transcript. \
+                Condense it into concise prose, keeping every fact, name, number, date, \
+                decision and action item.{} Do not add a greeting or commentary.\n\n\
+                Transcript part: {}",
+                part, parts, attribution, chunk
+            ),
+            limits: TemplateLimits {
+                context_size: self.limits.context_size,
+                max_output_tokens,
+            },
+            // Condensed parts stay prose; only the final pass produces JSON
+            json_schema: None,
+        }
+    }
+
+    // Reduce step: apply the template to the condensed parts, in order
+    pub fn merge_prompt(&self, condensed: &str, parts: usize) -> ChatPrompt {
+        ChatPrompt {
+            system: self.system.clone(),
+            user: format!(
+                "{}\n\nThe transcript was long, so it has been condensed from {} consecutive parts. \
+                Treat it as one continuous transcript.\n\nTranscript: {}",
+                self.instruction(),
+                parts,
+                condensed
+            ),
+            limits: self.limits,
+            json_schema: self.json_schema.clone(),
+        }
+    }
+}
@@ -0,0 +1,80 @@
+use crate::transcription::Segment;
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[serde(tag = "state", rename_all = "snake_case")]
+pub enum JobStatus {
+    Queued,
+    // Percent complete, 0-100
+    Running { progress: f32 },
+    Completed { text: String, segments: Vec<Segment> },
+    Failed { error: String },
+    Cancelled,
+}
+
+impl JobStatus {
+    pub fn is_finished(&self) -> bool {
+        matches!(self, Self::Completed { .. } | Self::Failed { .. } | Self::Cancelled)
+    }
+}
+
+// Files written beside the audio when a job completes; watch folders set these
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct Sidecars {
+    // `<name>.md` formatted with this template
+    pub template: Option<String>,
+    // `<name>.srt`
+    pub subtitles: bool,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct Job {
+    pub id: String,
+    pub path: PathBuf,
+    pub status: JobStatus,
+    // Runs so far, including retries
+    pub attempts: u32,
+    // `<name>.txt` is always written when set
+    #[serde(default)]
+    pub sidecars: Option<Sidecars>,
+    // Seconds since the Unix epoch
+    pub created_at: u64,
+    pub updated_at: u64,
+}
+
+impl Job {
+    pub fn new(id: String, path: PathBuf) -> Self {
+        let now = now();
+        Self {
+            id,
+            path,
+            status: JobStatus::Queued,
+            attempts: 0,
+            sidecars: None,
+            created_at: now,
+            updated_at: now,
+        }
+    }
+
+    pub fn set_status(&mut self, status: JobStatus) {
+        self.status = status;
+        self.updated_at = now();
+    }
+}
+
+// Broadcast on every change; the app forwards these to the frontend
+#[derive(Serialize, Clone, Debug)]
+#[serde(untagged)]
+pub enum JobEvent {
+    Updated(Job),
+    Removed { id: String },
+}
+
+fn now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|elapsed| elapsed.as_secs())
+        .unwrap_or_default()
+}
@@ -0,0 +1,6 @@
+pub mod job;
+pub mod queue;
+pub mod sidecars;
+
+pub use job::{Job, JobEvent, JobStatus, Sidecars};
+pub use queue::{JobHold, JobQueue};
@@ -0,0 +1,578 @@
+use super::job::{Job, JobEvent, JobStatus, Sidecars};
+use super::sidecars::write_sidecars;
+use crate::pipeline;
+use crate::server::ServerBackend;
+use crate::transcription::{flag_words, DecodeControl, Segment};
+use crate::vocabulary::vocabulary_prompt;
+use anyhow::{anyhow, bail, Result};
+use rand::distributions::Alphanumeric;
+use rand::Rng;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+use tokio::sync::{broadcast, Notify};
+
+// Batch transcription of files and folders, one job at a time in the background.
+// Job state is persisted so a queue survives restarts.
+pub struct JobQueue {
+    jobs: Mutex<Vec<Job>>,
+    path: PathBuf,
+    // The running job and its cancel flag
+    running: Mutex<Option<(String, Arc<AtomicBool>)>>,
+    // Live dictation takes priority: no new job starts while any JobHold is alive
+    holds: AtomicUsize,
+    wake: Notify,
+    events: broadcast::Sender<JobEvent>,
+}
+
+impl JobQueue {
+    pub fn load() -> Result<Self> {
+        let project_dirs = directories::ProjectDirs::from("com", "supavoice", "Supavoice")
+            .ok_or_else(|| anyhow!("Failed to get project directories"))?;
+        let data_dir = project_dirs.data_dir();
+        std::fs::create_dir_all(data_dir)?;
+
+        Self::open(data_dir.join("jobs.json"))
+    }
+
+    // A queue persisted at `path`, which needn't exist yet
+    pub fn open(path: PathBuf) -> Result<Self> {
+        let mut jobs: Vec<Job> = if path.exists() {
+            let content = std::fs::read_to_string(&path)?;
+            match serde_json::from_str(&content) {
+                Ok(jobs) => jobs,
+                Err(e) => {
+                    // Set aside rather than overwritten by the next save
+                    let backup = path.with_extension("json.bad");
+                    std::fs::rename(&path, &backup)?;
+                    eprintln!("⚠️  Job queue was unreadable ({}), moved to {}", e, backup.display());
+                    Vec::new()
+                }
+            }
+        } else {
+            Vec::new()
+        };
+
+        // Jobs interrupted by a quit start over
+        for job in jobs.iter_mut() {
+            if matches!(job.status, JobStatus::Running { .. }) {
+                job.set_status(JobStatus::Queued);
+            }
+        }
+
+        let (events, _) = broadcast::channel(256);
+        Ok(Self {
+            jobs: Mutex::new(jobs),
+            path,
+            running: Mutex::new(None),
+            holds: AtomicUsize::new(0),
+            wake: Notify::new(),
+            events,
+        })
+    }
+
+    pub fn subscribe(&self) -> broadcast::Receiver<JobEvent> {
+        self.events.subscribe()
+    }
+
+    pub fn jobs(&self) -> Vec<Job> {
+        self.jobs.lock().unwrap().clone()
+    }
+
+    // Folders are searched recursively for WAV files, the format Whisper reads
+    pub fn enqueue(&self, paths: &[PathBuf]) -> Result<Vec<Job>> {
+        let mut files = Vec::new();
+        for path in paths {
+            if path.is_dir() {
+                collect_audio_files(path, &mut files)?;
+            } else if path.is_file() {
+                files.push(path.clone());
+            } else {
+                bail!("No such file or folder: {}", path.display());
+            }
+        }
+        if files.is_empty() {
+            bail!("No WAV files found");
+        }
+
+        Ok(self.enqueue_files(files, None))
+    }
+
+    // Files already queued or running are skipped, so a file noticed twice by a
+    // watch folder is only transcribed once
+    pub fn enqueue_files(&self, files: Vec<PathBuf>, sidecars: Option<Sidecars>) -> Vec<Job> {
+        let added: Vec<Job> = {
+            let mut jobs = self.jobs.lock().unwrap();
+            let added: Vec<Job> = files
+                .into_iter()
+                .filter(|file| !jobs.iter().any(|job| &job.path == file && !job.status.is_finished()))
+                .map(|file| {
+                    let mut job = Job::new(new_job_id(), file);
+                    job.sidecars = sidecars.clone();
+                    job
+                })
+                .collect();
+            jobs.extend(added.iter().cloned());
+            added
+        };
+        if added.is_empty() {
+            return added;
+        }
+        self.save();
+
+        for job in &added {
+            self.emit(JobEvent::Updated(job.clone()));
+        }
+        self.wake.notify_one();
+        added
+    }
+
+    // For watch folders: a file whose job failed or was cancelled waits for the user to
+    // retry it, rather than being queued again on every start or folder change
+    pub fn enqueue_watched(&self, files: Vec<PathBuf>, sidecars: Sidecars) -> Vec<Job> {
+        let files: Vec<PathBuf> = {
+            let jobs = self.jobs.lock().unwrap();
+            files
+                .into_iter()
+                .filter(|file| {
+                    !jobs.iter().any(|job| {
+                        &job.path == file && matches!(job.status, JobStatus::Failed { .. } | JobStatus::Cancelled)
+                    })
+                })
+                .collect()
+        };
+        self.enqueue_files(files, Some(sidecars))
+    }
+
+    pub fn cancel(&self, id: &str) -> Result<()> {
+        // A running decode notices the flag and the worker marks the job cancelled
+        if let Some((running_id, cancel)) = self.running.lock().unwrap().as_ref() {
+            if running_id == id {
+                cancel.store(true, Ordering::Relaxed);
+                return Ok(());
+            }
+        }
+
+        self.update(id, |job| match job.status {
+            JobStatus::Queued => {
+                job.set_status(JobStatus::Cancelled);
+                Ok(())
+            }
+            _ => Err(anyhow!("Only queued or running jobs can be cancelled")),
+        })
+    }
+
+    pub fn retry(&self, id: &str) -> Result<()> {
+        self.update(id, |job| match job.status {
+            JobStatus::Failed { .. } | JobStatus::Cancelled => {
+                job.set_status(JobStatus::Queued);
+                Ok(())
+            }
+            _ => Err(anyhow!("Only failed or cancelled jobs can be retried")),
+        })?;
+        self.wake.notify_one();
+        Ok(())
+    }
+
+    pub fn remove(&self, id: &str) -> Result<()> {
+        {
+            let mut jobs = self.jobs.lock().unwrap();
+            let index = jobs
+                .iter()
+                .position(|job| job.id == id)
+                .ok_or_else(|| anyhow!("Job not found: {}", id))?;
+            if matches!(jobs[index].status, JobStatus::Running { .. }) {
+                bail!("Cancel the job before removing it");
+            }
+            jobs.remove(index);
+        }
+        self.save();
+        self.emit(JobEvent::Removed { id: id.to_string() });
+        Ok(())
+    }
+
+    pub fn clear_finished(&self) {
+        let removed: Vec<String> = {
+            let mut jobs = self.jobs.lock().unwrap();
+            let removed = jobs
+                .iter()
+                .filter(|job| job.status.is_finished())
+                .map(|job| job.id.clone())
+                .collect();
+            jobs.retain(|job| !job.status.is_finished());
+            removed
+        };
+        self.save();
+        for id in removed {
+            self.emit(JobEvent::Removed { id });
+        }
+    }
+
+    // Held while recording or transcribing live; a job already running finishes.
+    // Holds overlap (a new recording starts while the last one is transcribed), so
+    // jobs resume once every hold is dropped.
+    pub fn hold(self: &Arc<Self>) -> JobHold {
+        self.holds.fetch_add(1, Ordering::SeqCst);
+        JobHold(self.clone())
+    }
+
+    fn is_held(&self) -> bool {
+        self.holds.load(Ordering::SeqCst) > 0
+    }
+
+    // Processes queued jobs until the process exits
+    pub async fn run(self: Arc<Self>, backend: Arc<dyn ServerBackend>) {
+        loop {
+            let next = if self.is_held() {
+                None
+            } else {
+                self.start_next()
+            };
+            let Some((job, cancel)) = next else {
+                self.wake.notified().await;
+                continue;
+            };
+
+            eprintln!("📦 Transcribing job {} ({})", job.id, job.path.display());
+            let result = self.process(backend.as_ref(), &job, cancel.clone()).await;
+            self.finish(&job.id, result, &cancel);
+        }
+    }
+
+    fn start_next(&self) -> Option<(Job, Arc<AtomicBool>)> {
+        let job = {
+            let mut jobs = self.jobs.lock().unwrap();
+            let job = jobs.iter_mut().find(|job| job.status == JobStatus::Queued)?;
+            job.attempts += 1;
+            job.set_status(JobStatus::Running { progress: 0.0 });
+            job.clone()
+        };
+
+        let cancel = Arc::new(AtomicBool::new(false));
+        *self.running.lock().unwrap() = Some((job.id.clone(), cancel.clone()));
+        self.save();
+        self.emit(JobEvent::Updated(job.clone()));
+        Some((job, cancel))
+    }
+
+    async fn process(
+        self: &Arc<Self>,
+        backend: &dyn ServerBackend,
+        job: &Job,
+        cancel: Arc<AtomicBool>,
+    ) -> Result<(String, Vec<Segment>)> {
+        let prefs = backend.preferences().await;
+        // Batch jobs always decode locally: progress and cancellation come from Whisper
+        let transcriber = backend.transcriber(&prefs).await?;
+        transcriber.set_word_timestamps(prefs.word_timestamps);
+        let prompt = vocabulary_prompt(&prefs.custom_vocabulary);
+
+        let queue = self.clone();
+        let id = job.id.clone();
+        let control = DecodeControl {
+            on_progress: Some(Arc::new(move |progress| queue.report_progress(&id, progress))),
+            cancel: Some(cancel),
+        };
+        let audio_path = job.path.to_string_lossy().to_string();
+
+        // On the blocking pool so neither the UI nor live transcription waits on it
+        let decode_path = audio_path.clone();
+        let segments = tokio::task::spawn_blocking(move || {
+            transcriber.transcribe_segments_with_control(&decode_path, prompt.as_deref(), &control)
+        })
+        .await??;
+        let segments = pipeline::guard_segments(&prefs, &audio_path, segments).await;
+        let mut segments = pipeline::diarize_segments(&prefs, &audio_path, segments).await;
+        flag_words(&mut segments, prefs.confidence.threshold);
+
+        let processed = pipeline::postprocess_segments(&prefs, &prefs.custom_vocabulary, &segments);
+        if let Some(sidecars) = &job.sidecars {
+            write_sidecars(backend, &prefs, &job.path, sidecars, &processed.text, &segments).await?;
+        }
+        Ok((processed.text, segments))
+    }
+
+    fn report_progress(&self, id: &str, progress: f32) {
+        let job = {
+            let mut jobs = self.jobs.lock().unwrap();
+            let Some(job) = jobs.iter_mut().find(|job| job.id == id) else {
+                return;
+            };
+            // Whisper calls back often; only whole-percent changes are worth an event
+            match job.status {
+                JobStatus::Running { progress: previous } if previous.floor() != progress.floor() => {}
+                _ => return,
+            }
+            job.set_status(JobStatus::Running { progress });
+            job.clone()
+        };
+        // Progress isn't persisted; an interrupted job starts over anyway
+        self.emit(JobEvent::Updated(job));
+    }
+
+    fn finish(&self, id: &str, result: Result<(String, Vec<Segment>)>, cancel: &AtomicBool) {
+        *self.running.lock().unwrap() = None;
+
+        let status = match result {
+            _ if cancel.load(Ordering::Relaxed) => JobStatus::Cancelled,
+            Ok((text, segments)) => JobStatus::Completed { text, segments },
+            Err(e) => {
+                eprintln!("❌ Job {} failed: {:#}", id, e);
+                JobStatus::Failed {
+                    error: format!("{:#}", e),
+                }
+            }
+        };
+
+        let _ = self.update(id, |job| {
+            job.set_status(status);
+            Ok(())
+        });
+    }
+
+    fn update(&self, id: &str, change: impl FnOnce(&mut Job) -> Result<()>) -> Result<()> {
+        let job = {
+            let mut jobs = self.jobs.lock().unwrap();
+            let job = jobs
+                .iter_mut()
+                .find(|job| job.id == id)
+                .ok_or_else(|| anyhow!("Job not found: {}", id))?;
+            change(job)?;
+            job.clone()
+        };
+        self.save();
+        self.emit(JobEvent::Updated(job));
+        Ok(())
+    }
+
+    fn emit(&self, event: JobEvent) {
+        // No subscribers is fine
+        let _ = self.events.send(event);
+    }
+
+    fn save(&self) {
+        let json = match serde_json::to_string_pretty(&*self.jobs.lock().unwrap()) {
+            Ok(json) => json,
+            Err(e) => return eprintln!("⚠️  Failed to serialize jobs: {}", e),
+        };
+        if let Err(e) = std::fs::write(&self.path, json) {
+            eprintln!("⚠️  Failed to save jobs: {}", e);
+        }
+    }
+}
+
+pub(crate) fn collect_audio_files(dir: &Path, files: &mut Vec<PathBuf>) -> Result<()> {
+    let mut entries: Vec<PathBuf> = std::fs::read_dir(dir)?
+        .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+        .collect();
+    entries.sort();
+
+    for path in entries {
+        if path.is_dir() {
+            collect_audio_files(&path, files)?;
+        } else if is_wav(&path) {
+            files.push(path);
+        }
+    }
+    Ok(())
+}
+
+pub(crate) fn is_wav(path: &Path) -> bool {
+    path.extension()
+        .and_then(|extension| extension.to_str())
+        .is_some_and(|extension| extension.eq_ignore_ascii_case("wav"))
+}
+
+fn new_job_id() -> String {
+    rand::thread_rng()
+        .sample_iter(&Alphanumeric)
+        .take(12)
+        .map(char::from)
+        .collect()
+}
+
+pub struct JobHold(Arc<JobQueue>);
+
+impl Drop for JobHold {
+    fn drop(&mut self) {
+        if self.0.holds.fetch_sub(1, Ordering::SeqCst) == 1 {
+            self.0.wake.notify_one();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // A queue in its own temp file, removed when dropped
+    struct TestQueue {
+        queue: JobQueue,
+        path: PathBuf,
+    }
+
+    impl TestQueue {
+        fn new() -> Self {
+            let path = std::env::temp_dir().join(format!("supavoice-jobs-{}.json", new_job_id()));
+            Self {
+                queue: JobQueue::open(path.clone()).unwrap(),
+                path,
+            }
+        }
+
+        fn reopen(&self) -> JobQueue {
+            JobQueue::open(self.path.clone()).unwrap()
+        }
+
+        fn status(&self, id: &str) -> JobStatus {
+            self.queue.jobs().into_iter().find(|job| job.id == id).unwrap().status
+        }
+    }
+
+    impl Drop for TestQueue {
+        fn drop(&mut self) {
+            let _ = std::fs::remove_file(&self.path);
+        }
+    }
+
+    fn files(names: &[&str]) -> Vec<PathBuf> {
+        names.iter().map(PathBuf::from).collect()
+    }
+
+    #[test]
+    fn enqueue_skips_files_already_queued() {
+        let test = TestQueue::new();
+        let queue = &test.queue;
+
+        assert_eq!(queue.enqueue_files(files(&["a.wav", "b.wav"]), None).len(), 2);
+        let added = queue.enqueue_files(files(&["b.wav", "c.wav"]), None);
+        assert_eq!(added.len(), 1);
+        assert_eq!(added[0].path, PathBuf::from("c.wav"));
+
+        // Running counts as queued
+        let (running, _) = queue.start_next().unwrap();
+        assert!(queue.enqueue_files(vec![running.path], None).is_empty());
+        assert_eq!(queue.jobs().len(), 3);
+    }
+
+    #[test]
+    fn watch_folders_leave_stopped_jobs_to_retry() {
+        let test = TestQueue::new();
+        let queue = &test.queue;
+        let sidecars = Sidecars {
+            template: None,
+            subtitles: true,
+        };
+        queue.enqueue_watched(files(&["failed.wav", "cancelled.wav", "done.wav"]), sidecars.clone());
+
+        for (file, result) in [
+            ("failed.wav", Err(anyhow!("decode failed"))),
+            ("cancelled.wav", Err(anyhow!("cancelled"))),
+            ("done.wav", Ok(("done".to_string(), Vec::new()))),
+        ] {
+            let (job, cancel) = queue.start_next().unwrap();
+            assert_eq!(job.path, PathBuf::from(file));
+            cancel.store(file == "cancelled.wav", Ordering::Relaxed);
+            queue.finish(&job.id, result, &cancel);
+        }
+
+        // Completed files are only seen again once their transcript is deleted to redo them
+        let added = queue.enqueue_watched(files(&["failed.wav", "cancelled.wav", "done.wav"]), sidecars.clone());
+        assert_eq!(added.len(), 1);
+        assert_eq!(added[0].path, PathBuf::from("done.wav"));
+        assert_eq!(added[0].sidecars, Some(sidecars));
+    }
+
+    #[test]
+    fn cancel_and_retry() {
+        let test = TestQueue::new();
+        let queue = &test.queue;
+        let id = queue.enqueue_files(files(&["a.wav"]), None)[0].id.clone();
+
+        queue.cancel(&id).unwrap();
+        assert_eq!(test.status(&id), JobStatus::Cancelled);
+        assert!(queue.cancel(&id).is_err());
+
+        queue.retry(&id).unwrap();
+        assert_eq!(test.status(&id), JobStatus::Queued);
+        assert!(queue.retry(&id).is_err());
+
+        // A running job is cancelled through its flag, then marked by the worker
+        let (job, cancel) = queue.start_next().unwrap();
+        assert_eq!(job.attempts, 1);
+        queue.cancel(&id).unwrap();
+        assert!(cancel.load(Ordering::Relaxed));
+        assert!(matches!(test.status(&id), JobStatus::Running { .. }));
+        assert!(queue.remove(&id).is_err());
+        queue.finish(&id, Err(anyhow!("cancelled")), &cancel);
+        assert_eq!(test.status(&id), JobStatus::Cancelled);
+
+        queue.retry(&id).unwrap();
+        let (job, cancel) = queue.start_next().unwrap();
+        assert_eq!(job.attempts, 2);
+        queue.finish(&id, Err(anyhow!("decode failed")), &cancel);
+        assert_eq!(
+            test.status(&id),
+            JobStatus::Failed {
+                error: "decode failed".to_string()
+            }
+        );
+        assert!(queue.cancel(&id).is_err());
+
+        queue.retry(&id).unwrap();
+        let (_, cancel) = queue.start_next().unwrap();
+        queue.finish(&id, Ok(("done".to_string(), Vec::new())), &cancel);
+        assert!(queue.retry(&id).is_err());
+
+        queue.remove(&id).unwrap();
+        assert!(queue.jobs().is_empty());
+    }
+
+    #[test]
+    fn interrupted_jobs_are_queued_again_on_load() {
+        let test = TestQueue::new();
+        let queue = &test.queue;
+        let added = queue.enqueue_files(files(&["a.wav", "b.wav"]), None);
+        let (running, _) = queue.start_next().unwrap();
+        queue.cancel(&added[1].id).unwrap();
+
+        let reopened = test.reopen();
+        let status = |id: &str| reopened.jobs().into_iter().find(|job| job.id == id).unwrap().status;
+        assert_eq!(status(&running.id), JobStatus::Queued);
+        assert_eq!(status(&added[1].id), JobStatus::Cancelled);
+    }
+
+    #[test]
+    fn missing_file_opens_empty() {
+        let test = TestQueue::new();
+        assert!(test.queue.jobs().is_empty());
+        assert!(!test.path.exists());
+    }
+
+    #[test]
+    fn corrupt_file_is_set_aside() {
+        let test = TestQueue::new();
+        std::fs::write(&test.path, "[{\"id\": truncated").unwrap();
+
+        let reopened = test.reopen();
+        assert!(reopened.jobs().is_empty());
+        let backup = test.path.with_extension("json.bad");
+        assert_eq!(std::fs::read_to_string(&backup).unwrap(), "[{\"id\": truncated");
+        std::fs::remove_file(backup).unwrap();
+    }
+
+    #[test]
+    fn overlapping_holds() {
+        let test = TestQueue::new();
+        let queue = Arc::new(test.reopen());
+
+        let recording = queue.hold();
+        let transcribing = queue.hold();
+        assert!(queue.is_held());
+        // The last recording's transcription ends while a new recording runs
+        drop(transcribing);
+        assert!(queue.is_held());
+        drop(recording);
+        assert!(!queue.is_held());
+    }
+}
@@ -0,0 +1,48 @@
+use super::job::Sidecars;
+use crate::diarization::speaker_labels;
+use crate::formatting::StructuredNotes;
+use crate::pipeline;
+use crate::preferences::AppPreferences;
+use crate::server::ServerBackend;
+use crate::transcription::{to_srt, Segment};
+use anyhow::{Context, Result};
+use std::path::Path;
+
+// Writes `<name>.txt`, and optionally `<name>.srt` and `<name>.md`, beside the audio.
+// The transcript goes first so a formatting failure still leaves it on disk.
+pub(super) async fn write_sidecars(
+    backend: &dyn ServerBackend,
+    prefs: &AppPreferences,
+    audio_path: &Path,
+    sidecars: &Sidecars,
+    text: &str,
+    segments: &[Segment],
+) -> Result<()> {
+    write(&audio_path.with_extension("txt"), text).await?;
+
+    if sidecars.subtitles {
+        write(&audio_path.with_extension("srt"), &to_srt(segments)).await?;
+    }
+
+    if let Some(template_id) = &sidecars.template {
+        let template = pipeline::format_template(prefs, template_id)?.with_speakers(speaker_labels(segments, &[]));
+        let formatted =
+            pipeline::format_or_clean(prefs, &template, text, || backend.formatting_backend(prefs)).await?;
+
+        // JSON templates are rendered as Markdown lists
+        let markdown = if template.json_schema.is_some() {
+            serde_json::from_str::<StructuredNotes>(&formatted)?.to_markdown()
+        } else {
+            formatted
+        };
+        write(&audio_path.with_extension("md"), &markdown).await?;
+    }
+
+    Ok(())
+}
+
+async fn write(path: &Path, content: &str) -> Result<()> {
+    tokio::fs::write(path, format!("{}\n", content.trim_end()))
+        .await
+        .with_context(|| format!("Writing {}", path.display()))
+}
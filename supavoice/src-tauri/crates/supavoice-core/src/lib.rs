@@ -0,0 +1,14 @@
+pub mod audio;
+pub mod diarization;
+pub mod formatting;
+pub mod jobs;
+pub mod models;
+pub mod output;
+pub mod pipeline;
+pub mod postprocess;
+pub mod preferences;
+pub mod secrets;
+pub mod server;
+pub mod transcription;
+pub mod vocabulary;
+pub mod watch;
@@ -2,12 +2,27 @@ use super::registry::ModelRegistry;
 use super::types::ModelStatus;
 use anyhow::Result;
 use reqwest::Client;
+use serde::Serialize;
 use sha2::{Digest, Sha256};
 use std::path::PathBuf;
-use tauri::Emitter;
 use tokio::fs::File;
 use tokio::io::AsyncWriteExt;
 
+// Reported while downloading; the app forwards these as events, the CLI prints them
+#[derive(Serialize, Clone, Debug)]
+#[serde(untagged)]
+pub enum DownloadEvent {
+    Progress {
+        model_id: String,
+        progress: f32,
+        bytes: u64,
+        total: u64,
+    },
+    Complete {
+        model_id: String,
+    },
+}
+
 pub struct ModelDownloader {
     client: Client,
     registry: std::sync::Arc<ModelRegistry>,
@@ -24,7 +39,7 @@ impl ModelDownloader {
     pub async fn download_model(
         &self,
         model_id: String,
-        app_handle: tauri::AppHandle,
+        on_event: impl Fn(DownloadEvent) + Send + Sync,
     ) -> Result<()> {
         use super::types::ModelKind;
 
@@ -38,7 +53,7 @@ impl ModelDownloader {
         }
 
         // Download model file (GGML/GGUF format)
-        self.download_file(&download_url, &model_path, &model_id, &app_handle).await?;
+        self.download_file(&download_url, &model_path, &model_id, &on_event).await?;
 
         // Update registry
         self.registry
@@ -46,13 +61,7 @@ impl ModelDownloader {
             .await?;
         self.registry.update_model_path(&model_id, model_path).await?;
 
-        // Emit completion event
-        app_handle.emit(
-            "download_complete",
-            serde_json::json!({
-                "model_id": model_id,
-            }),
-        )?;
+        on_event(DownloadEvent::Complete { model_id });
 
         Ok(())
     }
@@ -62,7 +71,7 @@ impl ModelDownloader {
         url: &str,
         file_path: &PathBuf,
         model_id: &str,
-        app_handle: &tauri::AppHandle,
+        on_event: &(impl Fn(DownloadEvent) + Send + Sync),
     ) -> Result<()> {
         // Download to .part file first
         let part_path = file_path.with_extension("part");
@@ -100,16 +109,12 @@ impl ModelDownloader {
                 )
                 .await?;
 
-            // Emit progress event
-            app_handle.emit(
-                "download_progress",
-                serde_json::json!({
-                    "model_id": model_id,
-                    "progress": progress,
-                    "bytes": downloaded,
-                    "total": total_size,
-                }),
-            )?;
+            on_event(DownloadEvent::Progress {
+                model_id: model_id.to_string(),
+                progress,
+                bytes: downloaded,
+                total: total_size,
+            });
         }
 
         file.flush().await?;
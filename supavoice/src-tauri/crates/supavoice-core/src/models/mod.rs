@@ -2,6 +2,6 @@ pub mod downloader;
 pub mod registry;
 pub mod types;
 
-pub use downloader::ModelDownloader;
+pub use downloader::{DownloadEvent, ModelDownloader};
 pub use registry::ModelRegistry;
 pub use types::{ModelKind, ModelRecord, ModelStatus};
@@ -0,0 +1,71 @@
+use super::platform::{on_path, DisplayServer};
+use anyhow::{Context, Result};
+use std::io::Write;
+use std::process::{Command, Stdio};
+
+pub trait Clipboard: Send {
+    // None when the clipboard is empty or holds something other than text
+    fn get_text(&mut self) -> Option<String>;
+    fn set_text(&mut self, text: &str) -> Result<()>;
+}
+
+// arboard talks to X11 only, so native Wayland apps need wl-clipboard
+pub enum SystemClipboard {
+    Arboard(arboard::Clipboard),
+    WlClipboard,
+}
+
+impl SystemClipboard {
+    pub fn new() -> Result<Self> {
+        if DisplayServer::detect() == DisplayServer::Wayland
+            && on_path("wl-copy")
+            && on_path("wl-paste")
+        {
+            return Ok(SystemClipboard::WlClipboard);
+        }
+        let clipboard = arboard::Clipboard::new().context("Failed to open clipboard")?;
+        Ok(SystemClipboard::Arboard(clipboard))
+    }
+}
+
+impl Clipboard for SystemClipboard {
+    fn get_text(&mut self) -> Option<String> {
+        match self {
+            SystemClipboard::Arboard(clipboard) => clipboard.get_text().ok(),
+            SystemClipboard::WlClipboard => {
+                let output = Command::new("wl-paste")
+                    .args(["--no-newline", "--type", "text"])
+                    .output()
+                    .ok()?;
+                output
+                    .status
+                    .success()
+                    .then(|| String::from_utf8_lossy(&output.stdout).to_string())
+            }
+        }
+    }
+
+    fn set_text(&mut self, text: &str) -> Result<()> {
+        match self {
+            SystemClipboard::Arboard(clipboard) => clipboard
+                .set_text(text)
+                .context("Failed to write clipboard"),
+            SystemClipboard::WlClipboard => {
+                let mut child = Command::new("wl-copy")
+                    .stdin(Stdio::piped())
+                    .spawn()
+                    .context("Failed to run wl-copy")?;
+                child
+                    .stdin
+                    .take()
+                    .context("wl-copy has no stdin")?
+                    .write_all(text.as_bytes())
+                    .context("Failed to write to wl-copy")?;
+                // wl-copy forks a server that keeps serving the selection, so this returns quickly
+                let status = child.wait().context("Failed to wait for wl-copy")?;
+                anyhow::ensure!(status.success(), "wl-copy exited with {}", status);
+                Ok(())
+            }
+        }
+    }
+}
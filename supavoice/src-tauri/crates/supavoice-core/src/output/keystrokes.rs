@@ -0,0 +1,131 @@
+use super::platform::{on_path, DisplayServer};
+use anyhow::{Context, Result};
+use std::process::Command;
+
+// Synthetic keyboard input sent to whichever app has focus
+pub trait Keystrokes: Send + Sync {
+    // Cmd+V on macOS, Ctrl+V elsewhere
+    fn paste(&self) -> Result<()>;
+    fn type_text(&self, text: &str) -> Result<()>;
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SystemKeystrokes {
+    // System Events via osascript; needs the Accessibility permission
+    AppleScript,
+    // SendKeys via PowerShell
+    PowerShell,
+    Xdotool,
+    Wtype,
+    // Works on any Linux session but needs the ydotoold daemon
+    Ydotool,
+}
+
+impl SystemKeystrokes {
+    pub fn detect() -> Result<Self> {
+        if cfg!(target_os = "macos") {
+            return Ok(SystemKeystrokes::AppleScript);
+        }
+        if cfg!(target_os = "windows") {
+            return Ok(SystemKeystrokes::PowerShell);
+        }
+
+        let helper = match DisplayServer::detect() {
+            DisplayServer::Wayland if on_path("wtype") => Some(SystemKeystrokes::Wtype),
+            // XWayland apps still accept xdotool input
+            DisplayServer::X11 | DisplayServer::Wayland if on_path("xdotool") => {
+                Some(SystemKeystrokes::Xdotool)
+            }
+            _ if on_path("ydotool") => Some(SystemKeystrokes::Ydotool),
+            _ => None,
+        };
+        helper
+            .context("No keystroke helper found; install xdotool (X11), wtype or ydotool (Wayland)")
+    }
+
+    fn run(&self, program: &str, args: &[&str]) -> Result<()> {
+        let output = Command::new(program)
+            .args(args)
+            .output()
+            .with_context(|| format!("Failed to run {}", program))?;
+        anyhow::ensure!(
+            output.status.success(),
+            "{} failed: {}",
+            program,
+            String::from_utf8_lossy(&output.stderr).trim()
+        );
+        Ok(())
+    }
+}
+
+impl Keystrokes for SystemKeystrokes {
+    fn paste(&self) -> Result<()> {
+        match self {
+            SystemKeystrokes::AppleScript => self.run(
+                "osascript",
+                &["-e", "tell application \"System Events\" to keystroke \"v\" using command down"],
+            ),
+            SystemKeystrokes::PowerShell => self.run(
+                "powershell",
+                &[
+                    "-NoProfile",
+                    "-Command",
+                    "Add-Type -AssemblyName System.Windows.Forms; [System.Windows.Forms.SendKeys]::SendWait('^v')",
+                ],
+            ),
+            SystemKeystrokes::Xdotool => self.run("xdotool", &["key", "--clearmodifiers", "ctrl+v"]),
+            SystemKeystrokes::Wtype => self.run("wtype", &["-M", "ctrl", "v", "-m", "ctrl"]),
+            // Linux input event codes: 29 = left ctrl, 47 = v
+            SystemKeystrokes::Ydotool => self.run("ydotool", &["key", "29:1", "47:1", "47:0", "29:0"]),
+        }
+    }
+
+    fn type_text(&self, text: &str) -> Result<()> {
+        match self {
+            // The text goes in as an argument so it never needs AppleScript quoting
+            SystemKeystrokes::AppleScript => self.run(
+                "osascript",
+                &[
+                    "-e",
+                    "on run argv",
+                    "-e",
+                    "tell application \"System Events\" to keystroke (item 1 of argv)",
+                    "-e",
+                    "end run",
+                    "--",
+                    text,
+                ],
+            ),
+            SystemKeystrokes::PowerShell => {
+                let script = format!(
+                    "Add-Type -AssemblyName System.Windows.Forms; [System.Windows.Forms.SendKeys]::SendWait('{}')",
+                    send_keys_escape(text).replace('\'', "''")
+                );
+                self.run("powershell", &["-NoProfile", "-Command", &script])
+            }
+            SystemKeystrokes::Xdotool => {
+                self.run("xdotool", &["type", "--clearmodifiers", "--", text])
+            }
+            SystemKeystrokes::Wtype => self.run("wtype", &["--", text]),
+            SystemKeystrokes::Ydotool => self.run("ydotool", &["type", "--", text]),
+        }
+    }
+}
+
+// SendKeys treats + ^ % ~ ( ) { } [ ] as commands
+fn send_keys_escape(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '+' | '^' | '%' | '~' | '(' | ')' | '{' | '}' | '[' | ']' => {
+                escaped.push('{');
+                escaped.push(c);
+                escaped.push('}');
+            }
+            '\n' => escaped.push_str("{ENTER}"),
+            '\r' => {}
+            _ => escaped.push(c),
+        }
+    }
+    escaped
+}
@@ -0,0 +1,6 @@
+pub mod clipboard;
+pub mod keystrokes;
+pub mod platform;
+pub mod sink;
+
+pub use sink::{deliver, system_sink, OutputMode, OutputSink};
@@ -0,0 +1,31 @@
+use std::env;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum DisplayServer {
+    Wayland,
+    X11,
+    // macOS, Windows, or a Linux session without a display
+    Other,
+}
+
+impl DisplayServer {
+    pub fn detect() -> Self {
+        if !cfg!(target_os = "linux") {
+            return DisplayServer::Other;
+        }
+        if env::var_os("WAYLAND_DISPLAY").is_some() {
+            DisplayServer::Wayland
+        } else if env::var_os("DISPLAY").is_some() {
+            DisplayServer::X11
+        } else {
+            DisplayServer::Other
+        }
+    }
+}
+
+// Whether a helper binary like xdotool or wtype is installed
+pub fn on_path(binary: &str) -> bool {
+    env::var_os("PATH")
+        .map(|path| env::split_paths(&path).any(|dir| dir.join(binary).is_file()))
+        .unwrap_or(false)
+}
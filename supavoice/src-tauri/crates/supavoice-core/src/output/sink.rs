@@ -0,0 +1,316 @@
+use super::clipboard::{Clipboard, SystemClipboard};
+use super::keystrokes::{Keystrokes, SystemKeystrokes};
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use std::sync::Mutex;
+use std::thread;
+use std::time::Duration;
+
+// Where a finished transcript or formatted text goes besides the overlay
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum OutputMode {
+    // Only shown in the overlay
+    #[default]
+    Overlay,
+    Clipboard,
+    // Pasted into the previously focused app, then the clipboard is restored
+    Paste,
+    // Typed out as keystrokes; slower, but leaves the clipboard alone
+    Type,
+}
+
+impl OutputMode {
+    // Paste and type go to the focused app, so the overlay has to give focus back first
+    pub fn needs_focus(self) -> bool {
+        matches!(self, OutputMode::Paste | OutputMode::Type)
+    }
+}
+
+pub trait OutputSink: Send + Sync {
+    fn name(&self) -> &'static str;
+    fn deliver(&self, text: &str) -> Result<()>;
+}
+
+pub struct ClipboardSink<C: Clipboard> {
+    clipboard: Mutex<C>,
+}
+
+impl<C: Clipboard> ClipboardSink<C> {
+    pub fn new(clipboard: C) -> Self {
+        Self {
+            clipboard: Mutex::new(clipboard),
+        }
+    }
+}
+
+impl<C: Clipboard> OutputSink for ClipboardSink<C> {
+    fn name(&self) -> &'static str {
+        "clipboard"
+    }
+
+    fn deliver(&self, text: &str) -> Result<()> {
+        self.clipboard.lock().unwrap().set_text(text)
+    }
+}
+
+pub struct PasteSink<C: Clipboard, K: Keystrokes> {
+    clipboard: Mutex<C>,
+    keystrokes: K,
+    // The target app reads the clipboard asynchronously after the paste keystroke
+    restore_delay: Duration,
+}
+
+impl<C: Clipboard, K: Keystrokes> PasteSink<C, K> {
+    pub fn new(clipboard: C, keystrokes: K, restore_delay: Duration) -> Self {
+        Self {
+            clipboard: Mutex::new(clipboard),
+            keystrokes,
+            restore_delay,
+        }
+    }
+}
+
+impl<C: Clipboard, K: Keystrokes> OutputSink for PasteSink<C, K> {
+    fn name(&self) -> &'static str {
+        "paste"
+    }
+
+    fn deliver(&self, text: &str) -> Result<()> {
+        let mut clipboard = self.clipboard.lock().unwrap();
+        let previous = clipboard.get_text();
+
+        clipboard.set_text(text)?;
+        let pasted = self.keystrokes.paste();
+        thread::sleep(self.restore_delay);
+
+        // Don't clobber something the user copied in the meantime
+        if let Some(previous) = previous {
+            if clipboard.get_text().as_deref() == Some(text) {
+                clipboard.set_text(&previous)?;
+            }
+        }
+        pasted
+    }
+}
+
+pub struct TypeSink<K: Keystrokes> {
+    keystrokes: K,
+}
+
+impl<K: Keystrokes> TypeSink<K> {
+    pub fn new(keystrokes: K) -> Self {
+        Self { keystrokes }
+    }
+}
+
+impl<K: Keystrokes> OutputSink for TypeSink<K> {
+    fn name(&self) -> &'static str {
+        "type"
+    }
+
+    fn deliver(&self, text: &str) -> Result<()> {
+        self.keystrokes.type_text(text)
+    }
+}
+
+// None for the overlay-only mode
+pub fn system_sink(mode: OutputMode) -> Result<Option<Box<dyn OutputSink>>> {
+    let sink: Box<dyn OutputSink> = match mode {
+        OutputMode::Overlay => return Ok(None),
+        OutputMode::Clipboard => Box::new(ClipboardSink::new(SystemClipboard::new()?)),
+        OutputMode::Paste => Box::new(PasteSink::new(
+            SystemClipboard::new()?,
+            SystemKeystrokes::detect()?,
+            Duration::from_millis(300),
+        )),
+        OutputMode::Type => Box::new(TypeSink::new(SystemKeystrokes::detect()?)),
+    };
+    Ok(Some(sink))
+}
+
+// Empty results (e.g. silence) are never pasted or typed
+pub fn deliver(sink: &dyn OutputSink, text: &str) -> Result<bool> {
+    if text.trim().is_empty() {
+        return Ok(false);
+    }
+    sink.deliver(text)?;
+    Ok(true)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Arc;
+
+    #[derive(Clone, Default)]
+    struct MockClipboard {
+        text: Arc<Mutex<Option<String>>>,
+    }
+
+    impl Clipboard for MockClipboard {
+        fn get_text(&mut self) -> Option<String> {
+            self.text.lock().unwrap().clone()
+        }
+
+        fn set_text(&mut self, text: &str) -> Result<()> {
+            *self.text.lock().unwrap() = Some(text.to_string());
+            Ok(())
+        }
+    }
+
+    // Records what was sent and what the clipboard held at paste time
+    #[derive(Clone, Default)]
+    struct MockKeystrokes {
+        clipboard: MockClipboard,
+        events: Arc<Mutex<Vec<String>>>,
+        // Simulates the user copying something while the paste is in flight
+        copy_during_paste: Option<&'static str>,
+        fail: bool,
+    }
+
+    impl Keystrokes for MockKeystrokes {
+        fn paste(&self) -> Result<()> {
+            let pasted = self
+                .clipboard
+                .text
+                .lock()
+                .unwrap()
+                .clone()
+                .unwrap_or_default();
+            self.events
+                .lock()
+                .unwrap()
+                .push(format!("paste:{}", pasted));
+            if let Some(copied) = self.copy_during_paste {
+                *self.clipboard.text.lock().unwrap() = Some(copied.to_string());
+            }
+            anyhow::ensure!(!self.fail, "no accessibility permission");
+            Ok(())
+        }
+
+        fn type_text(&self, text: &str) -> Result<()> {
+            self.events.lock().unwrap().push(format!("type:{}", text));
+            anyhow::ensure!(!self.fail, "no accessibility permission");
+            Ok(())
+        }
+    }
+
+    // Captures delivered text without touching the system
+    #[derive(Default)]
+    struct MockSink {
+        delivered: Mutex<Vec<String>>,
+    }
+
+    impl OutputSink for MockSink {
+        fn name(&self) -> &'static str {
+            "mock"
+        }
+
+        fn deliver(&self, text: &str) -> Result<()> {
+            self.delivered.lock().unwrap().push(text.to_string());
+            Ok(())
+        }
+    }
+
+    fn paste_sink(keystrokes: &MockKeystrokes) -> PasteSink<MockClipboard, MockKeystrokes> {
+        PasteSink::new(
+            keystrokes.clipboard.clone(),
+            keystrokes.clone(),
+            Duration::ZERO,
+        )
+    }
+
+    #[test]
+    fn paste_restores_previous_clipboard() {
+        let keystrokes = MockKeystrokes::default();
+        *keystrokes.clipboard.text.lock().unwrap() = Some("copied earlier".to_string());
+
+        paste_sink(&keystrokes).deliver("Hello world").unwrap();
+
+        assert_eq!(
+            *keystrokes.events.lock().unwrap(),
+            vec!["paste:Hello world"]
+        );
+        assert_eq!(
+            keystrokes.clipboard.text.lock().unwrap().as_deref(),
+            Some("copied earlier")
+        );
+    }
+
+    #[test]
+    fn paste_keeps_text_copied_in_the_meantime() {
+        let keystrokes = MockKeystrokes {
+            copy_during_paste: Some("new copy"),
+            ..Default::default()
+        };
+        *keystrokes.clipboard.text.lock().unwrap() = Some("copied earlier".to_string());
+
+        paste_sink(&keystrokes).deliver("Hello world").unwrap();
+
+        assert_eq!(
+            keystrokes.clipboard.text.lock().unwrap().as_deref(),
+            Some("new copy")
+        );
+    }
+
+    #[test]
+    fn paste_with_empty_clipboard_leaves_result() {
+        let keystrokes = MockKeystrokes::default();
+
+        paste_sink(&keystrokes).deliver("Hello world").unwrap();
+
+        assert_eq!(
+            keystrokes.clipboard.text.lock().unwrap().as_deref(),
+            Some("Hello world")
+        );
+    }
+
+    #[test]
+    fn failed_paste_still_restores_clipboard() {
+        let keystrokes = MockKeystrokes {
+            fail: true,
+            ..Default::default()
+        };
+        *keystrokes.clipboard.text.lock().unwrap() = Some("copied earlier".to_string());
+
+        assert!(paste_sink(&keystrokes).deliver("Hello world").is_err());
+        assert_eq!(
+            keystrokes.clipboard.text.lock().unwrap().as_deref(),
+            Some("copied earlier")
+        );
+    }
+
+    #[test]
+    fn clipboard_and_type_sinks() {
+        let keystrokes = MockKeystrokes::default();
+
+        ClipboardSink::new(keystrokes.clipboard.clone())
+            .deliver("to clipboard")
+            .unwrap();
+        TypeSink::new(keystrokes.clone()).deliver("typed").unwrap();
+
+        assert_eq!(
+            keystrokes.clipboard.text.lock().unwrap().as_deref(),
+            Some("to clipboard")
+        );
+        assert_eq!(*keystrokes.events.lock().unwrap(), vec!["type:typed"]);
+    }
+
+    #[test]
+    fn empty_results_are_not_delivered() {
+        let sink = MockSink::default();
+
+        assert!(!deliver(&sink, "  \n").unwrap());
+        assert!(deliver(&sink, "Hello").unwrap());
+
+        assert_eq!(*sink.delivered.lock().unwrap(), vec!["Hello"]);
+    }
+
+    #[test]
+    fn overlay_mode_has_no_sink() {
+        assert!(system_sink(OutputMode::Overlay).unwrap().is_none());
+        assert!(!OutputMode::Clipboard.needs_focus());
+        assert!(OutputMode::Paste.needs_focus());
+    }
+}
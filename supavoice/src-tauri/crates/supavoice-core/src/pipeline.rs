@@ -0,0 +1,412 @@
+use crate::audio::load_wav_mono;
+use crate::diarization::{diarize, speaker_label, speaker_labels, speaker_turns};
+use crate::formatting::{format_with_template, parse_structured_notes, FormatTemplate, FormattingBackend};
+use crate::models::{ModelKind, ModelRegistry};
+use crate::postprocess::snippets::ExpandedSnippet;
+use crate::postprocess::{
+    clean_segments, clean_text, interpret_segments, CleanupLevel, CommandSet, CorrectedTranscript, ItnRuleSet,
+    SnippetExpander, VocabularyCorrector,
+};
+use crate::preferences::{AppPreferences, TranscriptionBackendPreference};
+use crate::secrets;
+use crate::transcription::{
+    filter_hallucinations, flag_words, GuardAudio, OpenAiCompatibleTranscriber, Segment, Transcriber, WhisperTranscriber,
+    LOCAL_LANGUAGE,
+};
+use crate::vocabulary::VocabularyEntry;
+use anyhow::{anyhow, Result};
+use serde::Serialize;
+use std::future::Future;
+use std::path::PathBuf;
+use std::sync::Arc;
+
+// Shared steps of the app and CLI: model selection, transcription with remote fallback,
+// post-processing and formatting
+
+pub async fn whisper_model_path(registry: &ModelRegistry, prefs: &AppPreferences) -> Result<PathBuf> {
+    let model_id = if let Some(preferred_model) = prefs.active_whisper_model.clone() {
+        // Use user's preferred model if it's installed
+        if let Ok(model) = registry.get_model(&preferred_model).await {
+            if model.path.is_some() {
+                preferred_model
+            } else {
+                return Err(anyhow!("Selected model '{}' is not installed", preferred_model));
+            }
+        } else {
+            return Err(anyhow!("Selected model '{}' not found", preferred_model));
+        }
+    } else {
+        // Auto-select: Priority order: whisper-base-en (fastest), small-en, small (multilingual)
+        if let Ok(model) = registry.get_model("whisper-base-en").await {
+            if model.path.is_some() {
+                "whisper-base-en".to_string()
+            } else if let Ok(model) = registry.get_model("whisper-small-en").await {
+                if model.path.is_some() {
+                    "whisper-small-en".to_string()
+                } else {
+                    "whisper-small".to_string()
+                }
+            } else {
+                "whisper-small".to_string()
+            }
+        } else {
+            "whisper-base-en".to_string()
+        }
+    };
+
+    let model = registry.get_model(&model_id).await?;
+    model.path.ok_or_else(|| anyhow!("Model not installed"))
+}
+
+pub async fn llm_model_path(registry: &ModelRegistry, prefs: &AppPreferences) -> Result<PathBuf> {
+    let model_id = if let Some(preferred_model) = prefs.active_llm_model.clone() {
+        // Use user's preferred model if it's installed
+        if let Ok(model) = registry.get_model(&preferred_model).await {
+            if model.path.is_some() {
+                preferred_model
+            } else {
+                return Err(anyhow!("Selected LLM model '{}' is not installed", preferred_model));
+            }
+        } else {
+            return Err(anyhow!("Selected LLM model '{}' not found", preferred_model));
+        }
+    } else {
+        // Auto-select: Priority order: gemma-2-2b-instruct > qwen2-1.5b-instruct
+        let mut installed = None;
+        for id in ["gemma-2-2b-instruct", "qwen2-1.5b-instruct"] {
+            if let Ok(model) = registry.get_model(id).await {
+                if model.path.is_some() {
+                    installed = Some(id.to_string());
+                    break;
+                }
+            }
+        }
+        installed.ok_or_else(|| {
+            anyhow!("No LLM model installed. Please install Gemma or Qwen model from Settings.")
+        })?
+    };
+
+    let model = registry.get_model(&model_id).await?;
+    model.path.ok_or_else(|| anyhow!("Model not installed"))
+}
+
+// Transcribes with the preferred backend; `local` loads (or reuses) the Whisper model
+pub async fn transcribe_segments<F, Fut>(
+    prefs: &AppPreferences,
+    audio_path: &str,
+    prompt: Option<&str>,
+    local: F,
+) -> Result<Vec<Segment>>
+where
+    F: Fn() -> Fut,
+    Fut: Future<Output = Result<Arc<WhisperTranscriber>>>,
+{
+    // The model is shared, so the word-timestamp preference is applied on every use
+    let local = || async {
+        let transcriber = local().await?;
+        transcriber.set_word_timestamps(prefs.word_timestamps);
+        Ok::<_, anyhow::Error>(transcriber)
+    };
+
+    let segments = match &prefs.transcription_backend {
+        TranscriptionBackendPreference::Local => local().await?.transcribe_segments(audio_path, prompt).await?,
+        TranscriptionBackendPreference::OpenAiCompatible {
+            base_url,
+            model,
+            fallback_to_local,
+            language,
+        } => {
+            let api_key = secrets::get_api_key(secrets::TRANSCRIPTION_API_KEY)?;
+            let remote = OpenAiCompatibleTranscriber::new(base_url, api_key, model).with_language(language.clone());
+
+            match remote.transcribe_segments(audio_path, prompt).await {
+                Ok(segments) => segments,
+                Err(e) if *fallback_to_local => {
+                    eprintln!("⚠️  Remote transcription failed ({}), falling back to local Whisper", e);
+                    local().await?.transcribe_segments(audio_path, prompt).await?
+                }
+                Err(e) => return Err(e),
+            }
+        }
+    };
+
+    let segments = guard_segments(prefs, audio_path, segments).await;
+    let mut segments = diarize_segments(prefs, audio_path, segments).await;
+    flag_words(&mut segments, prefs.confidence.threshold);
+    Ok(segments)
+}
+
+// The language transcripts are decoded in; None when a hosted service detects it
+pub fn transcription_language(prefs: &AppPreferences) -> Option<String> {
+    match &prefs.transcription_backend {
+        TranscriptionBackendPreference::Local => Some(LOCAL_LANGUAGE.to_string()),
+        TranscriptionBackendPreference::OpenAiCompatible { language, .. } => language.clone(),
+    }
+}
+
+// Drops text Whisper made up on silence and music. The recording is only read for the
+// silence guard; if it can't be, the other guards still run.
+pub async fn guard_segments(prefs: &AppPreferences, audio_path: &str, segments: Vec<Segment>) -> Vec<Segment> {
+    let guards = &prefs.hallucination_guards;
+    let audio = match guards.silence_threshold {
+        Some(_) => {
+            let audio_path = audio_path.to_string();
+            let result = tokio::task::spawn_blocking(move || load_wav_mono(&audio_path))
+                .await
+                .map_err(anyhow::Error::from)
+                .and_then(|result| result);
+            match result {
+                Ok(audio) => Some(audio),
+                Err(e) => {
+                    eprintln!("⚠️  Silence check skipped: {}", e);
+                    None
+                }
+            }
+        }
+        None => None,
+    };
+
+    let audio = audio.as_ref().map(|(samples, sample_rate)| GuardAudio {
+        samples,
+        sample_rate: *sample_rate,
+    });
+    filter_hallucinations(segments, audio.as_ref(), guards)
+}
+
+// Labels segments with speakers when diarization is enabled. A failure only loses
+// the labels, never the transcript.
+pub async fn diarize_segments(prefs: &AppPreferences, audio_path: &str, segments: Vec<Segment>) -> Vec<Segment> {
+    if !prefs.diarization.enabled {
+        return segments;
+    }
+
+    let options = prefs.diarization.clone();
+    let audio_path = audio_path.to_string();
+    let mut labelled = segments.clone();
+    let result = tokio::task::spawn_blocking(move || {
+        let (samples, sample_rate) = load_wav_mono(&audio_path)?;
+        diarize(&samples, sample_rate, &mut labelled, &options);
+        anyhow::Ok(labelled)
+    })
+    .await
+    .map_err(anyhow::Error::from)
+    .and_then(|result| result);
+
+    match result {
+        Ok(labelled) => {
+            let speakers = speaker_labels(&labelled, &[]).len();
+            if speakers > 1 {
+                eprintln!("🗣️  Found {} speakers", speakers);
+            }
+            labelled
+        }
+        Err(e) => {
+            eprintln!("⚠️  Diarization failed: {}", e);
+            segments
+        }
+    }
+}
+
+// The model for re-running low-confidence spans: the preferred review model, or
+// the largest installed Whisper model larger than the active one
+pub async fn review_model_path(registry: &ModelRegistry, prefs: &AppPreferences) -> Result<PathBuf> {
+    if let Some(model_id) = &prefs.confidence.review_model {
+        let model = registry.get_model(model_id).await?;
+        return model
+            .path
+            .ok_or_else(|| anyhow!("Review model '{}' is not installed", model_id));
+    }
+
+    let installed: Vec<_> = registry
+        .list_models()
+        .await?
+        .into_iter()
+        .filter(|model| model.kind == ModelKind::Whisper && model.path.is_some())
+        .collect();
+
+    // Re-decoding with the model that wrote the transcript would give the same words
+    let active_path = whisper_model_path(registry, prefs).await.ok();
+    let active_size = installed
+        .iter()
+        .find(|model| active_path.is_some() && model.path == active_path)
+        .map_or(0, |model| model.size_mb);
+
+    installed
+        .into_iter()
+        .filter(|model| model.size_mb > active_size)
+        .max_by_key(|model| model.size_mb)
+        .and_then(|model| model.path)
+        .ok_or_else(|| anyhow!("No Whisper model larger than the active one is installed"))
+}
+
+// Re-decodes `start..end` of a recording with a fresh transcriber, typically the
+// larger review model. Loading it takes a while, but it's only used on request.
+pub async fn retranscribe_range(
+    model_path: PathBuf,
+    prefs: &AppPreferences,
+    audio_path: &str,
+    start: f64,
+    end: f64,
+    prompt: Option<&str>,
+) -> Result<Vec<Segment>> {
+    let decode_path = audio_path.to_string();
+    let prompt = prompt.map(str::to_string);
+    let word_timestamps = prefs.word_timestamps;
+    let segments = tokio::task::spawn_blocking(move || {
+        let transcriber = WhisperTranscriber::new(model_path)?;
+        transcriber.set_word_timestamps(word_timestamps);
+        transcriber.transcribe_range(&decode_path, start, end, prompt.as_deref())
+    })
+    .await??;
+
+    let mut segments = guard_segments(prefs, audio_path, segments).await;
+    flag_words(&mut segments, prefs.confidence.threshold);
+    Ok(segments)
+}
+
+#[derive(Serialize, Clone, Debug)]
+pub struct ProcessedTranscript {
+    pub text: String,
+    pub snippets: Vec<ExpandedSnippet>,
+    pub corrected: CorrectedTranscript,
+}
+
+// Everything between Whisper and the user: clean-up, dictation commands, inverse text
+// normalization, snippets and vocabulary correction
+pub fn postprocess_segments(
+    prefs: &AppPreferences,
+    vocabulary: &[VocabularyEntry],
+    segments: &[Segment],
+) -> ProcessedTranscript {
+    postprocess_speaker_segments(prefs, vocabulary, segments, &[])
+}
+
+// Diarized recordings with more than one speaker become a paragraph per turn, each
+// starting with the speaker's label; `speaker_names` renames speakers by index
+pub fn postprocess_speaker_segments(
+    prefs: &AppPreferences,
+    vocabulary: &[VocabularyEntry],
+    segments: &[Segment],
+    speaker_names: &[String],
+) -> ProcessedTranscript {
+    let turns = speaker_turns(segments);
+    let result = if turns.is_empty() {
+        clean_and_interpret(prefs, segments)
+    } else {
+        // Turns are cleaned separately so one speaker's words never merge into another's
+        turns
+            .iter()
+            .map(|turn| {
+                format!(
+                    "{}: {}",
+                    speaker_label(turn.speaker, speaker_names),
+                    clean_and_interpret(prefs, &segments[turn.segments.clone()])
+                )
+            })
+            .collect::<Vec<_>>()
+            .join("\n\n")
+    };
+
+    // Expand spoken snippet triggers ("insert my signature") before formatting, and
+    // before ITN so a trigger with a number ("signature ten") still matches
+    let mut expansion = SnippetExpander::new(&prefs.snippets).expand(&result);
+
+    // Written forms for spoken numbers, dates and amounts ("twenty dollars" -> "$20")
+    let itn = &prefs.inverse_text_normalization;
+    if let Some(rules) = ItnRuleSet::for_language(&itn.language).filter(|_| itn.enabled) {
+        let (text, ranges) = rules.normalize_except(&expansion.text, &expansion.ranges());
+        expansion.text = text;
+        for (snippet, range) in expansion.expanded.iter_mut().zip(ranges) {
+            snippet.start = range.start;
+            snippet.end = range.end;
+        }
+    }
+
+    // The initial prompt only biases Whisper; fix remaining near-misses deterministically.
+    // Snippet text is stored as the user wants it, so it's left alone.
+    let corrected = VocabularyCorrector::new(vocabulary).correct_except(&expansion.text, &expansion.ranges());
+
+    ProcessedTranscript {
+        text: corrected.text.clone(),
+        snippets: expansion.expanded,
+        corrected,
+    }
+}
+
+fn clean_and_interpret(prefs: &AppPreferences, segments: &[Segment]) -> String {
+    // Drop "um"s, stutters and doubled words, and fix capitalization across segment joins
+    let cleaned = clean_segments(
+        segments.iter().map(|segment| segment.text.as_str()),
+        prefs.cleanup_level,
+    );
+
+    // Spoken punctuation and editing commands ("comma", "new paragraph", "delete that")
+    if prefs.dictation.enabled {
+        let language = &prefs.dictation.language;
+        let custom = prefs
+            .dictation
+            .custom_commands
+            .get(language)
+            .map(Vec::as_slice)
+            .unwrap_or_default();
+        let commands = CommandSet::for_language(language, custom);
+        interpret_segments(&commands, cleaned.iter().map(String::as_str))
+    } else {
+        cleaned
+            .iter()
+            .map(|segment| segment.trim())
+            .filter(|segment| !segment.is_empty())
+            .collect::<Vec<_>>()
+            .join(" ")
+    }
+}
+
+pub fn format_template(prefs: &AppPreferences, template_id: &str) -> Result<FormatTemplate> {
+    let mut template =
+        FormatTemplate::builtin(template_id).ok_or_else(|| anyhow!("Unknown format type: {}", template_id))?;
+    if let Some(limits) = prefs.template_limits.get(&template.id) {
+        template.limits = *limits;
+    }
+    Ok(template)
+}
+
+pub async fn format_transcript(
+    backend: &dyn FormattingBackend,
+    template: &FormatTemplate,
+    transcript: &str,
+) -> Result<String> {
+    let result = format_with_template(backend, template, transcript).await?;
+
+    // JSON templates are validated so callers never see malformed output
+    if template.json_schema.is_some() {
+        let notes = parse_structured_notes(&result)?;
+        return Ok(serde_json::to_string_pretty(&notes)?);
+    }
+
+    Ok(result)
+}
+
+// Formats with the preferred backend. Text templates fall back to the deterministic
+// clean-up when no backend is available, as the app does; JSON templates can't.
+pub async fn format_or_clean<F, Fut>(
+    prefs: &AppPreferences,
+    template: &FormatTemplate,
+    transcript: &str,
+    backend: F,
+) -> Result<String>
+where
+    F: FnOnce() -> Fut,
+    Fut: Future<Output = Result<Box<dyn FormattingBackend>>>,
+{
+    let backend = match backend().await {
+        Ok(backend) => backend,
+        Err(e) if template.json_schema.is_none() => {
+            eprintln!("⚠️  No formatting backend ({}), returning cleaned transcript", e);
+            return Ok(clean_text(transcript, prefs.cleanup_level.max(CleanupLevel::Light)));
+        }
+        Err(e) => return Err(e),
+    };
+
+    eprintln!("📝 Formatting as {} with {} backend", template.id, backend.name());
+    format_transcript(backend.as_ref(), template, transcript).await
+}
@@ -0,0 +1,26 @@
+// Letter-case helpers shared by clean-up and dictation commands
+
+pub fn capitalize(word: &str) -> String {
+    let mut chars = word.chars();
+    match chars.next() {
+        Some(first) => first.to_uppercase().chain(chars).collect(),
+        None => String::new(),
+    }
+}
+
+pub fn lowercase_first(word: &str) -> String {
+    let mut chars = word.chars();
+    match chars.next() {
+        Some(first) => first.to_lowercase().chain(chars).collect(),
+        None => String::new(),
+    }
+}
+
+// "World" but not "I", "I'm" or acronyms like "NASA"
+pub fn is_capitalized_word(word: &str) -> bool {
+    let letters: Vec<char> = word.chars().filter(|c| c.is_alphabetic()).collect();
+    letters.len() > 1
+        && letters[0].is_uppercase()
+        && letters[1..].iter().all(|c| c.is_lowercase())
+        && !word.starts_with("I'")
+}
@@ -0,0 +1,450 @@
+use super::case::{capitalize, is_capitalized_word, lowercase_first};
+use serde::{Deserialize, Serialize};
+
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum CleanupLevel {
+    Off,
+    // "um"/"uh", stutters ("I-I", "th-the") and doubled function words ("the the")
+    #[default]
+    Light,
+    // Also "you know"/"I mean" set off by commas, any doubled word and repeated short phrases
+    Standard,
+    // Also hedges like "basically" or "like" set off by commas, and "So,"/"Well," openers
+    Aggressive,
+}
+
+// Hesitation sounds; removed wherever they appear
+const HESITATIONS: &[&str] = &["um", "umm", "uh", "uhh", "erm", "er", "hmm", "mm", "ah"];
+// Only removed when set off by commas or at the start of a sentence
+const STANDARD_FILLERS: &[&[&str]] = &[&["you", "know"], &["i", "mean"]];
+const AGGRESSIVE_FILLERS: &[&[&str]] = &[
+    &["like"],
+    &["basically"],
+    &["actually"],
+    &["literally"],
+    &["sort", "of"],
+    &["kind", "of"],
+];
+// Only removed at the start of a sentence when followed by a comma
+const AGGRESSIVE_OPENERS: &[&str] = &["so", "well", "okay", "ok", "right"];
+// Grammatical doubles ("I know that that works", "we had had enough")
+const ALLOWED_DOUBLES: &[&str] = &["that", "had"];
+// Function words that are stutters when repeated whole ("I-I", "the the"). Other words
+// can be repeated on purpose: "no no no", "a no-no", "we can can the project".
+const STUTTER_WORDS: &[&str] = &[
+    "a", "an", "the", "i", "i'm", "i've", "i'll", "i'd", "you", "he", "she", "it", "it's", "we", "we're",
+    "they", "they're", "me", "my", "to", "of", "in", "on", "at", "for", "with", "and", "but", "or", "is",
+    "was", "are", "be", "if", "this", "there", "what",
+];
+// Common prefixes that look like stutters ("re-read", "co-op")
+const HYPHEN_PREFIXES: &[&str] = &["re", "co", "de", "un", "in", "bi", "ex", "pre", "non"];
+
+#[derive(Clone, Debug)]
+struct Token {
+    lead: String,
+    core: String,
+    trail: String,
+    // First token of a Whisper segment
+    segment_start: bool,
+}
+
+impl Token {
+    fn parse(raw: &str, segment_start: bool) -> Self {
+        let core_start = raw.find(|c: char| c.is_alphanumeric()).unwrap_or(raw.len());
+        let core_end = raw
+            .rfind(|c: char| c.is_alphanumeric())
+            .map(|i| i + raw[i..].chars().next().map_or(1, char::len_utf8))
+            .unwrap_or(core_start);
+
+        Self {
+            lead: raw[..core_start].to_string(),
+            core: raw[core_start..core_end].to_string(),
+            trail: raw[core_end..].to_string(),
+            segment_start,
+        }
+    }
+
+    fn word(&self) -> String {
+        self.core.to_lowercase()
+    }
+
+    fn ends_sentence(&self) -> bool {
+        self.trail.contains(['.', '?', '!'])
+    }
+
+    fn ends_clause(&self) -> bool {
+        self.trail.contains([',', ';', ':']) || self.ends_sentence()
+    }
+
+    fn render(&self) -> String {
+        format!("{}{}{}", self.lead, self.core, self.trail)
+    }
+}
+
+// Cleans Whisper segments before any formatting. Works across segment boundaries
+// and returns one string per input segment (empty if everything was filler).
+pub fn clean_segments<'s>(segments: impl IntoIterator<Item = &'s str>, level: CleanupLevel) -> Vec<String> {
+    let mut tokens = Vec::new();
+    let mut segment_count = 0;
+    for segment in segments {
+        segment_count += 1;
+        for (i, raw) in segment.split_whitespace().enumerate() {
+            tokens.push(Token::parse(raw, i == 0));
+        }
+        // Keep empty segments so the output lines up with the input
+        if segment.trim().is_empty() {
+            tokens.push(Token::parse("", true));
+        }
+    }
+
+    if level != CleanupLevel::Off {
+        fix_stutters(&mut tokens);
+        tokens = remove_fillers(tokens, level);
+        tokens = remove_repeats(tokens, level);
+        fix_capitalization(&mut tokens);
+    }
+
+    let mut output: Vec<String> = Vec::with_capacity(segment_count);
+    for token in tokens {
+        if token.segment_start || output.is_empty() {
+            output.push(String::new());
+        }
+        let rendered = token.render();
+        if rendered.is_empty() {
+            continue;
+        }
+        let segment = output.last_mut().unwrap();
+        if !segment.is_empty() {
+            segment.push(' ');
+        }
+        segment.push_str(&rendered);
+    }
+    output.resize(segment_count, String::new());
+
+    output
+}
+
+// Cleans already-joined text (e.g. a transcript pasted into the formatter), keeping line breaks
+pub fn clean_text(text: &str, level: CleanupLevel) -> String {
+    text.split('\n')
+        .map(|line| clean_segments([line], level).concat())
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+// "I-I-I think" -> "I think", "th-the" -> "the"
+fn fix_stutters(tokens: &mut [Token]) {
+    for token in tokens {
+        let parts: Vec<&str> = token.core.split('-').collect();
+        if parts.len() < 2 || parts.iter().any(|part| part.is_empty()) {
+            continue;
+        }
+
+        let last = parts[parts.len() - 1];
+        let last_lower = last.to_lowercase();
+        let is_stutter = parts[..parts.len() - 1].iter().all(|part| {
+            let part = part.to_lowercase();
+            (part == last_lower && STUTTER_WORDS.contains(&part.as_str()))
+                || (part.chars().count() <= 2
+                    && last_lower.starts_with(&part)
+                    && last_lower != part
+                    && !HYPHEN_PREFIXES.contains(&part.as_str()))
+        });
+
+        if is_stutter {
+            // Keep the capital of a sentence-initial stutter ("Th-the" -> "The")
+            let capitalized = parts[0].chars().next().is_some_and(char::is_uppercase);
+            token.core = if capitalized { capitalize(last) } else { last.to_string() };
+        }
+    }
+}
+
+fn remove_fillers(tokens: Vec<Token>, level: CleanupLevel) -> Vec<Token> {
+    let mut phrases: Vec<&[&str]> = Vec::new();
+    if level >= CleanupLevel::Standard {
+        phrases.extend(STANDARD_FILLERS);
+    }
+    if level >= CleanupLevel::Aggressive {
+        phrases.extend(AGGRESSIVE_FILLERS);
+    }
+    // Longest first
+    phrases.sort_by_key(|phrase| std::cmp::Reverse(phrase.len()));
+
+    let mut output: Vec<Token> = Vec::with_capacity(tokens.len());
+    // Opening quotes/brackets and segment boundaries of removed words move to the next word
+    let mut carry_lead = String::new();
+    let mut carry_segment_start = false;
+    let mut i = 0;
+
+    while i < tokens.len() {
+        let previous = output.iter().rev().find(|token| !token.core.is_empty());
+        let sentence_start = previous.is_none_or(Token::ends_sentence);
+        let after_comma = previous.is_some_and(|token| token.trail.ends_with(','));
+
+        let word = tokens[i].word();
+        let is_hesitation = !word.is_empty() && HESITATIONS.contains(&word.as_str());
+        let is_opener = level >= CleanupLevel::Aggressive
+            && sentence_start
+            && AGGRESSIVE_OPENERS.contains(&word.as_str())
+            && tokens[i].trail == ",";
+
+        let length = if is_hesitation || is_opener {
+            Some(1)
+        } else {
+            phrases
+                .iter()
+                .find(|phrase| {
+                    let end = i + phrase.len();
+                    end <= tokens.len()
+                        && tokens[i..end].iter().zip(phrase.iter()).all(|(token, word)| token.word() == *word)
+                        // Only the last word may carry punctuation
+                        && tokens[i..end - 1].iter().all(|token| token.trail.is_empty())
+                        && (sentence_start || after_comma)
+                        && (tokens[end - 1].ends_clause() || end == tokens.len())
+                })
+                .map(|phrase| phrase.len())
+        };
+
+        let Some(length) = length else {
+            let mut token = tokens[i].clone();
+            if carry_segment_start && token.segment_start {
+                // The removed words were a whole segment; keep it as an empty one
+                output.push(Token::parse("", true));
+            } else {
+                token.segment_start |= carry_segment_start;
+            }
+            token.lead = std::mem::take(&mut carry_lead) + &token.lead;
+            carry_segment_start = false;
+            output.push(token);
+            i += 1;
+            continue;
+        };
+
+        let removed = &tokens[i..i + length];
+        let first = &removed[0];
+        let last = &removed[length - 1];
+
+        if let Some(previous) = output.iter_mut().rev().find(|token| !token.core.is_empty()) {
+            if last.ends_sentence() && !previous.ends_sentence() {
+                // "and that's it, um." -> "and that's it."
+                let terminal: String = last.trail.chars().filter(|c| matches!(c, '.' | '?' | '!')).collect();
+                previous.trail = previous.trail.trim_end_matches([',', ';', ':']).to_string() + &terminal;
+            } else if last.trail.contains(',') && previous.trail.ends_with(',') {
+                // "It was, you know, fine" -> "It was fine"
+                previous.trail.pop();
+            }
+        }
+
+        if first.segment_start {
+            if carry_segment_start {
+                output.push(Token::parse("", true));
+            }
+            carry_segment_start = true;
+        }
+        carry_lead.push_str(&first.lead);
+        i += length;
+    }
+    if carry_segment_start {
+        output.push(Token::parse("", true));
+    }
+
+    output
+}
+
+// "the the" -> "the", and at Standard also "no no", "I, I" and "I think I think"
+fn remove_repeats(tokens: Vec<Token>, level: CleanupLevel) -> Vec<Token> {
+    let max_phrase = if level >= CleanupLevel::Standard { 3 } else { 1 };
+    let mut tokens = tokens;
+    let mut i = 0;
+
+    while i < tokens.len() {
+        let mut removed = false;
+
+        for n in (1..=max_phrase).rev() {
+            if i + 2 * n > tokens.len() {
+                continue;
+            }
+            let first = &tokens[i..i + n];
+            let second = &tokens[i + n..i + 2 * n];
+
+            let same = first
+                .iter()
+                .zip(second)
+                .all(|(a, b)| !a.core.is_empty() && a.word() == b.word());
+            if !same || (n == 1 && ALLOWED_DOUBLES.contains(&first[0].word().as_str())) {
+                continue;
+            }
+            if level == CleanupLevel::Light && !STUTTER_WORDS.contains(&first[0].word().as_str()) {
+                continue;
+            }
+
+            // Punctuation inside the repeat means separate clauses, except a
+            // comma after a single stuttered word ("I, I think") at Standard
+            let inner_punctuation_ok = first[..n - 1].iter().all(|token| token.trail.is_empty())
+                && (first[n - 1].trail.is_empty() || (n == 1 && level >= CleanupLevel::Standard && first[0].trail == ","));
+            if !inner_punctuation_ok || second.iter().any(|token| !token.lead.is_empty()) {
+                continue;
+            }
+
+            let lead = first[0].lead.clone();
+            let segment_start = first[0].segment_start;
+            let capitalized = first[0].core.chars().next().is_some_and(char::is_uppercase);
+            tokens.drain(i..i + n);
+
+            let kept = &mut tokens[i];
+            kept.lead = lead + &kept.lead;
+            if capitalized {
+                kept.core = capitalize(&kept.core);
+            }
+            if segment_start && kept.segment_start {
+                // The repeat was a whole segment; keep it as an empty one
+                tokens.insert(i, Token::parse("", true));
+                i += 1;
+            } else {
+                tokens[i].segment_start |= segment_start;
+            }
+            removed = true;
+            break;
+        }
+
+        // Check the same position again for triples ("the the the")
+        if !removed {
+            i += 1;
+        }
+    }
+
+    tokens
+}
+
+fn fix_capitalization(tokens: &mut [Token]) {
+    // Words Whisper also wrote in lowercase are ordinary words, not names
+    let lowercase_words: std::collections::HashSet<String> = tokens
+        .iter()
+        .filter(|token| token.core.chars().next().is_some_and(char::is_lowercase))
+        .map(|token| token.core.clone())
+        .collect();
+
+    let mut previous: Option<Token> = None;
+    for token in tokens.iter_mut() {
+        if token.core.is_empty() {
+            continue;
+        }
+
+        match token.word().as_str() {
+            "i" | "i'm" | "i've" | "i'll" | "i'd" => token.core = capitalize(&token.core),
+            _ => {}
+        }
+
+        match &previous {
+            None => token.core = capitalize(&token.core),
+            // Abbreviations like "e.g." don't end sentences
+            Some(previous) if previous.ends_sentence() && !previous.core.contains('.') => {
+                token.core = capitalize(&token.core)
+            }
+            // Whisper capitalizes every segment, even ones that continue a sentence
+            Some(previous) if token.segment_start && !previous.ends_sentence() => {
+                let lowered = lowercase_first(&token.core);
+                if is_capitalized_word(&token.core) && lowercase_words.contains(&lowered) {
+                    token.core = lowered;
+                }
+            }
+            _ => {}
+        }
+
+        previous = Some(token.clone());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn run(level: CleanupLevel, cases: &[(&str, &str)]) {
+        for (input, expected) in cases {
+            assert_eq!(clean_text(input, level), *expected, "input: {:?}", input);
+        }
+    }
+
+    #[test]
+    fn light() {
+        run(
+            CleanupLevel::Light,
+            &[
+                ("Um, I think so.", "I think so."),
+                ("So uh we should go", "So we should go"),
+                ("and that's it, um.", "And that's it."),
+                ("I-I-I think so", "I think so"),
+                ("Th-the plan works", "The plan works"),
+                ("the the plan", "The plan"),
+                ("I I think", "I think"),
+                ("I know that that works", "I know that that works"),
+                ("We had had enough", "We had had enough"),
+                ("We need to re-read it", "We need to re-read it"),
+                ("It's a co-op", "It's a co-op"),
+                // What people said, not disfluencies
+                ("Uh-huh, that sounds good.", "Uh-huh, that sounds good."),
+                ("She said no no no", "She said no no no"),
+                ("Is it a no-no?", "Is it a no-no?"),
+                ("We can can the project", "We can can the project"),
+                ("It was so-so", "It was so-so"),
+                ("Bye-bye for now", "Bye-bye for now"),
+                ("It was, you know, fine", "It was, you know, fine"),
+            ],
+        );
+    }
+
+    #[test]
+    fn standard() {
+        run(
+            CleanupLevel::Standard,
+            &[
+                ("It was, you know, fine", "It was fine"),
+                ("I mean, it works", "It works"),
+                ("I, I think so", "I think so"),
+                ("I think I think it works", "I think it works"),
+                ("She said no no", "She said no"),
+                ("You know the answer", "You know the answer"),
+                ("So, basically, it works", "So, basically, it works"),
+            ],
+        );
+    }
+
+    #[test]
+    fn aggressive() {
+        run(
+            CleanupLevel::Aggressive,
+            &[
+                ("So, basically, it works", "It works"),
+                ("Well, it's, like, done", "It's done"),
+                ("I like it", "I like it"),
+                ("Okay, um, let's start", "Let's start"),
+            ],
+        );
+    }
+
+    #[test]
+    fn off() {
+        run(CleanupLevel::Off, &[("Um, the the plan", "Um, the the plan")]);
+    }
+
+    #[test]
+    fn segments() {
+        let cases: &[(&[&str], &[&str])] = &[
+            // A segment of only filler stays, empty, so the output lines up with the input
+            (&["Um.", "So the the plan"], &["", "So the plan"]),
+            (&["We met the", "the team"], &["We met", "the team"]),
+            // Whisper capitalizes segments that continue a sentence
+            (
+                &["The store is closed, so we go to the", "Store tomorrow."],
+                &["The store is closed, so we go to the", "store tomorrow."],
+            ),
+            (&["I called", "Dana today."], &["I called", "Dana today."]),
+        ];
+
+        for (segments, expected) in cases {
+            let cleaned = clean_segments(segments.iter().copied(), CleanupLevel::Light);
+            assert_eq!(cleaned, *expected, "segments: {:?}", segments);
+        }
+    }
+}
@@ -0,0 +1,422 @@
+use super::case::{capitalize, is_capitalized_word, lowercase_first};
+use serde::{Deserialize, Serialize};
+
+// Punctuation Whisper guesses on its own; dropped when a spoken command replaces it
+const WHISPER_PUNCTUATION: &[char] = &['.', ',', '?', '!', ';', ':'];
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[serde(tag = "action", content = "text", rename_all = "snake_case")]
+pub enum DictationAction {
+    // Attaches to the previous word: , . ? ! and closing brackets or quotes
+    Punctuation(String),
+    // The next word attaches to it: opening brackets or quotes
+    Open(String),
+    // Inserted like a dictated word, e.g. "smiley face" -> ":)"
+    Insert(String),
+    NewLine,
+    NewParagraph,
+    // Removes the last dictated phrase
+    DeleteThat,
+    AllCapsNext,
+    AllCapsOn,
+    AllCapsOff,
+    // The next word is written out instead of interpreted ("literal next word comma")
+    Literal,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct DictationCommand {
+    pub phrase: String,
+    #[serde(flatten)]
+    pub action: DictationAction,
+}
+
+impl DictationCommand {
+    fn new(phrase: &str, action: DictationAction) -> Self {
+        Self {
+            phrase: phrase.to_string(),
+            action,
+        }
+    }
+}
+
+// Built-in commands for a Whisper language code; unknown languages have none
+pub fn builtin_commands(language: &str) -> Vec<DictationCommand> {
+    use DictationAction::*;
+
+    let punctuation = |text: &str| Punctuation(text.to_string());
+    let open = |text: &str| Open(text.to_string());
+
+    let table: Vec<(&str, DictationAction)> = match language {
+        "en" => vec![
+            ("comma", punctuation(",")),
+            ("period", punctuation(".")),
+            ("full stop", punctuation(".")),
+            ("question mark", punctuation("?")),
+            ("exclamation mark", punctuation("!")),
+            ("exclamation point", punctuation("!")),
+            ("colon", punctuation(":")),
+            ("semicolon", punctuation(";")),
+            ("semi colon", punctuation(";")),
+            ("open quote", open("\"")),
+            ("close quote", punctuation("\"")),
+            ("end quote", punctuation("\"")),
+            ("open paren", open("(")),
+            ("open parenthesis", open("(")),
+            ("close paren", punctuation(")")),
+            ("close parenthesis", punctuation(")")),
+            ("new line", NewLine),
+            ("newline", NewLine),
+            ("new paragraph", NewParagraph),
+            ("delete that", DeleteThat),
+            ("scratch that", DeleteThat),
+            // Bare "all caps" and "literal" are ordinary words, so these need more
+            ("all caps next word", AllCapsNext),
+            ("all caps on", AllCapsOn),
+            ("all caps off", AllCapsOff),
+            ("literal next word", Literal),
+        ],
+        "de" => vec![
+            ("komma", punctuation(",")),
+            ("punkt", punctuation(".")),
+            ("fragezeichen", punctuation("?")),
+            ("ausrufezeichen", punctuation("!")),
+            ("doppelpunkt", punctuation(":")),
+            ("semikolon", punctuation(";")),
+            ("anführungszeichen auf", open("„")),
+            ("anführungszeichen zu", punctuation("“")),
+            ("klammer auf", open("(")),
+            ("klammer zu", punctuation(")")),
+            ("neue zeile", NewLine),
+            ("neuer absatz", NewParagraph),
+            ("lösch das", DeleteThat),
+            ("streich das", DeleteThat),
+            ("nächstes wort groß", AllCapsNext),
+            ("großbuchstaben an", AllCapsOn),
+            ("großbuchstaben aus", AllCapsOff),
+            ("nächstes wort wörtlich", Literal),
+        ],
+        _ => Vec::new(),
+    };
+
+    table
+        .into_iter()
+        .map(|(phrase, action)| DictationCommand::new(phrase, action))
+        .collect()
+}
+
+pub struct CommandSet {
+    // Longest phrases first, so a custom phrase wins over a shorter one it starts with
+    commands: Vec<(Vec<String>, DictationAction)>,
+}
+
+impl CommandSet {
+    // Built-in commands for `language` plus the user's own; custom phrases
+    // replace built-in ones with the same words
+    pub fn for_language(language: &str, custom: &[DictationCommand]) -> Self {
+        let mut commands: Vec<(Vec<String>, DictationAction)> = Vec::new();
+
+        for command in builtin_commands(language).iter().chain(custom) {
+            let words = phrase_words(&command.phrase);
+            if words.is_empty() {
+                continue;
+            }
+            commands.retain(|(existing, _)| *existing != words);
+            commands.push((words, command.action.clone()));
+        }
+        commands.sort_by_key(|(words, _)| std::cmp::Reverse(words.len()));
+
+        Self { commands }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.commands.is_empty()
+    }
+
+    fn match_at(&self, words: &[String]) -> Option<(usize, &DictationAction)> {
+        self.commands
+            .iter()
+            .find(|(phrase, _)| words.starts_with(phrase))
+            .map(|(phrase, action)| (phrase.len(), action))
+    }
+}
+
+// Applies dictation commands to Whisper segments as they arrive. Each segment and
+// each stretch of words between commands is a phrase that "delete that" can remove.
+pub struct DictationInterpreter<'a> {
+    commands: &'a CommandSet,
+    output: String,
+    phrase_starts: Vec<usize>,
+    phrase_open: bool,
+    caps_lock: bool,
+    caps_next: bool,
+    capitalize_next: bool,
+    lowercase_next: bool,
+    no_space_next: bool,
+    last_was_word: bool,
+}
+
+impl<'a> DictationInterpreter<'a> {
+    pub fn new(commands: &'a CommandSet) -> Self {
+        Self {
+            commands,
+            output: String::new(),
+            phrase_starts: Vec::new(),
+            phrase_open: false,
+            caps_lock: false,
+            caps_next: false,
+            capitalize_next: true,
+            lowercase_next: false,
+            no_space_next: false,
+            last_was_word: false,
+        }
+    }
+
+    pub fn push_segment(&mut self, text: &str) {
+        let tokens: Vec<&str> = text.split_whitespace().collect();
+        let words: Vec<String> = tokens.iter().map(|token| normalize_word(token)).collect();
+        self.phrase_open = false;
+
+        let mut i = 0;
+        while i < tokens.len() {
+            let Some((length, action)) = self.commands.match_at(&words[i..]) else {
+                self.push_word(tokens[i]);
+                i += 1;
+                continue;
+            };
+
+            let last_token = tokens[i + length - 1];
+            i += length;
+
+            if *action == DictationAction::Literal {
+                if let Some(token) = tokens.get(i) {
+                    self.push_word(token);
+                    i += 1;
+                }
+                continue;
+            }
+
+            // Whisper often ends the command itself with a period ("Comma."), which
+            // then capitalizes the following word
+            let whisper_sentence_end = last_token.ends_with(['.', '?', '!']);
+            self.apply(action, whisper_sentence_end);
+        }
+    }
+
+    pub fn finish(self) -> String {
+        self.output.trim().to_string()
+    }
+
+    fn apply(&mut self, action: &DictationAction, whisper_sentence_end: bool) {
+        self.phrase_open = false;
+
+        match action {
+            DictationAction::Punctuation(text) => {
+                if self.last_was_word {
+                    self.output.truncate(self.output.trim_end_matches(WHISPER_PUNCTUATION).len());
+                }
+                self.trim_trailing_spaces();
+                self.output.push_str(text);
+
+                let sentence_end = text.ends_with(['.', '?', '!']);
+                self.capitalize_next = sentence_end;
+                self.lowercase_next = !sentence_end && whisper_sentence_end;
+                self.no_space_next = false;
+            }
+            DictationAction::Open(text) => {
+                self.push_separator();
+                self.output.push_str(text);
+                self.no_space_next = true;
+            }
+            DictationAction::Insert(text) => {
+                self.push_separator();
+                self.output.push_str(text);
+                self.no_space_next = false;
+            }
+            DictationAction::NewLine | DictationAction::NewParagraph => {
+                self.trim_trailing_spaces();
+                self.output.push_str(if *action == DictationAction::NewLine { "\n" } else { "\n\n" });
+                self.capitalize_next = true;
+                self.lowercase_next = false;
+                self.no_space_next = false;
+            }
+            DictationAction::DeleteThat => {
+                if let Some(start) = self.phrase_starts.pop() {
+                    self.output.truncate(start);
+                    self.trim_trailing_spaces();
+                }
+                let tail = self.output.trim_end_matches(' ');
+                self.capitalize_next = tail.is_empty() || tail.ends_with(['.', '?', '!', '\n']);
+                self.lowercase_next = false;
+                self.no_space_next = false;
+            }
+            DictationAction::AllCapsNext => self.caps_next = true,
+            DictationAction::AllCapsOn => self.caps_lock = true,
+            DictationAction::AllCapsOff => self.caps_lock = false,
+            DictationAction::Literal => {}
+        }
+
+        self.last_was_word = false;
+    }
+
+    fn push_word(&mut self, token: &str) {
+        if !self.phrase_open {
+            self.phrase_starts.push(self.output.len());
+            self.phrase_open = true;
+        }
+
+        let word = if self.caps_lock || self.caps_next {
+            token.to_uppercase()
+        } else if self.capitalize_next {
+            capitalize(token)
+        } else if self.lowercase_next && is_capitalized_word(token) {
+            lowercase_first(token)
+        } else {
+            token.to_string()
+        };
+
+        self.push_separator();
+        self.output.push_str(&word);
+
+        self.caps_next = false;
+        self.capitalize_next = false;
+        self.lowercase_next = false;
+        self.no_space_next = false;
+        self.last_was_word = true;
+    }
+
+    fn push_separator(&mut self) {
+        if !self.output.is_empty() && !self.output.ends_with(char::is_whitespace) && !self.no_space_next {
+            self.output.push(' ');
+        }
+    }
+
+    fn trim_trailing_spaces(&mut self) {
+        self.output.truncate(self.output.trim_end_matches(' ').len());
+    }
+}
+
+// Runs the interpreter over a whole transcript's segments
+pub fn interpret_segments<'s>(commands: &CommandSet, segments: impl IntoIterator<Item = &'s str>) -> String {
+    let mut interpreter = DictationInterpreter::new(commands);
+    for segment in segments {
+        interpreter.push_segment(segment);
+    }
+    interpreter.finish()
+}
+
+fn phrase_words(phrase: &str) -> Vec<String> {
+    phrase
+        .split_whitespace()
+        .map(normalize_word)
+        .filter(|word| !word.is_empty())
+        .collect()
+}
+
+fn normalize_word(word: &str) -> String {
+    word.chars()
+        .filter(|c| c.is_alphanumeric())
+        .flat_map(char::to_lowercase)
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn run(language: &str, custom: &[DictationCommand], segments: &[&str]) -> String {
+        let commands = CommandSet::for_language(language, custom);
+        interpret_segments(&commands, segments.iter().copied())
+    }
+
+    #[test]
+    fn english_commands() {
+        let cases: &[(&[&str], &str)] = &[
+            (&["hello comma world period"], "Hello, world."),
+            (&["Hello, comma, world."], "Hello, world."),
+            (&["Hello. Comma. World."], "Hello, world."),
+            (&["Dear Sam comma new line thanks for the notes period"], "Dear Sam,\nThanks for the notes."),
+            (&["first point new paragraph second point"], "First point\n\nSecond point"),
+            (&["is it ready question mark"], "Is it ready?"),
+            (&["wow exclamation point that worked"], "Wow! That worked"),
+            (&["note colon ship it"], "Note: ship it"),
+            (&["he said open quote hi close quote"], "He said \"hi\""),
+            (&["see open paren below close paren"], "See (below)"),
+            (&["this is wrong delete that this is right"], "This is right"),
+            (&["keep this period", "drop this", "scratch that"], "Keep this."),
+            (&["keep this period wrong part delete that new line next"], "Keep this.\nNext"),
+            (&["delete that"], ""),
+            (&["use all caps next word nasa data"], "Use NASA data"),
+            (&["all caps on loud words all caps off quiet"], "LOUD WORDS quiet"),
+            (&["the literal next word comma is small"], "The comma is small"),
+            // Ordinary uses of the command words
+            (&["I mean it in the literal sense"], "I mean it in the literal sense"),
+            (&["use all caps for headings"], "Use all caps for headings"),
+            (&["a literal translation"], "A literal translation"),
+            (&["New line."], ""),
+            (&["I went home. Comma. I slept."], "I went home, I slept."),
+            (&["plain text, as Whisper wrote it."], "Plain text, as Whisper wrote it."),
+            (&["first segment", "second segment"], "First segment second segment"),
+        ];
+
+        for (segments, expected) in cases {
+            assert_eq!(run("en", &[], segments), *expected, "segments: {:?}", segments);
+        }
+    }
+
+    #[test]
+    fn german_commands() {
+        let cases: &[(&[&str], &str)] = &[
+            (&["hallo Komma Welt Punkt"], "Hallo, Welt."),
+            (&["erste Zeile neue Zeile zweite Zeile"], "Erste Zeile\nZweite Zeile"),
+            (&["er sagte Anführungszeichen auf hallo Anführungszeichen zu"], "Er sagte „hallo“"),
+            (&["falsch lösch das richtig"], "Richtig"),
+            (&["nächstes wort groß nasa Daten"], "NASA Daten"),
+            (&["das ist wörtlich gemeint"], "Das ist wörtlich gemeint"),
+            (&["alles in Großbuchstaben schreiben"], "Alles in Großbuchstaben schreiben"),
+            // English commands don't apply to German dictation
+            (&["hallo comma Welt"], "Hallo comma Welt"),
+        ];
+
+        for (segments, expected) in cases {
+            assert_eq!(run("de", &[], segments), *expected, "segments: {:?}", segments);
+        }
+    }
+
+    #[test]
+    fn custom_commands() {
+        let custom = vec![
+            DictationCommand::new("smiley face", DictationAction::Insert(":)".to_string())),
+            // Overrides the built-in "comma"
+            DictationCommand::new("comma", DictationAction::Punctuation(";".to_string())),
+        ];
+
+        let cases: &[(&str, &[&str], &str)] = &[
+            ("en", &["thanks smiley face"], "Thanks :)"),
+            ("en", &["first comma then second"], "First; then second"),
+            ("en", &["done full stop"], "Done."),
+            ("fr", &["merci smiley face"], "Merci :)"),
+            // No built-in commands, but custom ones still apply
+            ("fr", &["merci period"], "Merci period"),
+            ("fr", &["oui comma non"], "Oui; non"),
+        ];
+
+        for (language, segments, expected) in cases {
+            assert_eq!(run(language, &custom, segments), *expected, "segments: {:?}", segments);
+        }
+    }
+
+    #[test]
+    fn commands_round_trip_through_json() {
+        let command = DictationCommand::new("smiley face", DictationAction::Insert(":)".to_string()));
+        let json = serde_json::to_value(&command).unwrap();
+        assert_eq!(json, serde_json::json!({ "phrase": "smiley face", "action": "insert", "text": ":)" }));
+        assert_eq!(serde_json::from_value::<DictationCommand>(json).unwrap(), command);
+
+        let json = serde_json::json!({ "phrase": "new line", "action": "new_line" });
+        assert_eq!(
+            serde_json::from_value::<DictationCommand>(json).unwrap().action,
+            DictationAction::NewLine
+        );
+    }
+}
@@ -0,0 +1,192 @@
+use std::ops::Range;
+
+// A word of the transcript, split so rules can match on the bare word while the
+// surrounding punctuation and spacing are kept as written
+#[derive(Clone, Debug, PartialEq)]
+pub struct Word {
+    // Whitespace before the word, or "-" inside hyphenated words ("twenty-five")
+    pub sep: String,
+    pub lead: String,
+    pub core: String,
+    pub trail: String,
+    // Lowercase core without dots, so "P.M." matches "pm"
+    pub lower: String,
+}
+
+impl Word {
+    // No punctuation after the word, so a phrase may continue past it
+    pub fn is_open(&self) -> bool {
+        self.trail.is_empty()
+    }
+}
+
+pub struct Rewrite {
+    pub consumed: usize,
+    pub text: String,
+    // Replaces the last consumed word's punctuation, e.g. when "p.m." ends mid-sentence
+    pub trail: Option<String>,
+}
+
+impl Rewrite {
+    pub fn new(consumed: usize, text: String) -> Self {
+        Self {
+            consumed,
+            text,
+            trail: None,
+        }
+    }
+}
+
+// One spoken-to-written conversion, e.g. "twenty five dollars" -> "$25"
+pub trait ItnRule: Send + Sync {
+    fn name(&self) -> &'static str;
+
+    // Tries to rewrite the words starting at `words[0]`
+    fn apply(&self, words: &[Word]) -> Option<Rewrite>;
+}
+
+// The rules for one language, tried in order at each word
+pub struct RuleSet {
+    language: String,
+    rules: Vec<Box<dyn ItnRule>>,
+}
+
+impl RuleSet {
+    pub fn new(language: &str, rules: Vec<Box<dyn ItnRule>>) -> Self {
+        Self {
+            language: language.to_string(),
+            rules,
+        }
+    }
+
+    pub fn for_language(language: &str) -> Option<Self> {
+        match language {
+            "en" => Some(super::english::rules()),
+            _ => None,
+        }
+    }
+
+    pub fn language(&self) -> &str {
+        &self.language
+    }
+
+    pub fn normalize(&self, text: &str) -> String {
+        let (words, tail) = tokenize(text);
+        let mut output = String::with_capacity(text.len());
+        let mut i = 0;
+
+        while i < words.len() {
+            let word = &words[i];
+            let rewrite = if word.core.is_empty() {
+                None
+            } else {
+                self.rules.iter().find_map(|rule| {
+                    rule.apply(&words[i..])
+                        .filter(|rewrite| rewrite.consumed > 0)
+                })
+            };
+
+            match rewrite {
+                Some(rewrite) => {
+                    let last = &words[i + rewrite.consumed - 1];
+                    output.push_str(&word.sep);
+                    output.push_str(&word.lead);
+                    output.push_str(&rewrite.text);
+                    output.push_str(rewrite.trail.as_deref().unwrap_or(&last.trail));
+                    i += rewrite.consumed;
+                }
+                None => {
+                    output.push_str(&word.sep);
+                    output.push_str(&word.lead);
+                    output.push_str(&word.core);
+                    output.push_str(&word.trail);
+                    i += 1;
+                }
+            }
+        }
+        output.push_str(&tail);
+
+        output
+    }
+
+    // Leaves text inside `protected` byte ranges (sorted, e.g. expanded snippets) as
+    // written; returns the output and where those ranges ended up
+    pub fn normalize_except(
+        &self,
+        text: &str,
+        protected: &[Range<usize>],
+    ) -> (String, Vec<Range<usize>>) {
+        let mut output = String::with_capacity(text.len());
+        let mut moved = Vec::with_capacity(protected.len());
+        let mut copied_to = 0;
+
+        for range in protected {
+            output.push_str(&self.normalize(&text[copied_to..range.start]));
+            let start = output.len();
+            output.push_str(&text[range.clone()]);
+            moved.push(start..output.len());
+            copied_to = range.end;
+        }
+        output.push_str(&self.normalize(&text[copied_to..]));
+
+        (output, moved)
+    }
+}
+
+// Splits on whitespace and inside hyphenated words; returns the words and any trailing whitespace
+fn tokenize(text: &str) -> (Vec<Word>, String) {
+    let mut words = Vec::new();
+    let mut rest = text;
+
+    loop {
+        let trimmed = rest.trim_start();
+        let sep = &rest[..rest.len() - trimmed.len()];
+        if trimmed.is_empty() {
+            return (words, sep.to_string());
+        }
+
+        let end = trimmed.find(char::is_whitespace).unwrap_or(trimmed.len());
+        let raw = &trimmed[..end];
+        rest = &trimmed[end..];
+
+        let core_start = raw.find(char::is_alphanumeric).unwrap_or(raw.len());
+        let core_end = raw
+            .char_indices()
+            .rev()
+            .find(|(_, c)| c.is_alphanumeric())
+            .map(|(i, c)| i + c.len_utf8())
+            .unwrap_or(core_start);
+        let (lead, core, trail) = (
+            &raw[..core_start],
+            &raw[core_start..core_end],
+            &raw[core_end..],
+        );
+
+        let parts: Vec<&str> = core.split('-').collect();
+        let hyphenated = parts.len() > 1 && parts.iter().all(|part| !part.is_empty());
+        let parts = if hyphenated { parts } else { vec![core] };
+        let count = parts.len();
+
+        for (i, part) in parts.into_iter().enumerate() {
+            words.push(Word {
+                sep: if i == 0 {
+                    sep.to_string()
+                } else {
+                    "-".to_string()
+                },
+                lead: if i == 0 {
+                    lead.to_string()
+                } else {
+                    String::new()
+                },
+                core: part.to_string(),
+                trail: if i == count - 1 {
+                    trail.to_string()
+                } else {
+                    String::new()
+                },
+                lower: part.to_lowercase().replace('.', ""),
+            });
+        }
+    }
+}
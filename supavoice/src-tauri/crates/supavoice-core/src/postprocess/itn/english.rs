@@ -0,0 +1,792 @@
+use super::engine::{ItnRule, Rewrite, RuleSet, Word};
+use super::numbers::{
+    parse_number, parse_ordinal, parse_year, tens_value, unit_value, SpokenNumber,
+};
+
+const MONTHS: &[&str] = &[
+    "January",
+    "February",
+    "March",
+    "April",
+    "May",
+    "June",
+    "July",
+    "August",
+    "September",
+    "October",
+    "November",
+    "December",
+];
+
+const CURRENCIES: &[(&str, &str)] = &[
+    ("dollars", "$"),
+    ("dollar", "$"),
+    ("bucks", "$"),
+    ("euros", "€"),
+    ("euro", "€"),
+    ("yen", "¥"),
+];
+
+// Spoken unit (possibly several words) -> symbol; longest phrases first
+const UNITS: &[(&[&str], &str)] = &[
+    (&["kilometers", "per", "hour"], "km/h"),
+    (&["kilometres", "per", "hour"], "km/h"),
+    (&["miles", "per", "hour"], "mph"),
+    (&["degrees", "celsius"], "°C"),
+    (&["degrees", "fahrenheit"], "°F"),
+    (&["degree", "celsius"], "°C"),
+    (&["degree", "fahrenheit"], "°F"),
+    (&["kilometers"], "km"),
+    (&["kilometres"], "km"),
+    (&["kilometer"], "km"),
+    (&["kilometre"], "km"),
+    (&["meters"], "m"),
+    (&["metres"], "m"),
+    (&["meter"], "m"),
+    (&["metre"], "m"),
+    (&["centimeters"], "cm"),
+    (&["centimetres"], "cm"),
+    (&["centimeter"], "cm"),
+    (&["millimeters"], "mm"),
+    (&["millimetres"], "mm"),
+    (&["millimeter"], "mm"),
+    (&["miles"], "mi"),
+    (&["mile"], "mi"),
+    (&["kilograms"], "kg"),
+    (&["kilogram"], "kg"),
+    (&["kilos"], "kg"),
+    (&["grams"], "g"),
+    (&["gram"], "g"),
+    (&["milligrams"], "mg"),
+    (&["liters"], "L"),
+    (&["litres"], "L"),
+    (&["liter"], "L"),
+    (&["milliliters"], "mL"),
+    (&["millilitres"], "mL"),
+    (&["terabytes"], "TB"),
+    (&["gigabytes"], "GB"),
+    (&["gigabyte"], "GB"),
+    (&["megabytes"], "MB"),
+    (&["kilobytes"], "KB"),
+    (&["gigahertz"], "GHz"),
+    (&["megahertz"], "MHz"),
+    (&["degrees"], "°"),
+];
+
+// Symbols written without a space before them
+const ATTACHED_UNITS: &[&str] = &["°", "°C", "°F"];
+
+pub fn rules() -> RuleSet {
+    RuleSet::new(
+        "en",
+        vec![
+            Box::new(DateRule),
+            Box::new(TimeRule),
+            Box::new(CurrencyRule),
+            Box::new(PercentRule),
+            Box::new(UnitRule),
+            Box::new(YearRule),
+            Box::new(CardinalRule),
+        ],
+    )
+}
+
+fn open_at(words: &[Word], i: usize) -> bool {
+    i > 0 && i < words.len() && words[i - 1].is_open() && words[i].lead.is_empty()
+}
+
+fn month_at(words: &[Word], i: usize) -> Option<&'static str> {
+    let word = words.get(i)?;
+    let month = MONTHS
+        .iter()
+        .copied()
+        .find(|month| month.eq_ignore_ascii_case(&word.lower))?;
+    // Whisper capitalizes months; lowercase "march" is usually a verb unless an ordinal
+    // day follows ("march third"), and "may" is a verb even then ("you may first try")
+    let capitalized = word.core.starts_with(char::is_uppercase);
+    let ordinal_follows = open_at(words, i + 1) && parse_ordinal(&words[i + 1..]).is_some();
+    (capitalized || (month != "May" && ordinal_follows)).then_some(month)
+}
+
+// "minus five" -> -5
+fn parse_signed(words: &[Word]) -> Option<(bool, SpokenNumber)> {
+    let negative =
+        matches!(words.first()?.lower.as_str(), "minus" | "negative") && open_at(words, 1);
+    let offset = negative as usize;
+    let mut number = parse_number(&words[offset..])?;
+    number.consumed += offset;
+    Some((negative, number))
+}
+
+fn day_in_month(month: &str, day: u32) -> bool {
+    let days = match month {
+        "February" => 29,
+        "April" | "June" | "September" | "November" => 30,
+        _ => 31,
+    };
+    (1..=days).contains(&day)
+}
+
+// The words as spoken, for phrases a rule recognizes but leaves alone
+fn verbatim(words: &[Word]) -> String {
+    let mut text = String::new();
+    for (i, word) in words.iter().enumerate() {
+        if i > 0 {
+            text.push_str(&word.sep);
+            text.push_str(&word.lead);
+        }
+        text.push_str(&word.core);
+        if i + 1 < words.len() {
+            text.push_str(&word.trail);
+        }
+    }
+    text
+}
+
+fn year_at(words: &[Word], i: usize) -> Option<(u32, usize)> {
+    let rest = words.get(i..)?;
+    if let Some((year, consumed)) = parse_year(rest) {
+        return Some((year, consumed));
+    }
+    // "two thousand twenty four" or "2024"
+    let number = parse_number(rest)?;
+    (number.is_whole() && (1000..=2999).contains(&number.integer))
+        .then_some((number.integer as u32, number.consumed))
+}
+
+// "March third" -> "March 3", "March third, twenty twenty four" -> "March 3, 2024",
+// "the third of March" -> "March 3", "March twenty twenty four" -> "March 2024"
+struct DateRule;
+
+impl DateRule {
+    // Day and optional year after a month at `i`
+    fn after_month(words: &[Word], month: &str, i: usize) -> Option<Rewrite> {
+        if !open_at(words, i) {
+            return None;
+        }
+
+        let (day, day_words) = match parse_ordinal(&words[i..]) {
+            Some(day) => day,
+            None => {
+                // A bare number after the month may be a year instead ("March twenty twenty four")
+                if let Some((year, consumed)) = parse_year(&words[i..]) {
+                    return Some(Rewrite::new(i + consumed, format!("{} {}", month, year)));
+                }
+                let number = parse_number(&words[i..]).filter(SpokenNumber::is_whole)?;
+                (
+                    u32::try_from(number.integer).unwrap_or(u32::MAX),
+                    number.consumed,
+                )
+            }
+        };
+
+        let day_end = i + day_words;
+        // "March forty" isn't a date, and "March 40" would read like one
+        if !day_in_month(month, day) {
+            return Some(Rewrite::new(day_end, verbatim(&words[..day_end])));
+        }
+        let last_day_word = &words[day_end - 1];
+        // Year, either directly or after a comma
+        let year_start = day_end < words.len()
+            && words[day_end].lead.is_empty()
+            && (last_day_word.is_open() || last_day_word.trail == ",");
+        if year_start {
+            if let Some((year, consumed)) = year_at(words, day_end) {
+                return Some(Rewrite::new(
+                    day_end + consumed,
+                    format!("{} {}, {}", month, day, year),
+                ));
+            }
+        }
+
+        Some(Rewrite::new(day_end, format!("{} {}", month, day)))
+    }
+}
+
+impl ItnRule for DateRule {
+    fn name(&self) -> &'static str {
+        "date"
+    }
+
+    fn apply(&self, words: &[Word]) -> Option<Rewrite> {
+        if let Some(month) = month_at(words, 0) {
+            return Self::after_month(words, month, 1);
+        }
+
+        // "the third of March"
+        let start = (words[0].lower == "the") as usize;
+        if start == 1 && !open_at(words, 1) {
+            return None;
+        }
+        let (day, consumed) = parse_ordinal(&words[start..])?;
+        let of = start + consumed;
+        if !open_at(words, of) || words[of].lower != "of" || !open_at(words, of + 1) {
+            return None;
+        }
+        let month = month_at(words, of + 1)?;
+        if !day_in_month(month, day) {
+            return Some(Rewrite::new(of + 2, verbatim(&words[..of + 2])));
+        }
+
+        let mut rewrite = Rewrite::new(of + 2, format!("{} {}", month, day));
+        let month_word = &words[of + 1];
+        if of + 2 < words.len()
+            && (month_word.is_open() || month_word.trail == ",")
+            && words[of + 2].lead.is_empty()
+        {
+            if let Some((year, consumed)) = year_at(words, of + 2) {
+                rewrite = Rewrite::new(of + 2 + consumed, format!("{} {}, {}", month, day, year));
+            }
+        }
+        Some(rewrite)
+    }
+}
+
+// "three pm" -> "3 PM", "three thirty p.m." -> "3:30 PM", "at three oh five" -> "at 3:05",
+// "three o'clock" -> "3 o'clock"
+struct TimeRule;
+
+impl TimeRule {
+    fn hour(words: &[Word]) -> Option<(u32, Option<u32>, usize)> {
+        let first = words.first()?;
+
+        // Already written as "3" or "3:30"
+        if let Some((hour, minute)) = first.core.split_once(':') {
+            let hour: u32 = hour.parse().ok()?;
+            let minute: u32 = minute.parse().ok().filter(|_| minute.len() == 2)?;
+            return (first.lead.is_empty() && (1..=12).contains(&hour) && minute < 60).then_some((
+                hour,
+                Some(minute),
+                1,
+            ));
+        }
+        let hour = if first.core.chars().all(|c| c.is_ascii_digit()) {
+            first.core.parse().ok()?
+        } else {
+            unit_value(&first.lower)? as u32
+        };
+        if !(1..=12).contains(&hour) || !first.lead.is_empty() {
+            return None;
+        }
+
+        match Self::minutes(words, 1) {
+            Some((minute, consumed)) => Some((hour, Some(minute), 1 + consumed)),
+            None => Some((hour, None, 1)),
+        }
+    }
+
+    // "thirty", "forty five", "oh five", "fifteen"
+    fn minutes(words: &[Word], i: usize) -> Option<(u32, usize)> {
+        if !open_at(words, i) {
+            return None;
+        }
+        let word = words[i].lower.as_str();
+
+        if matches!(word, "oh" | "o") && open_at(words, i + 1) {
+            let digit = unit_value(&words[i + 1].lower).filter(|digit| (1..10).contains(digit))?;
+            return Some((digit as u32, 2));
+        }
+        if let Some(value) = unit_value(word).filter(|value| *value >= 10) {
+            return Some((value as u32, 1));
+        }
+        let tens = tens_value(word).filter(|tens| *tens < 60)? as u32;
+        if open_at(words, i + 1) {
+            if let Some(unit) =
+                unit_value(&words[i + 1].lower).filter(|unit| (1..10).contains(unit))
+            {
+                return Some((tens + unit as u32, 2));
+            }
+        }
+        Some((tens, 1))
+    }
+
+    fn format(hour: u32, minute: Option<u32>) -> String {
+        match minute {
+            Some(minute) => format!("{}:{:02}", hour, minute),
+            None => hour.to_string(),
+        }
+    }
+}
+
+impl ItnRule for TimeRule {
+    fn name(&self) -> &'static str {
+        "time"
+    }
+
+    fn apply(&self, words: &[Word]) -> Option<Rewrite> {
+        // "at three thirty": a time without am/pm needs the "at" and the minutes
+        if words[0].lower == "at" && open_at(words, 1) {
+            let (hour, minute, consumed) = Self::hour(&words[1..])?;
+            let next = 1 + consumed;
+            let has_meridiem =
+                open_at(words, next) && matches!(words[next].lower.as_str(), "am" | "pm");
+            if minute.is_some() && !has_meridiem && words[1].core.chars().all(char::is_alphabetic) {
+                return Some(Rewrite::new(
+                    next,
+                    format!("at {}", Self::format(hour, minute)),
+                ));
+            }
+            return None;
+        }
+
+        let (hour, minute, consumed) = Self::hour(words)?;
+        if !open_at(words, consumed) {
+            return None;
+        }
+
+        let marker = &words[consumed];
+        match marker.lower.as_str() {
+            "am" | "pm" => {
+                let mut rewrite = Rewrite::new(
+                    consumed + 1,
+                    format!(
+                        "{} {}",
+                        Self::format(hour, minute),
+                        marker.lower.to_uppercase()
+                    ),
+                );
+                // "p.m." mid-sentence: the final dot belongs to the abbreviation
+                if marker.core.contains('.') && marker.trail == "." {
+                    let sentence_continues = words
+                        .get(consumed + 1)
+                        .is_some_and(|next| next.core.starts_with(char::is_lowercase));
+                    if sentence_continues {
+                        rewrite.trail = Some(String::new());
+                    }
+                }
+                Some(rewrite)
+            }
+            "o'clock" if minute.is_none() => {
+                Some(Rewrite::new(consumed + 1, format!("{} o'clock", hour)))
+            }
+            _ => None,
+        }
+    }
+}
+
+// "twenty five dollars" -> "$25", "five dollars and fifty cents" -> "$5.50",
+// "two point five million dollars" -> "$2.5 million", "fifty cents" -> "50¢"
+struct CurrencyRule;
+
+impl ItnRule for CurrencyRule {
+    fn name(&self) -> &'static str {
+        "currency"
+    }
+
+    fn apply(&self, words: &[Word]) -> Option<Rewrite> {
+        let number = parse_number(words)?;
+        let i = number.consumed;
+        if !open_at(words, i) {
+            return None;
+        }
+
+        if matches!(words[i].lower.as_str(), "cents" | "cent")
+            && number.is_whole()
+            && number.integer < 100
+        {
+            return Some(Rewrite::new(i + 1, format!("{}¢", number.integer)));
+        }
+
+        let symbol = CURRENCIES
+            .iter()
+            .find(|(name, _)| *name == words[i].lower)
+            .map(|(_, symbol)| *symbol)?;
+        let mut consumed = i + 1;
+
+        let amount = match (&number.scale, &number.fraction) {
+            (Some(_), _) => number.written(),
+            // "two point five dollars" -> "$2.50"
+            (None, Some(fraction)) if fraction.len() <= 2 => {
+                format!("{}.{:0<2}", number.written().split('.').next()?, fraction)
+            }
+            (None, _) => {
+                // "and fifty cents" / "fifty cents"
+                let cents_start = if open_at(words, consumed) && words[consumed].lower == "and" {
+                    consumed + 1
+                } else {
+                    consumed
+                };
+                let cents = open_at(words, cents_start)
+                    .then(|| parse_number(&words[cents_start..]))
+                    .flatten()
+                    .filter(|cents| cents.is_whole() && cents.integer < 100)
+                    .filter(|cents| {
+                        open_at(words, cents_start + cents.consumed)
+                            && matches!(
+                                words[cents_start + cents.consumed].lower.as_str(),
+                                "cents" | "cent"
+                            )
+                    });
+
+                match cents {
+                    Some(cents) if number.fraction.is_none() => {
+                        consumed = cents_start + cents.consumed + 1;
+                        format!("{}.{:02}", number.written(), cents.integer)
+                    }
+                    _ => number.written(),
+                }
+            }
+        };
+
+        Some(Rewrite::new(consumed, format!("{}{}", symbol, amount)))
+    }
+}
+
+// "fifty percent" -> "50%"
+struct PercentRule;
+
+impl ItnRule for PercentRule {
+    fn name(&self) -> &'static str {
+        "percent"
+    }
+
+    fn apply(&self, words: &[Word]) -> Option<Rewrite> {
+        let (negative, number) = parse_signed(words)?;
+        let i = number.consumed;
+        if !open_at(words, i) {
+            return None;
+        }
+
+        let consumed = match words[i].lower.as_str() {
+            "percent" => i + 1,
+            "per" if open_at(words, i + 1) && words[i + 1].lower == "cent" => i + 2,
+            _ => return None,
+        };
+        let sign = if negative { "-" } else { "" };
+        Some(Rewrite::new(
+            consumed,
+            format!("{}{}%", sign, number.written()),
+        ))
+    }
+}
+
+// "five kilometers" -> "5 km", "minus ten degrees celsius" -> "-10°C"
+struct UnitRule;
+
+impl ItnRule for UnitRule {
+    fn name(&self) -> &'static str {
+        "unit"
+    }
+
+    fn apply(&self, words: &[Word]) -> Option<Rewrite> {
+        let (negative, number) = parse_signed(words)?;
+        let i = number.consumed;
+        if !open_at(words, i) || number.scale.is_some() {
+            return None;
+        }
+
+        let (phrase, symbol) = UNITS.iter().find(|(phrase, _)| {
+            phrase.iter().enumerate().all(|(offset, word)| {
+                (offset == 0 || open_at(words, i + offset))
+                    && words.get(i + offset).is_some_and(|w| w.lower == *word)
+            })
+        })?;
+
+        let sign = if negative { "-" } else { "" };
+        let space = if ATTACHED_UNITS.contains(symbol) {
+            ""
+        } else {
+            " "
+        };
+        Some(Rewrite::new(
+            i + phrase.len(),
+            format!("{}{}{}{}", sign, number.written(), space, symbol),
+        ))
+    }
+}
+
+// "in nineteen ninety nine" -> "in 1999", "the eighteen hundreds" -> "the 1800s"
+struct YearRule;
+
+impl ItnRule for YearRule {
+    fn name(&self) -> &'static str {
+        "year"
+    }
+
+    fn apply(&self, words: &[Word]) -> Option<Rewrite> {
+        // "the eighteen hundreds" -> "the 1800s"
+        let century = unit_value(&words[0].lower).filter(|value| (11..=19).contains(value));
+        if let Some(century) = century.filter(|_| open_at(words, 1) && words[1].lower == "hundreds")
+        {
+            return Some(Rewrite::new(2, format!("{}00s", century)));
+        }
+
+        // "two thousand and five" -> "2005"; a round "two thousand" stays a grouped cardinal
+        if words.len() > 1 && words[0].lower == "two" && words[1].lower == "thousand" {
+            let number = parse_number(words)
+                .filter(|number| number.is_whole() && (2001..=2099).contains(&number.integer))?;
+            return Some(Rewrite::new(number.consumed, number.integer.to_string()));
+        }
+
+        let (year, consumed) = parse_year(words)?;
+        Some(Rewrite::new(consumed, year.to_string()))
+    }
+}
+
+// Spoken numbers of ten or more, decimals and negatives; "one" to "nine" stay words
+struct CardinalRule;
+
+impl CardinalRule {
+    // "ten to five", "six ten", "five or twenty": when one number of a pair stays a word,
+    // converting the other reads like a typo ("10 to five"), so both stay words
+    fn mixed_pair(words: &[Word], first: &SpokenNumber) -> Option<usize> {
+        let mut i = first.consumed;
+        if open_at(words, i) && matches!(words[i].lower.as_str(), "to" | "or") {
+            i += 1;
+        }
+        if !open_at(words, i) {
+            return None;
+        }
+        // Not across lines, nor into an amount or measurement ("one twenty dollars")
+        if words[first.consumed..=i]
+            .iter()
+            .any(|word| word.sep.contains('\n'))
+        {
+            return None;
+        }
+        let measured = [&CurrencyRule as &dyn ItnRule, &PercentRule, &UnitRule]
+            .iter()
+            .any(|rule| rule.apply(&words[i..]).is_some());
+        if measured {
+            return None;
+        }
+        let second =
+            parse_number(&words[i..]).filter(|second| second.spoken && second.is_whole())?;
+        let stays_word = |number: &SpokenNumber| number.integer < 10;
+        (stays_word(first) != stays_word(&second)).then_some(i + second.consumed)
+    }
+}
+
+impl ItnRule for CardinalRule {
+    fn name(&self) -> &'static str {
+        "cardinal"
+    }
+
+    fn apply(&self, words: &[Word]) -> Option<Rewrite> {
+        let (negative, number) = parse_signed(words)?;
+        if !number.spoken {
+            return None;
+        }
+        if number.is_whole() && !negative {
+            if let Some(end) = Self::mixed_pair(words, &number) {
+                return Some(Rewrite::new(end, verbatim(&words[..end])));
+            }
+            if number.integer < 10 {
+                return None;
+            }
+        }
+        let sign = if negative { "-" } else { "" };
+        Some(Rewrite::new(
+            number.consumed,
+            format!("{}{}", sign, number.written()),
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn check(cases: &[(&str, &str)]) {
+        let rules = rules();
+        for (spoken, written) in cases {
+            assert_eq!(rules.normalize(spoken), *written, "input: {:?}", spoken);
+        }
+    }
+
+    #[test]
+    fn cardinals() {
+        check(&[
+            ("I have two apples", "I have two apples"),
+            ("we invited ten people", "we invited 10 people"),
+            ("twenty five people came", "25 people came"),
+            ("twenty-five people came", "25 people came"),
+            ("one hundred and five", "105"),
+            ("two hundred six", "206"),
+            ("twenty five hundred", "2,500"),
+            ("three thousand four hundred", "3,400"),
+            ("twenty five thousand", "25,000"),
+            ("one hundred thousand", "100,000"),
+            ("five million people", "5 million people"),
+            ("two point five million users", "2.5 million users"),
+            ("five million two hundred", "5,000,200"),
+            ("three point one four", "3.14"),
+            ("it was minus five outside", "it was -5 outside"),
+            ("eleven, twelve", "11, 12"),
+            ("Twenty people.", "20 people."),
+            ("one of them", "one of them"),
+            ("a million reasons", "1 million reasons"),
+            ("a lot of people", "a lot of people"),
+            ("bread and butter", "bread and butter"),
+            ("one hundred and", "100 and"),
+            ("already 25 people", "already 25 people"),
+            ("a well-known fact", "a well-known fact"),
+        ]);
+    }
+
+    #[test]
+    fn number_pairs() {
+        check(&[
+            ("ten to five", "ten to five"),
+            ("it's about six ten", "it's about six ten"),
+            ("five or twenty people", "five or twenty people"),
+            ("ten to twenty people", "10 to 20 people"),
+            ("two or three people", "two or three people"),
+            ("ten, five", "10, five"),
+            ("let's meet at six ten", "let's meet at 6:10"),
+            ("one twenty dollars", "one $20"),
+        ]);
+    }
+
+    #[test]
+    fn currency() {
+        check(&[
+            ("twenty five dollars", "$25"),
+            ("it costs five dollars and fifty cents", "it costs $5.50"),
+            ("five dollars fifty cents", "$5.50"),
+            ("fifty cents", "50¢"),
+            ("one dollar", "$1"),
+            ("two point five dollars", "$2.50"),
+            ("two point five million dollars", "$2.5 million"),
+            ("a hundred bucks", "$100"),
+            ("twenty euros.", "€20."),
+            ("25 dollars", "$25"),
+            ("1,200 dollars", "$1,200"),
+            ("1200 dollars", "$1,200"),
+            ("twelve thousand dollars", "$12,000"),
+        ]);
+    }
+
+    #[test]
+    fn percentages() {
+        check(&[
+            ("fifty percent", "50%"),
+            ("five percent of users", "5% of users"),
+            ("twelve point five per cent", "12.5%"),
+            ("minus three percent", "-3%"),
+            ("10 percent", "10%"),
+        ]);
+    }
+
+    #[test]
+    fn units() {
+        check(&[
+            ("five kilometers", "5 km"),
+            ("one kilometer", "1 km"),
+            ("ran five kilometres today", "ran 5 km today"),
+            ("ten kilograms", "10 kg"),
+            ("two point five liters", "2.5 L"),
+            ("sixty miles per hour", "60 mph"),
+            ("a hundred kilometers per hour", "100 km/h"),
+            ("twenty degrees celsius", "20°C"),
+            ("minus ten degrees", "-10°"),
+            ("sixteen gigabytes of memory", "16 GB of memory"),
+            ("5 miles", "5 mi"),
+            ("many kilometers", "many kilometers"),
+        ]);
+    }
+
+    #[test]
+    fn times() {
+        check(&[
+            ("at three pm", "at 3 PM"),
+            ("three p.m. tomorrow", "3 PM tomorrow"),
+            ("see you at three p.m.", "see you at 3 PM."),
+            ("three thirty pm", "3:30 PM"),
+            ("three oh five am", "3:05 AM"),
+            ("eleven forty five pm", "11:45 PM"),
+            ("let's meet at three thirty", "let's meet at 3:30"),
+            ("at three", "at three"),
+            ("three o'clock", "3 o'clock"),
+            ("3 pm", "3 PM"),
+            ("3:30 pm", "3:30 PM"),
+            ("I am here", "I am here"),
+            ("thirteen pm", "13 pm"),
+        ]);
+    }
+
+    #[test]
+    fn dates() {
+        check(&[
+            ("March third", "March 3"),
+            ("march third at three pm", "March 3 at 3 PM"),
+            ("the troops march on", "the troops march on"),
+            ("we march three miles", "we march 3 mi"),
+            ("you may first try", "you may first try"),
+            ("on may first", "on may first"),
+            ("March third at three pm", "March 3 at 3 PM"),
+            ("on March twenty first", "on March 21"),
+            ("on March twenty-first", "on March 21"),
+            ("March 3rd", "March 3"),
+            ("March three", "March 3"),
+            ("March third, twenty twenty four", "March 3, 2024"),
+            ("March third twenty twenty four", "March 3, 2024"),
+            ("March third, 2024.", "March 3, 2024."),
+            ("the third of March", "March 3"),
+            (
+                "on the fifth of November, nineteen oh five",
+                "on November 5, 1905",
+            ),
+            ("March twenty twenty four", "March 2024"),
+            ("May first", "May 1"),
+            ("you may first try", "you may first try"),
+            ("March forty", "March forty"),
+            ("March thirty second", "March thirty second"),
+            ("February thirtieth", "February thirtieth"),
+            ("the thirty first of April", "the thirty first of April"),
+            ("April thirtieth", "April 30"),
+            (
+                "December thirty first, two thousand and one",
+                "December 31, 2001",
+            ),
+        ]);
+    }
+
+    #[test]
+    fn years() {
+        check(&[
+            ("in nineteen ninety nine", "in 1999"),
+            ("in twenty twenty four", "in 2024"),
+            ("back in nineteen oh five", "back in 1905"),
+            ("the eighteen hundreds", "the 1800s"),
+            ("around eighteen hundred", "around 1800"),
+            ("in two thousand and five", "in 2005"),
+            ("two thousand twenty four", "2024"),
+            ("two thousand people", "2,000 people"),
+            ("seventeen seventy six", "1776"),
+            ("twenty five", "25"),
+        ]);
+    }
+
+    #[test]
+    fn punctuation_and_spacing() {
+        check(&[
+            ("It costs twenty dollars, right?", "It costs $20, right?"),
+            ("(twenty five dollars)", "($25)"),
+            ("twenty, five", "20, five"),
+            ("line one\ntwenty five dollars\n", "line one\n$25\n"),
+            ("  fifty percent  ", "  50%  "),
+            ("", ""),
+        ]);
+    }
+
+    #[test]
+    fn protected_ranges() {
+        let rules = rules();
+        let text = "I owe ten dollars. Call me at five five five, ten. Thanks";
+        let snippet = text.find("five five").unwrap()..text.find(". Thanks").unwrap();
+        let (output, ranges) = rules.normalize_except(text, &[snippet]);
+        assert_eq!(output, "I owe $10. Call me at five five five, ten. Thanks");
+        assert_eq!(&output[ranges[0].clone()], "five five five, ten");
+
+        let (output, ranges) = rules.normalize_except("ten and ten", &[0..3, 8..11]);
+        assert_eq!(output, "ten and ten");
+        assert_eq!(ranges, [0..3, 8..11]);
+    }
+
+    #[test]
+    fn unknown_languages_have_no_rules() {
+        assert!(RuleSet::for_language("xx").is_none());
+        assert_eq!(RuleSet::for_language("en").unwrap().language(), "en");
+    }
+}
@@ -0,0 +1,5 @@
+pub mod engine;
+pub mod english;
+pub mod numbers;
+
+pub use engine::{ItnRule, Rewrite, RuleSet, Word};
@@ -0,0 +1,428 @@
+use super::engine::Word;
+
+const UNITS: &[&str] = &[
+    "zero",
+    "one",
+    "two",
+    "three",
+    "four",
+    "five",
+    "six",
+    "seven",
+    "eight",
+    "nine",
+    "ten",
+    "eleven",
+    "twelve",
+    "thirteen",
+    "fourteen",
+    "fifteen",
+    "sixteen",
+    "seventeen",
+    "eighteen",
+    "nineteen",
+];
+const TENS: &[(&str, u64)] = &[
+    ("twenty", 20),
+    ("thirty", 30),
+    ("forty", 40),
+    ("fifty", 50),
+    ("sixty", 60),
+    ("seventy", 70),
+    ("eighty", 80),
+    ("ninety", 90),
+];
+const SCALES: &[(&str, u64)] = &[
+    ("thousand", 1_000),
+    ("million", 1_000_000),
+    ("billion", 1_000_000_000),
+    ("trillion", 1_000_000_000_000),
+];
+const ORDINAL_UNITS: &[&str] = &[
+    "",
+    "first",
+    "second",
+    "third",
+    "fourth",
+    "fifth",
+    "sixth",
+    "seventh",
+    "eighth",
+    "ninth",
+    "tenth",
+    "eleventh",
+    "twelfth",
+    "thirteenth",
+    "fourteenth",
+    "fifteenth",
+    "sixteenth",
+    "seventeenth",
+    "eighteenth",
+    "nineteenth",
+];
+const ORDINAL_TENS: &[(&str, u32)] = &[
+    ("twentieth", 20),
+    ("thirtieth", 30),
+    ("fortieth", 40),
+    ("fiftieth", 50),
+    ("sixtieth", 60),
+    ("seventieth", 70),
+    ("eightieth", 80),
+    ("ninetieth", 90),
+];
+
+pub fn unit_value(word: &str) -> Option<u64> {
+    UNITS
+        .iter()
+        .position(|unit| *unit == word)
+        .map(|value| value as u64)
+}
+
+pub fn tens_value(word: &str) -> Option<u64> {
+    TENS.iter()
+        .find(|(tens, _)| *tens == word)
+        .map(|(_, value)| *value)
+}
+
+fn scale_value(word: &str) -> Option<u64> {
+    SCALES
+        .iter()
+        .find(|(scale, _)| *scale == word)
+        .map(|(_, value)| *value)
+}
+
+// Single spoken digit, as used after "point" and in "oh five"
+fn digit_value(word: &str) -> Option<u64> {
+    match word {
+        "oh" | "o" => Some(0),
+        _ => unit_value(word).filter(|value| *value < 10),
+    }
+}
+
+// A word may start or continue a phrase only without punctuation in between
+fn continues(words: &[Word], i: usize) -> bool {
+    i < words.len() && i > 0 && words[i - 1].is_open() && words[i].lead.is_empty()
+}
+
+// Leading brackets or quotes are fine, currency or sign symbols mean it's already written
+fn plain_start(word: &Word) -> bool {
+    !word.lead.contains(['$', '€', '£', '¥', '-', '+', '#'])
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct SpokenNumber {
+    pub consumed: usize,
+    // Whole digits and optional decimal digits
+    pub integer: u64,
+    pub fraction: Option<String>,
+    // Ends in "million"/"billion"/"trillion" with a small multiplier, written "2.5 million"
+    pub scale: Option<(&'static str, u64)>,
+    // Spoken with words rather than digits
+    pub spoken: bool,
+}
+
+impl SpokenNumber {
+    // The multiplier when written with a scale word, otherwise the full value
+    pub fn written(&self) -> String {
+        // Grouped from four digits on, whether spoken ("twenty five hundred") or written ("1200")
+        let digits = group_thousands;
+
+        match (&self.scale, &self.fraction) {
+            (Some((word, size)), fraction) => {
+                let mantissa = self.integer / size;
+                match fraction {
+                    Some(fraction) => format!("{}.{} {}", mantissa, fraction, word),
+                    None => format!("{} {}", mantissa, word),
+                }
+            }
+            (None, Some(fraction)) => format!("{}.{}", digits(self.integer), fraction),
+            (None, None) => digits(self.integer),
+        }
+    }
+
+    pub fn is_whole(&self) -> bool {
+        self.fraction.is_none() && self.scale.is_none()
+    }
+}
+
+fn group_thousands(value: u64) -> String {
+    let digits = value.to_string();
+    let mut grouped = String::with_capacity(digits.len() + digits.len() / 3);
+    for (i, c) in digits.chars().enumerate() {
+        if i > 0 && (digits.len() - i).is_multiple_of(3) {
+            grouped.push(',');
+        }
+        grouped.push(c);
+    }
+    grouped
+}
+
+#[derive(Clone, Copy, PartialEq)]
+enum Last {
+    Nothing,
+    Unit,
+    Tens,
+    Hundred,
+    Scale,
+    And,
+}
+
+// Parses "twenty five", "a hundred", "two hundred and six", "three point one four",
+// "two point five million", or a number already written in digits
+pub fn parse_number(words: &[Word]) -> Option<SpokenNumber> {
+    let first = words.first()?;
+    if !plain_start(first) {
+        return None;
+    }
+
+    if let Some((integer, fraction)) = parse_digits(&first.core) {
+        let mut number = SpokenNumber {
+            consumed: 1,
+            integer,
+            fraction,
+            scale: None,
+            spoken: false,
+        };
+        apply_trailing_scale(words, &mut number);
+        return Some(number);
+    }
+
+    let mut total: u64 = 0;
+    let mut current: u64 = 0;
+    let mut last = Last::Nothing;
+    let mut last_scale = u64::MAX;
+    let mut scales_used = 0;
+    let mut consumed = 0;
+
+    while consumed < words.len() && (consumed == 0 || continues(words, consumed)) {
+        let word = words[consumed].lower.as_str();
+
+        // "a hundred", "a million"
+        let one_of = consumed == 0
+            && word == "a"
+            && continues(words, 1)
+            && (words[1].lower == "hundred" || scale_value(&words[1].lower).is_some());
+
+        if one_of {
+            current = 1;
+            last = Last::Unit;
+        } else if let Some(value) = unit_value(word) {
+            let allowed = match last {
+                Last::Nothing | Last::Hundred | Last::Scale | Last::And => true,
+                // "twenty five" but not "twenty fifteen"
+                Last::Tens => value > 0 && value < 10,
+                Last::Unit => false,
+            };
+            if !allowed || (value == 0 && last != Last::Nothing) {
+                break;
+            }
+            current += value;
+            last = Last::Unit;
+        } else if let Some(value) = tens_value(word) {
+            if !matches!(
+                last,
+                Last::Nothing | Last::Hundred | Last::Scale | Last::And
+            ) {
+                break;
+            }
+            current += value;
+            last = Last::Tens;
+        } else if word == "hundred" {
+            if !matches!(last, Last::Unit | Last::Tens) || current == 0 || current >= 100 {
+                break;
+            }
+            current *= 100;
+            last = Last::Hundred;
+        } else if let Some(size) = scale_value(word) {
+            if !matches!(last, Last::Unit | Last::Tens | Last::Hundred)
+                || current == 0
+                || size >= last_scale
+            {
+                break;
+            }
+            total += current * size;
+            current = 0;
+            last_scale = size;
+            scales_used += 1;
+            last = Last::Scale;
+        } else if word == "and" && matches!(last, Last::Hundred | Last::Scale) {
+            last = Last::And;
+        } else {
+            break;
+        }
+        consumed += 1;
+    }
+
+    // A dangling "and" belongs to the sentence, not the number
+    if last == Last::And {
+        consumed -= 1;
+    }
+    if consumed == 0 {
+        return None;
+    }
+
+    let mut number = SpokenNumber {
+        consumed,
+        integer: total + current,
+        fraction: None,
+        scale: None,
+        spoken: true,
+    };
+
+    // "point" followed by single digits
+    if continues(words, number.consumed) && words[number.consumed].lower == "point" {
+        let mut fraction = String::new();
+        let mut i = number.consumed + 1;
+        while continues(words, i) {
+            match digit_value(&words[i].lower) {
+                Some(digit) => fraction.push_str(&digit.to_string()),
+                None => break,
+            }
+            i += 1;
+        }
+        if !fraction.is_empty() {
+            number.fraction = Some(fraction);
+            number.consumed = i;
+        }
+    }
+
+    if number.fraction.is_some() {
+        apply_trailing_scale(words, &mut number);
+    } else if last == Last::Scale && scales_used == 1 && current == 0 && last_scale >= 1_000_000 {
+        // "five million" reads better than "5,000,000"
+        let name = SCALES
+            .iter()
+            .find(|(_, size)| *size == last_scale)
+            .map(|(name, _)| *name)?;
+        if number.integer / last_scale < 1000 {
+            number.scale = Some((name, last_scale));
+        }
+    }
+
+    Some(number)
+}
+
+// "2.5 million" or "two point five million"
+fn apply_trailing_scale(words: &[Word], number: &mut SpokenNumber) {
+    if !continues(words, number.consumed) {
+        return;
+    }
+    let Some(size) = scale_value(&words[number.consumed].lower) else {
+        return;
+    };
+    if size < 1_000_000 || number.integer >= 1000 {
+        return;
+    }
+    let name = SCALES
+        .iter()
+        .find(|(_, s)| *s == size)
+        .map(|(name, _)| *name)
+        .unwrap_or("");
+    number.integer *= size;
+    number.scale = Some((name, size));
+    number.consumed += 1;
+}
+
+// "25", "2.5" or "1,000"
+fn parse_digits(core: &str) -> Option<(u64, Option<String>)> {
+    let (integer, fraction) = match core.split_once('.') {
+        Some((integer, fraction)) => (integer, Some(fraction)),
+        None => (core, None),
+    };
+
+    let groups: Vec<&str> = integer.split(',').collect();
+    let valid_groups = groups.len() == 1
+        || (groups[0].len() <= 3 && groups[1..].iter().all(|group| group.len() == 3));
+    if integer.is_empty()
+        || !valid_groups
+        || !groups
+            .iter()
+            .all(|group| !group.is_empty() && group.chars().all(|c| c.is_ascii_digit()))
+    {
+        return None;
+    }
+    if let Some(fraction) = fraction {
+        if fraction.is_empty() || !fraction.chars().all(|c| c.is_ascii_digit()) {
+            return None;
+        }
+    }
+
+    let value = groups.concat().parse().ok()?;
+    Some((value, fraction.map(str::to_string)))
+}
+
+// "third", "twenty first", "twenty-first", or "3rd"
+pub fn parse_ordinal(words: &[Word]) -> Option<(u32, usize)> {
+    let first = words.first()?;
+    if !plain_start(first) {
+        return None;
+    }
+
+    let suffixed = ["st", "nd", "rd", "th"]
+        .iter()
+        .find_map(|suffix| first.lower.strip_suffix(suffix))
+        .filter(|digits| !digits.is_empty() && digits.chars().all(|c| c.is_ascii_digit()));
+    if let Some(digits) = suffixed {
+        return Some((digits.parse().ok()?, 1));
+    }
+
+    if let Some(value) = ORDINAL_UNITS
+        .iter()
+        .position(|ordinal| !ordinal.is_empty() && *ordinal == first.lower)
+    {
+        return Some((value as u32, 1));
+    }
+    if let Some((_, value)) = ORDINAL_TENS
+        .iter()
+        .find(|(ordinal, _)| *ordinal == first.lower)
+    {
+        return Some((*value, 1));
+    }
+
+    let tens = tens_value(&first.lower)? as u32;
+    if !continues(words, 1) {
+        return None;
+    }
+    let unit = ORDINAL_UNITS
+        .iter()
+        .take(10)
+        .position(|ordinal| !ordinal.is_empty() && *ordinal == words[1].lower)?;
+    Some((tens + unit as u32, 2))
+}
+
+// Years as spoken in pairs: "nineteen ninety nine", "twenty twenty four", "nineteen oh five",
+// "eighteen hundred"
+pub fn parse_year(words: &[Word]) -> Option<(u32, usize)> {
+    let first = words.first()?;
+    if !plain_start(first) {
+        return None;
+    }
+
+    let century = match first.lower.as_str() {
+        "twenty" => 20,
+        word => unit_value(word).filter(|value| (16..=19).contains(value))?,
+    } as u32;
+    if !continues(words, 1) {
+        return None;
+    }
+
+    let second = words[1].lower.as_str();
+    if second == "hundred" && century < 20 {
+        return Some((century * 100, 2));
+    }
+    if matches!(second, "oh" | "o") && continues(words, 2) {
+        let digit = digit_value(&words[2].lower).filter(|digit| *digit > 0)? as u32;
+        return Some((century * 100 + digit, 3));
+    }
+
+    if let Some(value) = unit_value(second).filter(|value| *value >= 10) {
+        return Some((century * 100 + value as u32, 2));
+    }
+    let tens = tens_value(second)? as u32;
+    if continues(words, 2) {
+        if let Some(unit) = unit_value(&words[2].lower).filter(|unit| (1..10).contains(unit)) {
+            return Some((century * 100 + tens + unit as u32, 3));
+        }
+    }
+    Some((century * 100 + tens, 2))
+}
@@ -0,0 +1,12 @@
+mod case;
+pub mod cleanup;
+pub mod dictation;
+pub mod itn;
+pub mod snippets;
+pub mod vocabulary;
+
+pub use cleanup::{clean_segments, clean_text, CleanupLevel};
+pub use dictation::{interpret_segments, CommandSet, DictationCommand};
+pub use itn::RuleSet as ItnRuleSet;
+pub use snippets::{Snippet, SnippetExpander};
+pub use vocabulary::{CorrectedTranscript, Substitution, VocabularyCorrector};
@@ -0,0 +1,332 @@
+use chrono::format::{Item, StrftimeItems};
+use chrono::Local;
+use serde::{Deserialize, Serialize};
+use std::ops::Range;
+
+const DEFAULT_DATE_FORMAT: &str = "%Y-%m-%d";
+const DEFAULT_TIME_FORMAT: &str = "%H:%M";
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct Snippet {
+    #[serde(default = "crate::vocabulary::new_entry_id")]
+    pub id: String,
+    // Spoken phrase, e.g. "insert my signature"
+    pub trigger: String,
+    // Supports {date}, {time} and {clipboard}; date and time take an optional
+    // strftime format, e.g. {date:%d %B %Y}
+    pub text: String,
+}
+
+impl Snippet {
+    pub fn normalized(mut self) -> anyhow::Result<Self> {
+        self.trigger = self.trigger.trim().to_string();
+        if trigger_words(&self.trigger).is_empty() {
+            return Err(anyhow::anyhow!("Snippet needs a trigger phrase"));
+        }
+        Ok(self)
+    }
+}
+
+#[derive(Serialize, Clone, Debug, PartialEq)]
+pub struct ExpandedSnippet {
+    pub snippet_id: String,
+    pub trigger: String,
+    // Byte range of the expanded text in the output
+    pub start: usize,
+    pub end: usize,
+}
+
+#[derive(Serialize, Clone, Debug, PartialEq)]
+pub struct SnippetExpansion {
+    pub text: String,
+    pub expanded: Vec<ExpandedSnippet>,
+}
+
+impl SnippetExpansion {
+    pub fn ranges(&self) -> Vec<Range<usize>> {
+        self.expanded.iter().map(|snippet| snippet.start..snippet.end).collect()
+    }
+}
+
+struct Trigger<'a> {
+    snippet: &'a Snippet,
+    words: Vec<String>,
+}
+
+// Replaces spoken trigger phrases in a transcript with stored snippet text
+pub struct SnippetExpander<'a> {
+    // Longest triggers first, so "insert standup template notes" beats "insert standup template"
+    triggers: Vec<Trigger<'a>>,
+}
+
+impl<'a> SnippetExpander<'a> {
+    pub fn new(snippets: &'a [Snippet]) -> Self {
+        let mut triggers: Vec<Trigger> = snippets
+            .iter()
+            .map(|snippet| Trigger {
+                snippet,
+                words: trigger_words(&snippet.trigger),
+            })
+            .filter(|trigger| !trigger.words.is_empty())
+            .collect();
+        triggers.sort_by_key(|trigger| std::cmp::Reverse(trigger.words.len()));
+
+        Self { triggers }
+    }
+
+    pub fn expand(&self, text: &str) -> SnippetExpansion {
+        let words = word_spans(text);
+        let lowered: Vec<String> = words
+            .iter()
+            .map(|span| normalize_word(&text[span.clone()]))
+            .collect();
+
+        let mut output = String::with_capacity(text.len());
+        let mut expanded = Vec::new();
+        let mut copied_to = 0;
+        let mut clipboard: Option<Option<String>> = None;
+        let mut i = 0;
+
+        while i < words.len() {
+            let Some(trigger) = self.triggers.iter().find(|trigger| {
+                lowered[i..].starts_with(&trigger.words)
+                    && joined_by_whitespace(text, &words[i..i + trigger.words.len()])
+            }) else {
+                i += 1;
+                continue;
+            };
+
+            let start = words[i].start;
+            let last = i + trigger.words.len() - 1;
+            // Whisper punctuates the trigger as its own sentence ("Insert my signature.")
+            let end = words[last].end
+                + text[words[last].end..]
+                    .chars()
+                    .take_while(|c| matches!(c, '.' | ',' | '!' | '?' | ';' | ':'))
+                    .map(char::len_utf8)
+                    .sum::<usize>();
+
+            output.push_str(&text[copied_to..start]);
+            let snippet_start = output.len();
+            output.push_str(&render(&trigger.snippet.text, &mut clipboard));
+
+            expanded.push(ExpandedSnippet {
+                snippet_id: trigger.snippet.id.clone(),
+                trigger: text[start..end].to_string(),
+                start: snippet_start,
+                end: output.len(),
+            });
+
+            copied_to = end;
+            i = last + 1;
+        }
+        output.push_str(&text[copied_to..]);
+
+        SnippetExpansion {
+            text: output,
+            expanded,
+        }
+    }
+}
+
+// Fills in placeholders; unknown ones are left as typed so mistakes stay visible
+fn render(template: &str, clipboard: &mut Option<Option<String>>) -> String {
+    let mut output = String::with_capacity(template.len());
+    let mut rest = template;
+
+    while let Some(open) = rest.find('{') {
+        output.push_str(&rest[..open]);
+        let Some(close) = rest[open..].find('}').map(|close| open + close) else {
+            rest = &rest[open..];
+            break;
+        };
+
+        let placeholder = &rest[open + 1..close];
+        let (name, format) = match placeholder.split_once(':') {
+            Some((name, format)) => (name.trim(), Some(format)),
+            None => (placeholder.trim(), None),
+        };
+
+        let value = match name {
+            "date" => format_now(format.unwrap_or(DEFAULT_DATE_FORMAT)),
+            "time" => format_now(format.unwrap_or(DEFAULT_TIME_FORMAT)),
+            // Only touch the clipboard when a snippet asks for it, and only once per transcript
+            "clipboard" => clipboard.get_or_insert_with(read_clipboard).clone(),
+            _ => None,
+        };
+
+        match value {
+            Some(value) => output.push_str(&value),
+            None => output.push_str(&rest[open..=close]),
+        }
+        rest = &rest[close + 1..];
+    }
+    output.push_str(rest);
+
+    output
+}
+
+fn format_now(format: &str) -> Option<String> {
+    let items: Vec<Item> = StrftimeItems::new(format).collect();
+    // Formatting with an invalid specifier panics, so check first
+    if items.iter().any(|item| matches!(item, Item::Error)) {
+        return None;
+    }
+    Some(Local::now().format_with_items(items.into_iter()).to_string())
+}
+
+fn read_clipboard() -> Option<String> {
+    match arboard::Clipboard::new().and_then(|mut clipboard| clipboard.get_text()) {
+        Ok(text) => Some(text),
+        Err(e) => {
+            eprintln!("⚠️  Could not read clipboard for snippet: {}", e);
+            None
+        }
+    }
+}
+
+fn trigger_words(trigger: &str) -> Vec<String> {
+    word_spans(trigger)
+        .into_iter()
+        .map(|span| normalize_word(&trigger[span]))
+        .collect()
+}
+
+fn normalize_word(word: &str) -> String {
+    word.chars()
+        .filter(|c| c.is_alphanumeric())
+        .flat_map(char::to_lowercase)
+        .collect()
+}
+
+// Whisper may add commas inside a phrase ("insert, my signature"), which still
+// counts; sentence breaks don't
+fn joined_by_whitespace(text: &str, words: &[Range<usize>]) -> bool {
+    words.windows(2).all(|pair| {
+        text[pair[0].end..pair[1].start]
+            .chars()
+            .all(|c| c.is_whitespace() || c == ',' || c == '-')
+    })
+}
+
+fn word_spans(text: &str) -> Vec<Range<usize>> {
+    let mut spans = Vec::new();
+    let mut start = None;
+
+    for (i, c) in text.char_indices() {
+        let in_word = c.is_alphanumeric() || c == '\'';
+        match (in_word, start) {
+            (true, None) => start = Some(i),
+            (false, Some(s)) => {
+                spans.push(s..i);
+                start = None;
+            }
+            _ => {}
+        }
+    }
+    if let Some(s) = start {
+        spans.push(s..text.len());
+    }
+
+    spans
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn snippet(trigger: &str, text: &str) -> Snippet {
+        Snippet {
+            id: trigger.replace(' ', "-"),
+            trigger: trigger.to_string(),
+            text: text.to_string(),
+        }
+    }
+
+    fn snippets() -> Vec<Snippet> {
+        vec![
+            snippet("insert my signature", "Best,\nSam"),
+            snippet("insert standup template", "Yesterday:\nToday:"),
+            snippet("insert standup template notes", "Notes:"),
+            snippet("signature ten", "S10"),
+        ]
+    }
+
+    #[test]
+    fn expands_triggers() {
+        let cases: &[(&str, &str)] = &[
+            ("Thanks. Insert my signature.", "Thanks. Best,\nSam"),
+            ("insert, my signature please", "Best,\nSam please"),
+            ("INSERT MY SIGNATURE", "Best,\nSam"),
+            // Longest trigger wins
+            ("insert standup template notes", "Notes:"),
+            ("insert standup template now", "Yesterday:\nToday: now"),
+            ("use signature ten here", "use S10 here"),
+            // Not across sentence breaks, or on part of a trigger
+            ("insert my. Signature", "insert my. Signature"),
+            ("insert my signatures", "insert my signatures"),
+            ("my signature", "my signature"),
+            ("", ""),
+        ];
+
+        let snippets = snippets();
+        let expander = SnippetExpander::new(&snippets);
+        for (input, expected) in cases {
+            assert_eq!(expander.expand(input).text, *expected, "input: {:?}", input);
+        }
+    }
+
+    #[test]
+    fn records_expansions() {
+        let snippets = snippets();
+        let expansion = SnippetExpander::new(&snippets).expand("Hi. Insert my signature. Bye");
+        assert_eq!(
+            expansion.expanded,
+            [ExpandedSnippet {
+                snippet_id: "insert-my-signature".to_string(),
+                trigger: "Insert my signature.".to_string(),
+                start: 4,
+                end: 13,
+            }]
+        );
+        assert_eq!(&expansion.text[expansion.ranges()[0].clone()], "Best,\nSam");
+    }
+
+    #[test]
+    fn renders_placeholders() {
+        let year = Local::now().format("%Y").to_string();
+        let cases: &[(&str, String)] = &[
+            ("plain text", "plain text".to_string()),
+            ("{date:%Y}", year.clone()),
+            ("Year { date:%Y} ends", format!("Year {} ends", year)),
+            ("{clipboard} and {clipboard}", "copied and copied".to_string()),
+            // Unknown placeholders, bad formats and unclosed braces stay as typed
+            ("{name}", "{name}".to_string()),
+            ("{date:%Q}", "{date:%Q}".to_string()),
+            ("{time:%}", "{time:%}".to_string()),
+            ("open {date", "open {date".to_string()),
+        ];
+
+        for (template, expected) in cases {
+            let mut clipboard = Some(Some("copied".to_string()));
+            assert_eq!(render(template, &mut clipboard), *expected, "template: {:?}", template);
+        }
+    }
+
+    #[test]
+    fn default_formats() {
+        let mut clipboard = Some(None);
+        let date = render("{date}", &mut clipboard);
+        let time = render("{time}", &mut clipboard);
+        assert!(chrono::NaiveDate::parse_from_str(&date, DEFAULT_DATE_FORMAT).is_ok(), "{}", date);
+        assert!(chrono::NaiveTime::parse_from_str(&time, DEFAULT_TIME_FORMAT).is_ok(), "{}", time);
+        // An unreadable clipboard leaves the placeholder visible
+        assert_eq!(render("{clipboard}", &mut clipboard), "{clipboard}");
+    }
+
+    #[test]
+    fn needs_a_trigger() {
+        assert!(snippet(" , ", "text").normalized().is_err());
+        assert_eq!(snippet(" insert it ", "text").normalized().unwrap().trigger, "insert it");
+    }
+}
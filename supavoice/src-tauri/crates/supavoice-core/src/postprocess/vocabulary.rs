@@ -0,0 +1,482 @@
+use crate::vocabulary::VocabularyEntry;
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use std::ops::Range;
+
+// Multi-word windows let "supa voice" match "Supavoice"
+const MAX_WINDOW_WORDS: usize = 3;
+// Shorter words only match exactly; fuzzy matching them rewrites ordinary words
+const MIN_FUZZY_CHARS: usize = 4;
+const MIN_SPELLING_SIMILARITY: f64 = 0.6;
+// Words this short may only be one edit away, or common words match ("tour" vs "Tauri")
+const SHORT_WORD_CHARS: usize = 5;
+// Average of spelling and phonetic similarity needed to accept a near-miss
+const MIN_MATCH_SCORE: f64 = 0.8;
+// Joined words must also sound like the target and be about as long, or a near-miss
+// swallows a neighbouring word ("a tour he took" is not "Tauri")
+const MIN_WINDOW_SOUND: f64 = 0.8;
+const WINDOW_LENGTH_RATIO: std::ops::RangeInclusive<f64> = 0.8..=1.25;
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct Substitution {
+    pub original: String,
+    pub replacement: String,
+    // Byte range of `replacement` in the corrected text
+    pub start: usize,
+    pub end: usize,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct CorrectedTranscript {
+    pub text: String,
+    pub substitutions: Vec<Substitution>,
+}
+
+impl CorrectedTranscript {
+    // Restores the original wording of one substitution, shifting the ranges of later ones
+    pub fn undo(&mut self, index: usize) -> Result<()> {
+        let substitution = self
+            .substitutions
+            .get(index)
+            .ok_or_else(|| anyhow::anyhow!("No substitution at index {}", index))?;
+
+        if self.text.get(substitution.start..substitution.end) != Some(substitution.replacement.as_str()) {
+            return Err(anyhow::anyhow!("Transcript was edited since the substitution was made"));
+        }
+
+        let substitution = self.substitutions.remove(index);
+        self.text
+            .replace_range(substitution.start..substitution.end, &substitution.original);
+
+        let removed = substitution.end - substitution.start;
+        let inserted = substitution.original.len();
+        for later in self.substitutions.iter_mut().filter(|s| s.start >= substitution.end) {
+            later.start = later.start + inserted - removed;
+            later.end = later.end + inserted - removed;
+        }
+
+        Ok(())
+    }
+}
+
+// One spelling to look for: an entry's canonical form or one of its aliases
+struct Target {
+    canonical: String,
+    key: String,
+    phonetic: String,
+    case_sensitive: bool,
+    weight: f64,
+}
+
+struct WordSpan {
+    start: usize,
+    end: usize,
+}
+
+// Rewrites near-misses of custom vocabulary in a transcript, e.g. "super voice" -> "Supavoice"
+pub struct VocabularyCorrector {
+    targets: Vec<Target>,
+}
+
+impl VocabularyCorrector {
+    pub fn new(vocabulary: &[VocabularyEntry]) -> Self {
+        let targets = vocabulary
+            .iter()
+            .flat_map(|entry| {
+                std::iter::once(&entry.canonical)
+                    .chain(&entry.aliases)
+                    .map(move |spelling| (entry, spelling))
+            })
+            .filter_map(|(entry, spelling)| {
+                let key = normalize(spelling, entry.case_sensitive);
+                if key.is_empty() {
+                    return None;
+                }
+                Some(Target {
+                    canonical: entry.canonical.trim().to_string(),
+                    phonetic: phonetic_key(&key),
+                    key,
+                    case_sensitive: entry.case_sensitive,
+                    weight: entry.weight as f64,
+                })
+            })
+            .collect();
+
+        Self { targets }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.targets.is_empty()
+    }
+
+    pub fn correct(&self, text: &str) -> CorrectedTranscript {
+        self.correct_except(text, &[])
+    }
+
+    // Leaves words inside `protected` byte ranges alone, e.g. expanded snippet text
+    pub fn correct_except(&self, text: &str, protected: &[Range<usize>]) -> CorrectedTranscript {
+        let words: Vec<WordSpan> = word_spans(text)
+            .into_iter()
+            .filter(|word| !protected.iter().any(|range| word.start < range.end && range.start < word.end))
+            .collect();
+        let mut output = String::with_capacity(text.len());
+        let mut substitutions = Vec::new();
+        let mut copied_to = 0;
+        let mut i = 0;
+
+        while i < words.len() {
+            let Some((target, word_count)) = self.best_match(text, &words[i..]) else {
+                i += 1;
+                continue;
+            };
+
+            let start = words[i].start;
+            let end = words[i + word_count - 1].end;
+            let original = &text[start..end];
+
+            // A case-only difference may be the ordinary word ("a whisper"), so it's left
+            // alone unless the canonical casing is distinctive ("github" -> "GitHub")
+            let case_only = original.to_lowercase() == target.canonical.to_lowercase();
+            let fix_case = original != target.canonical
+                && !target.case_sensitive
+                && !is_plain_word(&target.canonical);
+            if !case_only || fix_case {
+                output.push_str(&text[copied_to..start]);
+                let replacement_start = output.len();
+                output.push_str(&target.canonical);
+
+                substitutions.push(Substitution {
+                    original: original.to_string(),
+                    replacement: target.canonical.clone(),
+                    start: replacement_start,
+                    end: output.len(),
+                });
+                copied_to = end;
+            }
+
+            i += word_count;
+        }
+        output.push_str(&text[copied_to..]);
+
+        CorrectedTranscript {
+            text: output,
+            substitutions,
+        }
+    }
+
+    // Best-scoring target for a window of 1..=MAX_WINDOW_WORDS words starting at words[0]
+    fn best_match(&self, text: &str, words: &[WordSpan]) -> Option<(&Target, usize)> {
+        let mut best: Option<(&Target, usize, f64)> = None;
+
+        for word_count in 1..=MAX_WINDOW_WORDS.min(words.len()) {
+            // Don't join words across punctuation ("supa. Voice")
+            if word_count > 1 {
+                let gap = &text[words[word_count - 2].end..words[word_count - 1].start];
+                if !gap.chars().all(|c| c.is_whitespace() || c == '-') {
+                    break;
+                }
+            }
+
+            let window = &text[words[0].start..words[word_count - 1].end];
+            let key = normalize(window, false);
+            let cased_key = normalize(window, true);
+            // The window without its first word, which matches on its own at the next position
+            let rest = &text[words[1.min(word_count - 1)].start..words[word_count - 1].end];
+            let rest_key = normalize(rest, false);
+            let cased_rest_key = normalize(rest, true);
+
+            for target in &self.targets {
+                let key = if target.case_sensitive { &cased_key } else { &key };
+                let rest_key = if target.case_sensitive { &cased_rest_key } else { &rest_key };
+                if word_count > 1 && (!window_matches(key, target) || match_score(rest_key, target).is_some()) {
+                    continue;
+                }
+                if let Some(score) = match_score(key, target) {
+                    // Weight only ranks candidates, it never lowers the acceptance bar.
+                    // Strictly greater keeps the shortest window on ties.
+                    let score = score * target.weight;
+                    if best.is_none_or(|(_, _, best_score)| score > best_score) {
+                        best = Some((target, word_count, score));
+                    }
+                }
+            }
+        }
+
+        best.map(|(target, word_count, _)| (target, word_count))
+    }
+}
+
+fn match_score(key: &str, target: &Target) -> Option<f64> {
+    if key == target.key {
+        return Some(1.0);
+    }
+    // A case-sensitive entry exists to leave other casings alone, so no near-misses
+    if target.case_sensitive {
+        return None;
+    }
+
+    let key_chars = key.chars().count();
+    let target_chars = target.key.chars().count();
+    if key_chars < MIN_FUZZY_CHARS || target_chars < MIN_FUZZY_CHARS {
+        return None;
+    }
+
+    // Windows much longer or shorter than the target can't be the same word
+    let length_ratio = key_chars as f64 / target_chars as f64;
+    if !(0.67..=1.5).contains(&length_ratio) {
+        return None;
+    }
+
+    if key_chars.max(target_chars) <= SHORT_WORD_CHARS && strsim::levenshtein(key, &target.key) > 1 {
+        return None;
+    }
+
+    let spelling = strsim::normalized_levenshtein(key, &target.key);
+    if spelling < MIN_SPELLING_SIMILARITY {
+        return None;
+    }
+
+    let sound = strsim::normalized_levenshtein(&phonetic_key(key), &target.phonetic);
+    let score = (spelling + sound) / 2.0;
+
+    (score >= MIN_MATCH_SCORE).then_some(score)
+}
+
+// Lowercase, or capitalized only at the start ("Whisper"): spellings an ordinary word
+// also has, unlike "GitHub", "iPhone" or "NASA"
+fn is_plain_word(canonical: &str) -> bool {
+    let mut chars = canonical.chars();
+    chars.next().is_some_and(char::is_alphabetic) && chars.all(|c| !c.is_uppercase())
+}
+
+// Extra checks for windows of several words: they must sound like the target and be
+// about as long
+fn window_matches(key: &str, target: &Target) -> bool {
+    if key == target.key {
+        return true;
+    }
+    let length_ratio = key.chars().count() as f64 / target.key.chars().count() as f64;
+    WINDOW_LENGTH_RATIO.contains(&length_ratio)
+        && strsim::normalized_levenshtein(&phonetic_key(key), &target.phonetic) >= MIN_WINDOW_SOUND
+}
+
+// Letters and digits only, so spacing and hyphens don't matter (nor case, unless asked)
+fn normalize(text: &str, keep_case: bool) -> String {
+    let chars = text.chars().filter(|c| c.is_alphanumeric());
+    if keep_case {
+        chars.collect()
+    } else {
+        chars.flat_map(char::to_lowercase).collect()
+    }
+}
+
+fn word_spans(text: &str) -> Vec<WordSpan> {
+    let mut spans = Vec::new();
+    let mut start = None;
+
+    for (i, c) in text.char_indices() {
+        let in_word = c.is_alphanumeric() || c == '\'';
+        match (in_word, start) {
+            (true, None) => start = Some(i),
+            (false, Some(s)) => {
+                spans.push(WordSpan { start: s, end: i });
+                start = None;
+            }
+            _ => {}
+        }
+    }
+    if let Some(s) = start {
+        spans.push(WordSpan { start: s, end: text.len() });
+    }
+
+    spans
+}
+
+// Simplified Metaphone: maps letters to consonant sound classes so that words
+// which sound alike ("supa", "super", "soopa") get similar codes
+fn phonetic_key(word: &str) -> String {
+    let chars: Vec<char> = word
+        .chars()
+        .filter(|c| c.is_ascii_alphabetic())
+        .map(|c| c.to_ascii_lowercase())
+        .collect();
+    let is_vowel = |c: char| matches!(c, 'a' | 'e' | 'i' | 'o' | 'u');
+
+    let mut code = String::new();
+    let mut i = 0;
+
+    // Silent initial letters
+    if chars.len() > 1 && matches!((chars[0], chars[1]), ('k', 'n') | ('g', 'n') | ('p', 'n') | ('w', 'r') | ('p', 's')) {
+        i = 1;
+    }
+
+    while i < chars.len() {
+        let c = chars[i];
+        let prev = if i > 0 { Some(chars[i - 1]) } else { None };
+        let next = chars.get(i + 1).copied();
+
+        let sound = match c {
+            // Only a leading vowel is significant
+            'a' | 'e' | 'i' | 'o' | 'u' => code.is_empty().then_some('A'),
+            'b' if prev == Some('m') && next.is_none() => None,
+            'c' if next == Some('h') => {
+                i += 1;
+                Some('X')
+            }
+            'c' if matches!(next, Some('e' | 'i' | 'y')) => Some('S'),
+            'c' | 'k' | 'q' => Some('K'),
+            't' if next == Some('h') => {
+                i += 1;
+                Some('0')
+            }
+            'd' | 't' => Some('T'),
+            'g' if next == Some('h') => {
+                i += 1;
+                None
+            }
+            'g' if matches!(next, Some('e' | 'i' | 'y')) => Some('J'),
+            'g' => Some('K'),
+            'h' => (next.is_some_and(is_vowel)
+                && !matches!(prev, Some('c' | 's' | 'p' | 't' | 'g')))
+            .then_some('H'),
+            'p' if next == Some('h') => {
+                i += 1;
+                Some('F')
+            }
+            's' if next == Some('h') => {
+                i += 1;
+                Some('X')
+            }
+            's' | 'z' => Some('S'),
+            'v' | 'f' => Some('F'),
+            'w' | 'y' => next.is_some_and(is_vowel).then_some(c.to_ascii_uppercase()),
+            'x' => {
+                if !code.ends_with('K') {
+                    code.push('K');
+                }
+                Some('S')
+            }
+            other => Some(other.to_ascii_uppercase()),
+        };
+
+        if let Some(sound) = sound {
+            if !code.ends_with(sound) {
+                code.push(sound);
+            }
+        }
+        i += 1;
+    }
+
+    code
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(canonical: &str, aliases: &[&str], case_sensitive: bool) -> VocabularyEntry {
+        VocabularyEntry {
+            aliases: aliases.iter().map(|alias| alias.to_string()).collect(),
+            case_sensitive,
+            ..VocabularyEntry::new(canonical)
+        }
+    }
+
+    fn corrector() -> VocabularyCorrector {
+        VocabularyCorrector::new(&[
+            entry("Supavoice", &[], false),
+            entry("Kubernetes", &[], false),
+            entry("GitHub", &["get hub"], false),
+            entry("Tauri", &[], false),
+            entry("Whisper", &[], false),
+            entry("Rust", &[], false),
+            entry("Go", &[], true),
+            entry("iPhone", &[], false),
+        ])
+    }
+
+    #[test]
+    fn substitutions() {
+        let cases: &[(&str, &str)] = &[
+            ("I use super voice daily", "I use Supavoice daily"),
+            ("supa voice is great", "Supavoice is great"),
+            ("supa-voice", "Supavoice"),
+            ("deploy to kubernetis now", "deploy to Kubernetes now"),
+            ("push it to get hub", "push it to GitHub"),
+            // Distinctive casing is fixed, ordinary capitalization isn't
+            ("push it to github", "push it to GitHub"),
+            ("my iphone and Iphone", "my iPhone and iPhone"),
+            ("tauri apps", "tauri apps"),
+            // Not joined across punctuation
+            ("It's super. Voice it.", "It's super. Voice it."),
+            // Ordinary words that only sound a bit like an entry
+            ("a tour he took", "a tour he took"),
+            ("I must go", "I must go"),
+            // Case-sensitive entries leave other casings alone
+            ("go home", "go home"),
+            ("I write Go", "I write Go"),
+        ];
+
+        let corrector = corrector();
+        for (input, expected) in cases {
+            assert_eq!(corrector.correct(input).text, *expected, "input: {:?}", input);
+        }
+    }
+
+    #[test]
+    fn case_only_differences_are_left_alone() {
+        let corrected = corrector().correct("she said it in a whisper");
+        assert_eq!(corrected.text, "she said it in a whisper");
+        assert!(corrected.substitutions.is_empty());
+    }
+
+    #[test]
+    fn substitution_ranges() {
+        let corrected = corrector().correct("super voice runs on kubernetis.");
+        assert_eq!(corrected.text, "Supavoice runs on Kubernetes.");
+        assert_eq!(
+            corrected.substitutions,
+            [
+                Substitution {
+                    original: "super voice".to_string(),
+                    replacement: "Supavoice".to_string(),
+                    start: 0,
+                    end: 9,
+                },
+                Substitution {
+                    original: "kubernetis".to_string(),
+                    replacement: "Kubernetes".to_string(),
+                    start: 18,
+                    end: 28,
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn undo_shifts_later_substitutions() {
+        let mut corrected = corrector().correct("super voice runs on kubernetis.");
+        corrected.undo(0).unwrap();
+        assert_eq!(corrected.text, "super voice runs on Kubernetes.");
+
+        let remaining = &corrected.substitutions[0];
+        assert_eq!(&corrected.text[remaining.start..remaining.end], "Kubernetes");
+        corrected.undo(0).unwrap();
+        assert_eq!(corrected.text, "super voice runs on kubernetis.");
+        assert!(corrected.undo(0).is_err());
+    }
+
+    #[test]
+    fn undo_refuses_edited_text() {
+        let mut corrected = corrector().correct("super voice");
+        corrected.text = "something else".to_string();
+        assert!(corrected.undo(0).is_err());
+    }
+
+    #[test]
+    fn protected_ranges() {
+        let text = "super voice, super voice and super voice";
+        let corrected = corrector().correct_except(text, &[0..11, 13..24]);
+        assert_eq!(corrected.text, "super voice, super voice and Supavoice");
+    }
+}
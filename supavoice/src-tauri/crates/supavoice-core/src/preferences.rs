@@ -0,0 +1,716 @@
+use crate::formatting::{FormatTemplate, TemplateLimits};
+use crate::output::OutputMode;
+use crate::postprocess::{CleanupLevel, DictationCommand, Snippet};
+use crate::vocabulary::{deserialize_vocabulary, new_entry_id, VocabularyEntry};
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::sync::Arc;
+use tokio::sync::RwLock;
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Default)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum FormattingBackendPreference {
+    // Bundled llama-server with the active LLM model, or llama.cpp in-process
+    // when no llama-server binary ships for this platform
+    #[default]
+    Local,
+    // Always run llama.cpp in-process (requires the llama-inprocess feature)
+    InProcess,
+    // Any OpenAI-compatible chat completions server; the API key is kept in the OS keyring
+    OpenAiCompatible {
+        base_url: String,
+        model: String,
+        // The model's context window in tokens; None uses the template limits
+        #[serde(default)]
+        context_size: Option<u32>,
+    },
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Default)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum TranscriptionBackendPreference {
+    // whisper-rs with the active Whisper model
+    #[default]
+    Local,
+    // Any OpenAI-compatible audio transcriptions server; the API key is kept in the OS keyring
+    OpenAiCompatible {
+        base_url: String,
+        model: String,
+        #[serde(default = "default_true")]
+        fallback_to_local: bool,
+        // Spoken language as an ISO-639-1 code; None lets the server detect it
+        #[serde(default)]
+        language: Option<String>,
+    },
+}
+
+fn default_true() -> bool {
+    true
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct DictationPreferences {
+    // Interpret spoken commands like "comma" or "new paragraph"
+    #[serde(default)]
+    pub enabled: bool,
+    // Selects the built-in command set
+    #[serde(default = "default_dictation_language")]
+    pub language: String,
+    // User commands keyed by language code, added to or replacing built-in phrases
+    #[serde(default)]
+    pub custom_commands: HashMap<String, Vec<DictationCommand>>,
+}
+
+impl Default for DictationPreferences {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            language: default_dictation_language(),
+            custom_commands: HashMap::new(),
+        }
+    }
+}
+
+fn default_dictation_language() -> String {
+    "en".to_string()
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct ItnPreferences {
+    // Rewrite spoken numbers, dates and amounts in written form ("twenty dollars" -> "$20")
+    #[serde(default)]
+    pub enabled: bool,
+    // Selects the rule set; languages without rules are left untouched
+    #[serde(default = "default_dictation_language")]
+    pub language: String,
+}
+
+impl Default for ItnPreferences {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            language: default_dictation_language(),
+        }
+    }
+}
+
+// Global shortcuts as accelerators like "CmdOrCtrl+Alt+L"; None leaves an action unbound
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct ShortcutPreferences {
+    #[serde(default)]
+    pub toggle_recording: Option<String>,
+    // Records while held, stops and transcribes on release
+    #[serde(default)]
+    pub hold_to_talk: Option<String>,
+    #[serde(default = "default_show_overlay_shortcut")]
+    pub show_overlay: Option<String>,
+}
+
+impl Default for ShortcutPreferences {
+    fn default() -> Self {
+        Self {
+            toggle_recording: None,
+            hold_to_talk: None,
+            show_overlay: default_show_overlay_shortcut(),
+        }
+    }
+}
+
+fn default_show_overlay_shortcut() -> Option<String> {
+    // ⌥⌘L on macOS, Ctrl+Alt+L elsewhere
+    Some("Alt+CmdOrCtrl+L".to_string())
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq)]
+pub struct OutputPreferences {
+    // Where transcripts go, and formatted text unless its template overrides it
+    #[serde(default)]
+    pub mode: OutputMode,
+    // Per-template overrides keyed by template id
+    #[serde(default)]
+    pub template_modes: HashMap<String, OutputMode>,
+}
+
+impl OutputPreferences {
+    pub fn mode_for(&self, template_id: Option<&str>) -> OutputMode {
+        template_id
+            .and_then(|id| self.template_modes.get(id))
+            .copied()
+            .unwrap_or(self.mode)
+    }
+}
+
+#[derive(Serialize, Clone, Debug)]
+pub struct VocabularyImportSummary {
+    pub added: usize,
+    pub updated: usize,
+}
+
+// Localhost HTTP/WebSocket API for editor plugins and scripts; the bearer token
+// lives in the OS keyring
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct ApiServerPreferences {
+    #[serde(default)]
+    pub enabled: bool,
+    #[serde(default = "default_api_server_port")]
+    pub port: u16,
+}
+
+impl Default for ApiServerPreferences {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            port: default_api_server_port(),
+        }
+    }
+}
+
+fn default_api_server_port() -> u16 {
+    8787
+}
+
+// Speaker labels for multi-person recordings such as meetings
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct DiarizationPreferences {
+    #[serde(default)]
+    pub enabled: bool,
+    // Known number of participants; otherwise inferred from `threshold`
+    #[serde(default)]
+    pub max_speakers: Option<usize>,
+    // Voice distance (0-2) below which segments count as the same speaker; lower
+    // values find more speakers
+    #[serde(default = "default_diarization_threshold")]
+    pub threshold: f32,
+}
+
+impl Default for DiarizationPreferences {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            max_speakers: None,
+            threshold: default_diarization_threshold(),
+        }
+    }
+}
+
+fn default_diarization_threshold() -> f32 {
+    0.5
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct ConfidencePreferences {
+    // Words Whisper is less sure of than this (0-1) are flagged for review
+    #[serde(default = "default_confidence_threshold")]
+    pub threshold: f32,
+    // Whisper model used to re-transcribe flagged spans; None picks the largest installed
+    #[serde(default)]
+    pub review_model: Option<String>,
+}
+
+impl Default for ConfidencePreferences {
+    fn default() -> Self {
+        Self {
+            threshold: default_confidence_threshold(),
+            review_model: None,
+        }
+    }
+}
+
+fn default_confidence_threshold() -> f32 {
+    0.5
+}
+
+// Filters for text Whisper invents on silence and music. A None threshold turns
+// that guard off.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct HallucinationGuardPreferences {
+    // Drops segments the backend thinks are probably not speech (0-1). Only backends
+    // that report a no-speech probability are affected; local Whisper doesn't yet,
+    // as whisper-rs 0.12 doesn't expose it, so this is off by default.
+    #[serde(default)]
+    pub no_speech_threshold: Option<f32>,
+    // Drops repetition loops: text that compresses better than this ratio
+    #[serde(default = "default_compression_ratio_threshold")]
+    pub compression_ratio_threshold: Option<f32>,
+    // Drops segments that are only a stock phrase such as "Thanks for watching!"
+    #[serde(default = "default_true")]
+    pub filter_known_phrases: bool,
+    #[serde(default)]
+    pub extra_phrases: Vec<String>,
+    // Drops segments whose audio never gets louder than this RMS level (0-1)
+    #[serde(default = "default_silence_threshold")]
+    pub silence_threshold: Option<f32>,
+}
+
+impl Default for HallucinationGuardPreferences {
+    fn default() -> Self {
+        Self {
+            no_speech_threshold: None,
+            compression_ratio_threshold: default_compression_ratio_threshold(),
+            filter_known_phrases: true,
+            extra_phrases: Vec::new(),
+            silence_threshold: default_silence_threshold(),
+        }
+    }
+}
+
+// Whisper's own default for falling back on repetition loops
+fn default_compression_ratio_threshold() -> Option<f32> {
+    Some(2.4)
+}
+
+fn default_silence_threshold() -> Option<f32> {
+    Some(0.005)
+}
+
+// A folder (e.g. a voice recorder or phone sync folder) whose new recordings are
+// transcribed automatically, with results written beside them
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct WatchFolder {
+    pub path: PathBuf,
+    // Also writes `<name>.md` formatted with this template
+    #[serde(default)]
+    pub template: Option<String>,
+    // Also writes `<name>.srt`
+    #[serde(default = "default_true")]
+    pub subtitles: bool,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct AppPreferences {
+    pub active_whisper_model: Option<String>,
+    pub active_llm_model: Option<String>,
+    #[serde(default, deserialize_with = "deserialize_vocabulary")]
+    pub custom_vocabulary: Vec<VocabularyEntry>,
+    // Spoken trigger phrases expanded after transcription
+    #[serde(default)]
+    pub snippets: Vec<Snippet>,
+    #[serde(default)]
+    pub dictation: DictationPreferences,
+    // Deterministic filler/disfluency removal applied to every transcript
+    #[serde(default)]
+    pub cleanup_level: CleanupLevel,
+    #[serde(default)]
+    pub inverse_text_normalization: ItnPreferences,
+    #[serde(default)]
+    pub shortcuts: ShortcutPreferences,
+    #[serde(default)]
+    pub output: OutputPreferences,
+    #[serde(default)]
+    pub api_server: ApiServerPreferences,
+    #[serde(default)]
+    pub watch_folders: Vec<WatchFolder>,
+    #[serde(default)]
+    pub diarization: DiarizationPreferences,
+    #[serde(default)]
+    pub confidence: ConfidencePreferences,
+    // Per-word start/end times for karaoke highlighting and subtitle editing
+    #[serde(default)]
+    pub word_timestamps: bool,
+    #[serde(default)]
+    pub hallucination_guards: HallucinationGuardPreferences,
+    #[serde(default)]
+    pub formatting_backend: FormattingBackendPreference,
+    #[serde(default)]
+    pub transcription_backend: TranscriptionBackendPreference,
+    // Per-template overrides of context size and output length, keyed by template id
+    #[serde(default)]
+    pub template_limits: HashMap<String, TemplateLimits>,
+}
+
+impl Default for AppPreferences {
+    fn default() -> Self {
+        Self {
+            active_whisper_model: None, // None means use auto-selection
+            active_llm_model: None,
+            custom_vocabulary: Vec::new(),
+            snippets: Vec::new(),
+            dictation: DictationPreferences::default(),
+            cleanup_level: CleanupLevel::default(),
+            inverse_text_normalization: ItnPreferences::default(),
+            shortcuts: ShortcutPreferences::default(),
+            output: OutputPreferences::default(),
+            api_server: ApiServerPreferences::default(),
+            watch_folders: Vec::new(),
+            diarization: DiarizationPreferences::default(),
+            confidence: ConfidencePreferences::default(),
+            word_timestamps: false,
+            hallucination_guards: HallucinationGuardPreferences::default(),
+            formatting_backend: FormattingBackendPreference::default(),
+            transcription_backend: TranscriptionBackendPreference::default(),
+            template_limits: HashMap::new(),
+        }
+    }
+}
+
+pub struct PreferencesManager {
+    preferences: Arc<RwLock<AppPreferences>>,
+    config_path: PathBuf,
+}
+
+impl PreferencesManager {
+    pub fn new() -> Result<Self> {
+        let project_dirs = directories::ProjectDirs::from("com", "supavoice", "Supavoice")
+            .ok_or_else(|| anyhow::anyhow!("Failed to get project directories"))?;
+
+        let config_dir = project_dirs.config_dir();
+        std::fs::create_dir_all(config_dir)?;
+
+        let config_path = config_dir.join("preferences.json");
+
+        // Load existing preferences or create default
+        let preferences = if config_path.exists() {
+            let content = std::fs::read_to_string(&config_path)?;
+            serde_json::from_str(&content).unwrap_or_default()
+        } else {
+            AppPreferences::default()
+        };
+
+        Ok(Self {
+            preferences: Arc::new(RwLock::new(preferences)),
+            config_path,
+        })
+    }
+
+    pub async fn get_preferences(&self) -> AppPreferences {
+        self.preferences.read().await.clone()
+    }
+
+    pub async fn set_active_whisper_model(&self, model_id: Option<String>) -> Result<()> {
+        let mut prefs = self.preferences.write().await;
+        prefs.active_whisper_model = model_id;
+        self.save(&prefs).await?;
+        Ok(())
+    }
+
+    pub async fn set_active_llm_model(&self, model_id: Option<String>) -> Result<()> {
+        let mut prefs = self.preferences.write().await;
+        prefs.active_llm_model = model_id;
+        self.save(&prefs).await?;
+        Ok(())
+    }
+
+    pub async fn set_formatting_backend(&self, backend: FormattingBackendPreference) -> Result<()> {
+        if let FormattingBackendPreference::OpenAiCompatible { context_size: Some(0), .. } = backend {
+            return Err(anyhow::anyhow!("Context size must be greater than 0"));
+        }
+
+        let mut prefs = self.preferences.write().await;
+        prefs.formatting_backend = backend;
+        self.save(&prefs).await?;
+        Ok(())
+    }
+
+    pub async fn set_transcription_backend(&self, backend: TranscriptionBackendPreference) -> Result<()> {
+        let mut prefs = self.preferences.write().await;
+        prefs.transcription_backend = backend;
+        self.save(&prefs).await?;
+        Ok(())
+    }
+
+    pub async fn set_template_limits(&self, template_id: String, limits: Option<TemplateLimits>) -> Result<()> {
+        if let Some(limits) = &limits {
+            if limits.context_size == 0 {
+                return Err(anyhow::anyhow!("Context size must be greater than 0"));
+            }
+            // The prompt and transcript need room alongside the output
+            if limits.max_output_tokens >= limits.context_size {
+                return Err(anyhow::anyhow!(
+                    "Max output tokens ({}) must be less than the context size ({})",
+                    limits.max_output_tokens,
+                    limits.context_size
+                ));
+            }
+        }
+
+        let mut prefs = self.preferences.write().await;
+        match limits {
+            Some(limits) => {
+                prefs.template_limits.insert(template_id, limits);
+            }
+            // None restores the template's built-in limits
+            None => {
+                prefs.template_limits.remove(&template_id);
+            }
+        }
+        self.save(&prefs).await?;
+        Ok(())
+    }
+
+    pub async fn add_vocabulary_entry(&self, entry: VocabularyEntry) -> Result<VocabularyEntry> {
+        let mut entry = entry.normalized()?;
+        // Ids are always assigned here, whatever the caller sent
+        entry.id = new_entry_id();
+
+        // Spellings are compared ignoring case, as on import
+        let mut prefs = self.preferences.write().await;
+        if prefs
+            .custom_vocabulary
+            .iter()
+            .any(|existing| existing.canonical.eq_ignore_ascii_case(&entry.canonical))
+        {
+            return Err(anyhow::anyhow!("'{}' is already in the vocabulary", entry.canonical));
+        }
+
+        prefs.custom_vocabulary.push(entry.clone());
+        self.save(&prefs).await?;
+        Ok(entry)
+    }
+
+    pub async fn update_vocabulary_entry(&self, entry: VocabularyEntry) -> Result<VocabularyEntry> {
+        let entry = entry.normalized()?;
+
+        let mut prefs = self.preferences.write().await;
+        if prefs
+            .custom_vocabulary
+            .iter()
+            .any(|existing| existing.id != entry.id && existing.canonical.eq_ignore_ascii_case(&entry.canonical))
+        {
+            return Err(anyhow::anyhow!("'{}' is already in the vocabulary", entry.canonical));
+        }
+
+        let existing = prefs
+            .custom_vocabulary
+            .iter_mut()
+            .find(|existing| existing.id == entry.id)
+            .ok_or_else(|| anyhow::anyhow!("Vocabulary entry not found: {}", entry.id))?;
+        *existing = entry.clone();
+
+        self.save(&prefs).await?;
+        Ok(entry)
+    }
+
+    pub async fn remove_vocabulary_entry(&self, id: String) -> Result<()> {
+        let mut prefs = self.preferences.write().await;
+        prefs.custom_vocabulary.retain(|entry| entry.id != id);
+        self.save(&prefs).await?;
+        Ok(())
+    }
+
+    // Merges imported entries by spelling (ignoring case), keeping the ids of
+    // entries that already exist; `replace` discards the current vocabulary first
+    pub async fn import_vocabulary(
+        &self,
+        entries: Vec<VocabularyEntry>,
+        replace: bool,
+    ) -> Result<VocabularyImportSummary> {
+        let mut prefs = self.preferences.write().await;
+        if replace {
+            prefs.custom_vocabulary.clear();
+        }
+
+        let mut summary = VocabularyImportSummary { added: 0, updated: 0 };
+        for mut entry in entries {
+            match prefs
+                .custom_vocabulary
+                .iter_mut()
+                .find(|existing| existing.canonical.eq_ignore_ascii_case(&entry.canonical))
+            {
+                Some(existing) => {
+                    entry.id = existing.id.clone();
+                    *existing = entry;
+                    summary.updated += 1;
+                }
+                None => {
+                    prefs.custom_vocabulary.push(entry);
+                    summary.added += 1;
+                }
+            }
+        }
+
+        self.save(&prefs).await?;
+        Ok(summary)
+    }
+
+    pub async fn get_vocabulary(&self) -> Vec<VocabularyEntry> {
+        self.preferences.read().await.custom_vocabulary.clone()
+    }
+
+    pub async fn set_cleanup_level(&self, level: CleanupLevel) -> Result<()> {
+        let mut prefs = self.preferences.write().await;
+        prefs.cleanup_level = level;
+        self.save(&prefs).await?;
+        Ok(())
+    }
+
+    pub async fn set_inverse_text_normalization(&self, enabled: bool, language: String) -> Result<()> {
+        let mut prefs = self.preferences.write().await;
+        prefs.inverse_text_normalization = ItnPreferences { enabled, language };
+        self.save(&prefs).await?;
+        Ok(())
+    }
+
+    pub async fn set_shortcuts(&self, shortcuts: ShortcutPreferences) -> Result<()> {
+        let mut prefs = self.preferences.write().await;
+        prefs.shortcuts = shortcuts;
+        self.save(&prefs).await?;
+        Ok(())
+    }
+
+    pub async fn set_output_mode(&self, mode: OutputMode) -> Result<()> {
+        let mut prefs = self.preferences.write().await;
+        prefs.output.mode = mode;
+        self.save(&prefs).await?;
+        Ok(())
+    }
+
+    pub async fn set_template_output_mode(&self, template_id: String, mode: Option<OutputMode>) -> Result<()> {
+        let mut prefs = self.preferences.write().await;
+        match mode {
+            Some(mode) => {
+                prefs.output.template_modes.insert(template_id, mode);
+            }
+            // None falls back to the global mode
+            None => {
+                prefs.output.template_modes.remove(&template_id);
+            }
+        }
+        self.save(&prefs).await?;
+        Ok(())
+    }
+
+    pub async fn set_api_server(&self, api_server: ApiServerPreferences) -> Result<()> {
+        let mut prefs = self.preferences.write().await;
+        prefs.api_server = api_server;
+        self.save(&prefs).await?;
+        Ok(())
+    }
+
+    pub async fn set_diarization(&self, diarization: DiarizationPreferences) -> Result<()> {
+        let mut prefs = self.preferences.write().await;
+        prefs.diarization = diarization;
+        self.save(&prefs).await?;
+        Ok(())
+    }
+
+    pub async fn set_confidence(&self, confidence: ConfidencePreferences) -> Result<()> {
+        let mut prefs = self.preferences.write().await;
+        prefs.confidence = confidence;
+        self.save(&prefs).await?;
+        Ok(())
+    }
+
+    pub async fn set_hallucination_guards(&self, guards: HallucinationGuardPreferences) -> Result<()> {
+        let mut prefs = self.preferences.write().await;
+        prefs.hallucination_guards = guards;
+        self.save(&prefs).await?;
+        Ok(())
+    }
+
+    pub async fn set_word_timestamps(&self, enabled: bool) -> Result<()> {
+        let mut prefs = self.preferences.write().await;
+        prefs.word_timestamps = enabled;
+        self.save(&prefs).await?;
+        Ok(())
+    }
+
+    // Keyed by path: adding a folder that's already watched replaces its settings
+    pub async fn add_watch_folder(&self, folder: WatchFolder) -> Result<()> {
+        if !folder.path.is_dir() {
+            return Err(anyhow::anyhow!("Not a folder: {}", folder.path.display()));
+        }
+        if let Some(template) = &folder.template {
+            if FormatTemplate::builtin(template).is_none() {
+                return Err(anyhow::anyhow!("Unknown format type: {}", template));
+            }
+        }
+
+        let mut prefs = self.preferences.write().await;
+        match prefs.watch_folders.iter_mut().find(|existing| existing.path == folder.path) {
+            Some(existing) => *existing = folder,
+            None => prefs.watch_folders.push(folder),
+        }
+        self.save(&prefs).await?;
+        Ok(())
+    }
+
+    pub async fn remove_watch_folder(&self, path: PathBuf) -> Result<()> {
+        let mut prefs = self.preferences.write().await;
+        prefs.watch_folders.retain(|folder| folder.path != path);
+        self.save(&prefs).await?;
+        Ok(())
+    }
+
+    pub async fn set_dictation_enabled(&self, enabled: bool) -> Result<()> {
+        let mut prefs = self.preferences.write().await;
+        prefs.dictation.enabled = enabled;
+        self.save(&prefs).await?;
+        Ok(())
+    }
+
+    pub async fn set_dictation_language(&self, language: String) -> Result<()> {
+        let mut prefs = self.preferences.write().await;
+        prefs.dictation.language = language;
+        self.save(&prefs).await?;
+        Ok(())
+    }
+
+    pub async fn set_dictation_commands(&self, language: String, commands: Vec<DictationCommand>) -> Result<()> {
+        let mut prefs = self.preferences.write().await;
+        if commands.is_empty() {
+            prefs.dictation.custom_commands.remove(&language);
+        } else {
+            prefs.dictation.custom_commands.insert(language, commands);
+        }
+        self.save(&prefs).await?;
+        Ok(())
+    }
+
+    pub async fn get_snippets(&self) -> Vec<Snippet> {
+        self.preferences.read().await.snippets.clone()
+    }
+
+    pub async fn add_snippet(&self, snippet: Snippet) -> Result<Snippet> {
+        let mut snippet = snippet.normalized()?;
+        snippet.id = new_entry_id();
+
+        let mut prefs = self.preferences.write().await;
+        if prefs
+            .snippets
+            .iter()
+            .any(|existing| existing.trigger.eq_ignore_ascii_case(&snippet.trigger))
+        {
+            return Err(anyhow::anyhow!("A snippet for '{}' already exists", snippet.trigger));
+        }
+
+        prefs.snippets.push(snippet.clone());
+        self.save(&prefs).await?;
+        Ok(snippet)
+    }
+
+    pub async fn update_snippet(&self, snippet: Snippet) -> Result<Snippet> {
+        let snippet = snippet.normalized()?;
+
+        let mut prefs = self.preferences.write().await;
+        if prefs.snippets.iter().any(|existing| {
+            existing.id != snippet.id && existing.trigger.eq_ignore_ascii_case(&snippet.trigger)
+        }) {
+            return Err(anyhow::anyhow!("A snippet for '{}' already exists", snippet.trigger));
+        }
+
+        let existing = prefs
+            .snippets
+            .iter_mut()
+            .find(|existing| existing.id == snippet.id)
+            .ok_or_else(|| anyhow::anyhow!("Snippet not found: {}", snippet.id))?;
+        *existing = snippet.clone();
+
+        self.save(&prefs).await?;
+        Ok(snippet)
+    }
+
+    pub async fn remove_snippet(&self, id: String) -> Result<()> {
+        let mut prefs = self.preferences.write().await;
+        prefs.snippets.retain(|snippet| snippet.id != id);
+        self.save(&prefs).await?;
+        Ok(())
+    }
+
+    async fn save(&self, prefs: &AppPreferences) -> Result<()> {
+        let json = serde_json::to_string_pretty(prefs)?;
+        tokio::fs::write(&self.config_path, json).await?;
+        Ok(())
+    }
+}
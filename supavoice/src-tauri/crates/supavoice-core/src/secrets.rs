@@ -0,0 +1,37 @@
+use anyhow::{Context, Result};
+
+// API keys live in the OS keyring (Keychain, Credential Manager, Secret Service),
+// never in preferences.json
+const KEYRING_SERVICE: &str = "com.supavoice.Supavoice";
+
+pub const FORMATTING_API_KEY: &str = "formatting-api-key";
+pub const TRANSCRIPTION_API_KEY: &str = "transcription-api-key";
+// Bearer token clients must present to the local API server
+pub const API_SERVER_TOKEN: &str = "api-server-token";
+
+pub fn get_api_key(account: &str) -> Result<Option<String>> {
+    let entry = keyring::Entry::new(KEYRING_SERVICE, account)
+        .context("Failed to open keyring entry")?;
+
+    match entry.get_password() {
+        Ok(key) => Ok(Some(key)),
+        Err(keyring::Error::NoEntry) => Ok(None),
+        Err(e) => Err(anyhow::anyhow!("Failed to read API key from keyring: {}", e)),
+    }
+}
+
+pub fn set_api_key(account: &str, api_key: Option<&str>) -> Result<()> {
+    let entry = keyring::Entry::new(KEYRING_SERVICE, account)
+        .context("Failed to open keyring entry")?;
+
+    match api_key {
+        Some(key) if !key.is_empty() => entry
+            .set_password(key)
+            .context("Failed to store API key in keyring"),
+        // Clearing a key that was never set is not an error
+        _ => match entry.delete_credential() {
+            Ok(()) | Err(keyring::Error::NoEntry) => Ok(()),
+            Err(e) => Err(anyhow::anyhow!("Failed to remove API key from keyring: {}", e)),
+        },
+    }
+}
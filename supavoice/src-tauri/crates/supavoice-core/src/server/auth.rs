@@ -0,0 +1,68 @@
+use super::error::ApiError;
+use super::routes::ServerState;
+use axum::extract::{Request, State};
+use axum::http::header::{AUTHORIZATION, UPGRADE};
+use axum::http::HeaderMap;
+use axum::middleware::Next;
+use axum::response::Response;
+use rand::distributions::Alphanumeric;
+use rand::Rng;
+use std::sync::Arc;
+
+pub fn generate_token() -> String {
+    rand::thread_rng()
+        .sample_iter(&Alphanumeric)
+        .take(32)
+        .map(char::from)
+        .collect()
+}
+
+pub async fn require_token(
+    State(state): State<Arc<ServerState>>,
+    request: Request,
+    next: Next,
+) -> Result<Response, ApiError> {
+    let presented = bearer_token(request.headers()).or_else(|| {
+        // Browsers can't set headers on WebSocket upgrades, so those may pass ?access_token=
+        if is_websocket_upgrade(request.headers()) {
+            query_token(request.uri().query()?)
+        } else {
+            None
+        }
+    });
+
+    match presented {
+        Some(token) if tokens_match(token, &state.token) => Ok(next.run(request).await),
+        _ => Err(ApiError::unauthorized()),
+    }
+}
+
+fn bearer_token(headers: &HeaderMap) -> Option<&str> {
+    headers
+        .get(AUTHORIZATION)?
+        .to_str()
+        .ok()?
+        .strip_prefix("Bearer ")
+        .map(str::trim)
+}
+
+fn is_websocket_upgrade(headers: &HeaderMap) -> bool {
+    headers
+        .get(UPGRADE)
+        .and_then(|value| value.to_str().ok())
+        .is_some_and(|value| value.eq_ignore_ascii_case("websocket"))
+}
+
+fn query_token(query: &str) -> Option<&str> {
+    query.split('&').find_map(|pair| pair.strip_prefix("access_token="))
+}
+
+// Constant-time comparison so response timing doesn't leak how much of a guess matched
+fn tokens_match(presented: &str, expected: &str) -> bool {
+    presented.len() == expected.len()
+        && presented
+            .bytes()
+            .zip(expected.bytes())
+            .fold(0, |diff, (a, b)| diff | (a ^ b))
+            == 0
+}
@@ -0,0 +1,31 @@
+use crate::formatting::FormattingBackend;
+use crate::models::ModelRecord;
+use crate::pipeline;
+use crate::preferences::AppPreferences;
+use crate::transcription::{Segment, WhisperTranscriber};
+use anyhow::Result;
+use async_trait::async_trait;
+use std::sync::Arc;
+
+// What the API server needs from its host. The app implements this on top of the
+// models it already keeps loaded, so API requests don't load a second copy.
+#[async_trait]
+pub trait ServerBackend: Send + Sync + 'static {
+    async fn preferences(&self) -> AppPreferences;
+
+    async fn transcriber(&self, prefs: &AppPreferences) -> Result<Arc<WhisperTranscriber>>;
+
+    async fn formatting_backend(&self, prefs: &AppPreferences) -> Result<Box<dyn FormattingBackend>>;
+
+    async fn models(&self) -> Result<Vec<ModelRecord>>;
+
+    // The preferred transcription backend, as when dictating in the app
+    async fn transcribe_segments(
+        &self,
+        prefs: &AppPreferences,
+        audio_path: &str,
+        prompt: Option<&str>,
+    ) -> Result<Vec<Segment>> {
+        pipeline::transcribe_segments(prefs, audio_path, prompt, || self.transcriber(prefs)).await
+    }
+}
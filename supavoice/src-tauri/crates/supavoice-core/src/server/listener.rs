@@ -0,0 +1,70 @@
+use super::backend::ServerBackend;
+use super::routes::{router, ServerState};
+use anyhow::{bail, Context, Result};
+use std::net::{Ipv4Addr, SocketAddr};
+use std::sync::Arc;
+use tokio::net::TcpListener;
+use tokio::sync::oneshot;
+
+pub struct ServerConfig {
+    pub port: u16,
+    pub token: String,
+}
+
+// A running server; dropping it shuts the server down
+pub struct ApiServer {
+    addr: SocketAddr,
+    shutdown: Option<oneshot::Sender<()>>,
+}
+
+impl ApiServer {
+    // Binds to loopback only, so other machines can't reach the API even with the token
+    pub async fn start(backend: Arc<dyn ServerBackend>, config: ServerConfig) -> Result<Self> {
+        if config.token.is_empty() {
+            bail!("The API server needs a bearer token");
+        }
+
+        let listener = TcpListener::bind((Ipv4Addr::LOCALHOST, config.port))
+            .await
+            .with_context(|| format!("Failed to listen on 127.0.0.1:{}", config.port))?;
+        let addr = listener.local_addr()?;
+
+        let app = router(Arc::new(ServerState {
+            backend,
+            token: config.token,
+        }));
+        let (shutdown, shutdown_rx) = oneshot::channel::<()>();
+
+        tokio::spawn(async move {
+            let server = axum::serve(listener, app).with_graceful_shutdown(async {
+                let _ = shutdown_rx.await;
+            });
+            if let Err(e) = server.await {
+                eprintln!("⚠️  API server stopped: {}", e);
+            }
+        });
+
+        eprintln!("🌐 API server listening on http://{}", addr);
+        Ok(Self {
+            addr,
+            shutdown: Some(shutdown),
+        })
+    }
+
+    pub fn addr(&self) -> SocketAddr {
+        self.addr
+    }
+
+    pub fn stop(self) {
+        // Shutdown happens in Drop
+    }
+}
+
+impl Drop for ApiServer {
+    fn drop(&mut self) {
+        if let Some(shutdown) = self.shutdown.take() {
+            let _ = shutdown.send(());
+            eprintln!("🛑 API server on {} stopped", self.addr);
+        }
+    }
+}
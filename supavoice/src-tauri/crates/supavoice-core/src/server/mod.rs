@@ -0,0 +1,10 @@
+pub mod auth;
+pub mod backend;
+pub mod error;
+pub mod listener;
+pub mod routes;
+pub mod stream;
+
+pub use auth::generate_token;
+pub use backend::ServerBackend;
+pub use listener::{ApiServer, ServerConfig};
@@ -0,0 +1,586 @@
+use super::auth::require_token;
+use super::backend::ServerBackend;
+use super::error::ApiError;
+use super::stream::transcribe_stream;
+use crate::diarization::speaker_labels;
+use crate::formatting::FormatTemplate;
+use crate::models::ModelKind;
+use crate::pipeline;
+use crate::preferences::AppPreferences;
+use crate::transcription::{to_srt, to_vtt, Segment};
+use crate::vocabulary::vocabulary_prompt;
+use axum::extract::{DefaultBodyLimit, Multipart, State};
+use axum::http::header::CONTENT_TYPE;
+use axum::response::{IntoResponse, Response};
+use axum::routing::{get, post};
+use axum::{middleware, Json, Router};
+use rand::distributions::Alphanumeric;
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+use std::sync::Arc;
+
+// Recordings are uploaded whole; an hour of 16 kHz 16-bit mono is about 115 MB
+pub(super) const MAX_AUDIO_BYTES: usize = 256 * 1024 * 1024;
+// Whisper only accepts 16 kHz; uploads and streams must already be at that rate
+pub(super) const SAMPLE_RATE: u32 = 16000;
+
+pub struct ServerState {
+    pub backend: Arc<dyn ServerBackend>,
+    pub token: String,
+}
+
+// OpenAI-shaped endpoints (`/v1/audio/transcriptions`, `/v1/models`) so existing client
+// libraries work by pointing their base URL at the app, plus formatting and streaming
+pub fn router(state: Arc<ServerState>) -> Router {
+    Router::new()
+        .route("/v1/models", get(list_models))
+        .route("/v1/audio/transcriptions", post(create_transcription))
+        .route("/v1/audio/stream", get(transcribe_stream))
+        .route("/v1/format", post(format))
+        .layer(DefaultBodyLimit::max(MAX_AUDIO_BYTES))
+        .layer(middleware::from_fn_with_state(state.clone(), require_token))
+        // Lets clients check the server is up before they have a token
+        .route("/health", get(|| async { "ok" }))
+        .with_state(state)
+}
+
+#[derive(Serialize)]
+struct ModelObject {
+    id: String,
+    object: &'static str,
+    owned_by: &'static str,
+    kind: ModelKind,
+}
+
+#[derive(Serialize)]
+struct ModelList {
+    object: &'static str,
+    data: Vec<ModelObject>,
+}
+
+async fn list_models(State(state): State<Arc<ServerState>>) -> Result<Json<ModelList>, ApiError> {
+    let models = state.backend.models().await?;
+    let data = models
+        .into_iter()
+        .filter(|model| model.path.is_some())
+        .map(|model| ModelObject {
+            id: model.id,
+            object: "model",
+            owned_by: "supavoice",
+            kind: model.kind,
+        })
+        .collect();
+
+    Ok(Json(ModelList { object: "list", data }))
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+enum ResponseFormat {
+    #[default]
+    Json,
+    Text,
+    VerboseJson,
+    Srt,
+    Vtt,
+}
+
+impl ResponseFormat {
+    fn parse(value: &str) -> Result<Self, ApiError> {
+        match value.trim() {
+            "json" => Ok(Self::Json),
+            "text" => Ok(Self::Text),
+            "verbose_json" => Ok(Self::VerboseJson),
+            "srt" => Ok(Self::Srt),
+            "vtt" => Ok(Self::Vtt),
+            other => Err(ApiError::bad_request(format!("Unsupported response_format: {}", other))),
+        }
+    }
+}
+
+#[derive(Serialize, Clone, Debug)]
+pub(super) struct Transcription {
+    pub text: String,
+    pub segments: Vec<Segment>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub language: Option<String>,
+    // Present when a template was requested
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub formatted: Option<String>,
+}
+
+#[derive(Serialize)]
+struct SimpleTranscription<'a> {
+    text: &'a str,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    formatted: Option<&'a str>,
+}
+
+#[derive(Serialize)]
+struct VerboseSegment<'a> {
+    id: usize,
+    start: f64,
+    end: f64,
+    text: &'a str,
+    // Set when diarization is enabled in the app
+    #[serde(skip_serializing_if = "Option::is_none")]
+    speaker: Option<usize>,
+}
+
+#[derive(Serialize)]
+struct VerboseTranscription<'a> {
+    task: &'static str,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    language: Option<&'a str>,
+    duration: f64,
+    text: &'a str,
+    segments: Vec<VerboseSegment<'a>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    formatted: Option<&'a str>,
+}
+
+// Multipart fields follow OpenAI: `file` (WAV, 16 kHz mono like the app records),
+// `prompt` and `response_format`. `model`, `language` and `temperature` are accepted
+// but the app's own settings apply. `template` (email, notes, action_items)
+// additionally formats the transcript.
+async fn create_transcription(
+    State(state): State<Arc<ServerState>>,
+    mut multipart: Multipart,
+) -> Result<Response, ApiError> {
+    let mut audio = None;
+    let mut prompt = None;
+    let mut template = None;
+    let mut response_format = ResponseFormat::default();
+
+    while let Some(field) = multipart
+        .next_field()
+        .await
+        .map_err(|e| ApiError::bad_request(e.body_text()))?
+    {
+        let name = field.name().unwrap_or_default().to_string();
+        match name.as_str() {
+            "file" => {
+                audio = Some(
+                    field
+                        .bytes()
+                        .await
+                        .map_err(|e| ApiError::bad_request(e.body_text()))?,
+                )
+            }
+            "prompt" | "template" | "response_format" => {
+                let value = field
+                    .text()
+                    .await
+                    .map_err(|e| ApiError::bad_request(e.body_text()))?;
+                match name.as_str() {
+                    "prompt" => prompt = Some(value),
+                    "template" => template = Some(value),
+                    _ => response_format = ResponseFormat::parse(&value)?,
+                }
+            }
+            _ => {}
+        }
+    }
+
+    let audio = audio.ok_or_else(|| ApiError::bad_request("Missing `file` field"))?;
+    // Only the header is read, so a bad upload is a 400 rather than a failed transcription
+    let spec = hound::WavReader::new(std::io::Cursor::new(&audio[..]))
+        .map_err(|e| ApiError::bad_request(format!("`file` must be a WAV recording: {}", e)))?
+        .spec();
+    // Other rates would be decoded at the wrong speed; stereo is mixed down
+    if spec.sample_rate != SAMPLE_RATE || spec.channels > 2 {
+        return Err(ApiError::bad_request(format!(
+            "Unsupported WAV format ({} Hz, {} channels); upload 16000 Hz mono or stereo",
+            spec.sample_rate, spec.channels
+        )));
+    }
+    let upload = TempAudio::new();
+    tokio::fs::write(upload.path(), &audio)
+        .await
+        .map_err(anyhow::Error::from)?;
+
+    let transcription = transcribe(
+        state.backend.as_ref(),
+        &upload.path().to_string_lossy(),
+        prompt.as_deref(),
+        template.as_deref(),
+    )
+    .await?;
+
+    Ok(match response_format {
+        ResponseFormat::Json => Json(SimpleTranscription {
+            text: &transcription.text,
+            formatted: transcription.formatted.as_deref(),
+        })
+        .into_response(),
+        ResponseFormat::Text => transcription.formatted.unwrap_or(transcription.text).into_response(),
+        ResponseFormat::VerboseJson => Json(VerboseTranscription {
+            task: "transcribe",
+            language: transcription.language.as_deref(),
+            duration: transcription.segments.last().map(|s| s.end).unwrap_or_default(),
+            text: &transcription.text,
+            segments: transcription
+                .segments
+                .iter()
+                .enumerate()
+                .map(|(id, segment)| VerboseSegment {
+                    id,
+                    start: segment.start,
+                    end: segment.end,
+                    text: &segment.text,
+                    speaker: segment.speaker,
+                })
+                .collect(),
+            formatted: transcription.formatted.as_deref(),
+        })
+        .into_response(),
+        ResponseFormat::Srt => to_srt(&transcription.segments).into_response(),
+        ResponseFormat::Vtt => ([(CONTENT_TYPE, "text/vtt")], to_vtt(&transcription.segments)).into_response(),
+    })
+}
+
+#[derive(Deserialize)]
+struct FormatRequest {
+    text: String,
+    template: String,
+    // Names of the speakers when `text` is split into "Name: ..." turns
+    #[serde(default)]
+    speakers: Vec<String>,
+}
+
+#[derive(Serialize)]
+struct FormatResponse {
+    template: String,
+    text: String,
+}
+
+async fn format(
+    State(state): State<Arc<ServerState>>,
+    Json(request): Json<FormatRequest>,
+) -> Result<Json<FormatResponse>, ApiError> {
+    let prefs = state.backend.preferences().await;
+    let template = pipeline::format_template(&prefs, &request.template)
+        .map_err(|e| ApiError::bad_request(e.to_string()))?
+        .with_speakers(request.speakers);
+    let text = format_text(state.backend.as_ref(), &prefs, &template, &request.text).await?;
+
+    Ok(Json(FormatResponse {
+        template: template.id,
+        text,
+    }))
+}
+
+// The same steps as dictating in the app: preferred backend, vocabulary prompt,
+// post-processing, then the optional template
+pub(super) async fn transcribe(
+    backend: &dyn ServerBackend,
+    audio_path: &str,
+    prompt: Option<&str>,
+    template: Option<&str>,
+) -> Result<Transcription, ApiError> {
+    let prefs = backend.preferences().await;
+
+    // Reject unknown templates before spending time on transcription
+    let template = template
+        .map(|id| pipeline::format_template(&prefs, id))
+        .transpose()
+        .map_err(|e| ApiError::bad_request(e.to_string()))?;
+
+    let prompt = prompt
+        .map(str::to_string)
+        .or_else(|| vocabulary_prompt(&prefs.custom_vocabulary));
+    let segments = backend.transcribe_segments(&prefs, audio_path, prompt.as_deref()).await?;
+    let processed = pipeline::postprocess_segments(&prefs, &prefs.custom_vocabulary, &segments);
+
+    let formatted = match &template {
+        Some(template) => {
+            let template = template.clone().with_speakers(speaker_labels(&segments, &[]));
+            Some(format_text(backend, &prefs, &template, &processed.text).await?)
+        }
+        None => None,
+    };
+
+    Ok(Transcription {
+        text: processed.text,
+        segments,
+        language: pipeline::transcription_language(&prefs),
+        formatted,
+    })
+}
+
+async fn format_text(
+    backend: &dyn ServerBackend,
+    prefs: &AppPreferences,
+    template: &FormatTemplate,
+    transcript: &str,
+) -> Result<String, ApiError> {
+    Ok(pipeline::format_or_clean(prefs, template, transcript, || backend.formatting_backend(prefs)).await?)
+}
+
+// Uploaded or streamed audio lives in the temp dir only while it's transcribed
+pub(super) struct TempAudio(PathBuf);
+
+impl TempAudio {
+    pub fn new() -> Self {
+        let id: String = rand::thread_rng()
+            .sample_iter(&Alphanumeric)
+            .take(12)
+            .map(char::from)
+            .collect();
+        Self(std::env::temp_dir().join(format!("supavoice_api_{}.wav", id)))
+    }
+
+    pub fn path(&self) -> &PathBuf {
+        &self.0
+    }
+}
+
+impl Drop for TempAudio {
+    fn drop(&mut self) {
+        let _ = std::fs::remove_file(&self.0);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::formatting::FormattingBackend;
+    use crate::models::ModelRecord;
+    use crate::transcription::WhisperTranscriber;
+    use anyhow::anyhow;
+    use async_trait::async_trait;
+    use axum::body::{to_bytes, Body};
+    use axum::http::header::{AUTHORIZATION, CONNECTION, UPGRADE};
+    use axum::http::{Request, StatusCode};
+    use tower::ServiceExt;
+
+    const TOKEN: &str = "test-token";
+    const BOUNDARY: &str = "supavoice-test-boundary";
+
+    // Answers every upload with the same two segments, without a Whisper model
+    struct FakeBackend;
+
+    #[async_trait]
+    impl ServerBackend for FakeBackend {
+        async fn preferences(&self) -> AppPreferences {
+            AppPreferences::default()
+        }
+
+        async fn transcriber(&self, _prefs: &AppPreferences) -> anyhow::Result<Arc<WhisperTranscriber>> {
+            Err(anyhow!("No Whisper model in tests"))
+        }
+
+        async fn formatting_backend(&self, _prefs: &AppPreferences) -> anyhow::Result<Box<dyn FormattingBackend>> {
+            Err(anyhow!("No formatting backend in tests"))
+        }
+
+        async fn models(&self) -> anyhow::Result<Vec<ModelRecord>> {
+            Ok(Vec::new())
+        }
+
+        async fn transcribe_segments(
+            &self,
+            _prefs: &AppPreferences,
+            _audio_path: &str,
+            _prompt: Option<&str>,
+        ) -> anyhow::Result<Vec<Segment>> {
+            Ok(vec![segment(0.0, 1.5, "Hello there."), segment(1.5, 3.0, "General Kenobi.")])
+        }
+    }
+
+    fn segment(start: f64, end: f64, text: &str) -> Segment {
+        Segment {
+            start,
+            end,
+            text: text.to_string(),
+            speaker: None,
+            confidence: None,
+            no_speech_prob: None,
+            words: Vec::new(),
+        }
+    }
+
+    fn app() -> Router {
+        router(Arc::new(ServerState {
+            backend: Arc::new(FakeBackend),
+            token: TOKEN.to_string(),
+        }))
+    }
+
+    fn wav() -> Vec<u8> {
+        wav_with(1, 16000)
+    }
+
+    // A tenth of a second of silence
+    fn wav_with(channels: u16, sample_rate: u32) -> Vec<u8> {
+        let spec = hound::WavSpec {
+            channels,
+            sample_rate,
+            bits_per_sample: 16,
+            sample_format: hound::SampleFormat::Int,
+        };
+        let mut bytes = std::io::Cursor::new(Vec::new());
+        let mut writer = hound::WavWriter::new(&mut bytes, spec).unwrap();
+        for _ in 0..sample_rate / 10 * channels as u32 {
+            writer.write_sample(0i16).unwrap();
+        }
+        writer.finalize().unwrap();
+        bytes.into_inner()
+    }
+
+    fn upload(file: &[u8], fields: &[(&str, &str)]) -> Request<Body> {
+        let mut body = Vec::new();
+        body.extend_from_slice(
+            format!(
+                "--{}\r\nContent-Disposition: form-data; name=\"file\"; filename=\"audio.wav\"\r\n\r\n",
+                BOUNDARY
+            )
+            .as_bytes(),
+        );
+        body.extend_from_slice(file);
+        for (name, value) in fields {
+            body.extend_from_slice(
+                format!(
+                    "\r\n--{}\r\nContent-Disposition: form-data; name=\"{}\"\r\n\r\n{}",
+                    BOUNDARY, name, value
+                )
+                .as_bytes(),
+            );
+        }
+        body.extend_from_slice(format!("\r\n--{}--\r\n", BOUNDARY).as_bytes());
+
+        Request::post("/v1/audio/transcriptions")
+            .header(AUTHORIZATION, format!("Bearer {}", TOKEN))
+            .header(CONTENT_TYPE, format!("multipart/form-data; boundary={}", BOUNDARY))
+            .body(Body::from(body))
+            .unwrap()
+    }
+
+    async fn send(request: Request<Body>) -> (StatusCode, String, String) {
+        let response = app().oneshot(request).await.unwrap();
+        let status = response.status();
+        let content_type = response
+            .headers()
+            .get(CONTENT_TYPE)
+            .and_then(|value| value.to_str().ok())
+            .unwrap_or_default()
+            .to_string();
+        let body = to_bytes(response.into_body(), usize::MAX).await.unwrap();
+        (status, content_type, String::from_utf8(body.to_vec()).unwrap())
+    }
+
+    fn get(uri: &str, authorization: Option<&str>) -> Request<Body> {
+        let mut request = Request::get(uri);
+        if let Some(authorization) = authorization {
+            request = request.header(AUTHORIZATION, authorization);
+        }
+        request.body(Body::empty()).unwrap()
+    }
+
+    fn websocket(uri: &str) -> Request<Body> {
+        Request::get(uri)
+            .header(CONNECTION, "upgrade")
+            .header(UPGRADE, "websocket")
+            .header("sec-websocket-version", "13")
+            .header("sec-websocket-key", "dGhlIHNhbXBsZSBub25jZQ==")
+            .body(Body::empty())
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn requires_bearer_token() {
+        let bearer = format!("Bearer {}", TOKEN);
+        let cases: &[(Option<&str>, StatusCode)] = &[
+            (None, StatusCode::UNAUTHORIZED),
+            (Some("Bearer wrong-token"), StatusCode::UNAUTHORIZED),
+            (Some(TOKEN), StatusCode::UNAUTHORIZED),
+            (Some(&bearer), StatusCode::OK),
+        ];
+
+        for (authorization, expected) in cases {
+            let (status, _, _) = send(get("/v1/models", *authorization)).await;
+            assert_eq!(status, *expected, "authorization: {:?}", authorization);
+        }
+    }
+
+    #[tokio::test]
+    async fn health_needs_no_token() {
+        let (status, _, body) = send(get("/health", None)).await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(body, "ok");
+    }
+
+    #[tokio::test]
+    async fn query_token_only_for_websocket_upgrades() {
+        let with_token = format!("/v1/audio/stream?access_token={}", TOKEN);
+
+        // Past auth, the upgrade itself fails without a real connection, but not with 401
+        let (status, _, _) = send(websocket(&with_token)).await;
+        assert_ne!(status, StatusCode::UNAUTHORIZED);
+
+        let (status, _, _) = send(websocket("/v1/audio/stream?access_token=wrong-token")).await;
+        assert_eq!(status, StatusCode::UNAUTHORIZED);
+
+        let (status, _, _) = send(get(&with_token, None)).await;
+        assert_eq!(status, StatusCode::UNAUTHORIZED);
+
+        let (status, _, _) = send(get(&format!("/v1/models?access_token={}", TOKEN), None)).await;
+        assert_eq!(status, StatusCode::UNAUTHORIZED);
+    }
+
+    #[tokio::test]
+    async fn response_formats() {
+        let (status, content_type, body) = send(upload(&wav(), &[])).await;
+        assert_eq!(status, StatusCode::OK);
+        assert!(content_type.starts_with("application/json"));
+        assert_eq!(
+            serde_json::from_str::<serde_json::Value>(&body).unwrap(),
+            serde_json::json!({ "text": "Hello there. General Kenobi." })
+        );
+
+        let (status, content_type, body) = send(upload(&wav(), &[("response_format", "text")])).await;
+        assert_eq!(status, StatusCode::OK);
+        assert!(content_type.starts_with("text/plain"));
+        assert_eq!(body, "Hello there. General Kenobi.");
+
+        let (status, _, body) = send(upload(&wav(), &[("response_format", "verbose_json")])).await;
+        assert_eq!(status, StatusCode::OK);
+        let verbose: serde_json::Value = serde_json::from_str(&body).unwrap();
+        assert_eq!(verbose["task"], "transcribe");
+        assert_eq!(verbose["language"], "en");
+        assert_eq!(verbose["duration"], 3.0);
+        assert_eq!(verbose["segments"].as_array().unwrap().len(), 2);
+        assert_eq!(verbose["segments"][1]["text"], "General Kenobi.");
+
+        let (status, _, body) = send(upload(&wav(), &[("response_format", "srt")])).await;
+        assert_eq!(status, StatusCode::OK);
+        assert!(body.starts_with("1\n00:00:00,000 --> 00:00:01,500\nHello there."), "srt: {:?}", body);
+
+        let (status, content_type, body) = send(upload(&wav(), &[("response_format", "vtt")])).await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(content_type, "text/vtt");
+        assert!(body.starts_with("WEBVTT"), "vtt: {:?}", body);
+
+        let (status, _, _) = send(upload(&wav(), &[("response_format", "xml")])).await;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+    }
+
+    #[tokio::test]
+    async fn rejects_bad_uploads() {
+        let (status, _, body) = send(upload(b"ID3 definitely not a wav", &[])).await;
+        assert_eq!(status, StatusCode::BAD_REQUEST, "body: {}", body);
+        assert!(body.contains("invalid_request_error"));
+
+        let (status, _, _) = send(upload(&wav(), &[("template", "limerick")])).await;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+
+        // Would be transcribed at the wrong speed
+        for (channels, sample_rate) in [(1, 48000), (2, 44100), (6, 16000)] {
+            let (status, _, body) = send(upload(&wav_with(channels, sample_rate), &[])).await;
+            assert_eq!(status, StatusCode::BAD_REQUEST, "{} channels at {} Hz", channels, sample_rate);
+            assert!(body.contains("Unsupported WAV format"), "body: {}", body);
+        }
+        let (status, _, body) = send(upload(&wav_with(2, 16000), &[])).await;
+        assert_eq!(status, StatusCode::OK, "body: {}", body);
+    }
+}
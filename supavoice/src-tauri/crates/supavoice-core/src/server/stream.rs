@@ -0,0 +1,128 @@
+use super::error::ApiError;
+use super::routes::{transcribe, ServerState, TempAudio, Transcription, MAX_AUDIO_BYTES, SAMPLE_RATE};
+use anyhow::Result;
+use axum::extract::ws::{Message, WebSocket, WebSocketUpgrade};
+use axum::extract::{Query, State};
+use axum::response::Response;
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+use std::sync::Arc;
+
+#[derive(Deserialize)]
+pub struct StreamParams {
+    #[serde(default = "default_sample_rate")]
+    sample_rate: u32,
+    #[serde(default)]
+    prompt: Option<String>,
+    #[serde(default)]
+    template: Option<String>,
+}
+
+fn default_sample_rate() -> u32 {
+    SAMPLE_RATE
+}
+
+#[derive(Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+enum ClientMessage {
+    End,
+}
+
+#[derive(Serialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+enum ServerMessage {
+    Transcript(Transcription),
+    Error { message: String },
+}
+
+// Clients send binary frames of 16 kHz mono s16le PCM, then `{"type":"end"}`; the
+// server answers with one `transcript` (or `error`) message and closes
+pub async fn transcribe_stream(
+    State(state): State<Arc<ServerState>>,
+    Query(params): Query<StreamParams>,
+    upgrade: WebSocketUpgrade,
+) -> Result<Response, ApiError> {
+    if params.sample_rate != SAMPLE_RATE {
+        return Err(ApiError::bad_request(format!(
+            "Unsupported sample_rate {}; stream 16000 Hz mono s16le PCM",
+            params.sample_rate
+        )));
+    }
+
+    Ok(upgrade
+        .max_message_size(MAX_AUDIO_BYTES)
+        .on_upgrade(move |socket| handle_stream(socket, state, params)))
+}
+
+async fn handle_stream(mut socket: WebSocket, state: Arc<ServerState>, params: StreamParams) {
+    let mut pcm = Vec::new();
+
+    while let Some(message) = socket.recv().await {
+        match message {
+            Ok(Message::Binary(bytes)) => {
+                if pcm.len() + bytes.len() > MAX_AUDIO_BYTES {
+                    let reply = ServerMessage::Error {
+                        message: "Stream exceeds the maximum audio length".to_string(),
+                    };
+                    send(&mut socket, &reply).await;
+                    return;
+                }
+                pcm.extend_from_slice(&bytes);
+            }
+            Ok(Message::Text(text)) => {
+                if let Ok(ClientMessage::End) = serde_json::from_str(text.as_str()) {
+                    break;
+                }
+            }
+            // Nobody is left to send the transcript to
+            Ok(Message::Close(_)) | Err(_) => return,
+            Ok(_) => {}
+        }
+    }
+
+    let reply = match transcribe_pcm(&state, pcm, &params).await {
+        Ok(transcription) => ServerMessage::Transcript(transcription),
+        Err(e) => ServerMessage::Error { message: e.message() },
+    };
+    send(&mut socket, &reply).await;
+    let _ = socket.send(Message::Close(None)).await;
+}
+
+async fn transcribe_pcm(state: &ServerState, pcm: Vec<u8>, params: &StreamParams) -> Result<Transcription, ApiError> {
+    let recording = TempAudio::new();
+    let path = recording.path().clone();
+    tokio::task::spawn_blocking(move || write_wav(&path, &pcm))
+        .await
+        .map_err(anyhow::Error::from)??;
+
+    transcribe(
+        state.backend.as_ref(),
+        &recording.path().to_string_lossy(),
+        params.prompt.as_deref(),
+        params.template.as_deref(),
+    )
+    .await
+}
+
+fn write_wav(path: &Path, pcm: &[u8]) -> Result<()> {
+    let spec = hound::WavSpec {
+        channels: 1,
+        sample_rate: SAMPLE_RATE,
+        bits_per_sample: 16,
+        sample_format: hound::SampleFormat::Int,
+    };
+
+    let mut writer = hound::WavWriter::create(path, spec)?;
+    // A trailing odd byte is half a sample and is dropped
+    for sample in pcm.chunks_exact(2) {
+        writer.write_sample(i16::from_le_bytes([sample[0], sample[1]]))?;
+    }
+    writer.finalize()?;
+    Ok(())
+}
+
+async fn send(socket: &mut WebSocket, message: &ServerMessage) {
+    if let Ok(json) = serde_json::to_string(message) {
+        let _ = socket.send(Message::Text(json.into())).await;
+    }
+}
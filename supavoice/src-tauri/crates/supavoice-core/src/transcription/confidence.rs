@@ -0,0 +1,294 @@
+use super::transcriber::{Segment, Word};
+use serde::Serialize;
+
+// A decoded token with its probability and, when token timestamps are enabled,
+// its time range in seconds from the start of the recording
+pub struct DecodedToken<'t> {
+    pub text: &'t str,
+    pub probability: f32,
+    pub times: Option<(f64, f64)>,
+}
+
+// Builds words from decoded tokens. Whisper's BPE tokens start a new word with a
+// leading space; other tokens continue the previous word, extending its end time.
+pub fn merge_tokens<'t>(tokens: impl IntoIterator<Item = DecodedToken<'t>>) -> Vec<Word> {
+    // Word text, the sum of its tokens' log probabilities and the token count
+    let mut words: Vec<(Word, f32, usize)> = Vec::new();
+    for token in tokens {
+        let log_probability = token.probability.max(1e-6).ln();
+        match words.last_mut() {
+            Some((word, sum, count)) if !token.text.starts_with(' ') => {
+                word.text.push_str(token.text);
+                if let Some((start, end)) = token.times {
+                    word.start = word.start.or(Some(start));
+                    word.end = Some(end);
+                }
+                *sum += log_probability;
+                *count += 1;
+            }
+            _ => {
+                if token.text.trim().is_empty() {
+                    continue;
+                }
+                let word = Word {
+                    text: token.text.trim_start().to_string(),
+                    confidence: 0.0,
+                    flagged: false,
+                    start: token.times.map(|(start, _)| start),
+                    end: token.times.map(|(_, end)| end),
+                };
+                words.push((word, log_probability, 1));
+            }
+        }
+    }
+
+    words
+        .into_iter()
+        .map(|(word, sum, count)| Word {
+            confidence: (sum / count as f32).exp(),
+            ..word
+        })
+        .collect()
+}
+
+// Geometric mean of the word confidences, like a word's over its tokens, so one
+// garbled word pulls the segment down
+pub fn segment_confidence(words: &[Word]) -> Option<f32> {
+    if words.is_empty() {
+        return None;
+    }
+    let sum: f32 = words.iter().map(|word| word.confidence.max(1e-6).ln()).sum();
+    Some((sum / words.len() as f32).exp())
+}
+
+// Marks words whose confidence is below `threshold`
+pub fn flag_words(segments: &mut [Segment], threshold: f32) {
+    for word in segments.iter_mut().flat_map(|segment| segment.words.iter_mut()) {
+        word.flagged = word.confidence < threshold;
+    }
+}
+
+// A run of flagged words within one segment, with the time range to re-run
+#[derive(Serialize, Clone, Debug, PartialEq)]
+pub struct LowConfidenceSpan {
+    pub segment: usize,
+    // Word indices within the segment
+    pub first_word: usize,
+    pub last_word: usize,
+    pub text: String,
+    // Lowest word confidence in the span
+    pub confidence: f32,
+    // Seconds from the start of the recording; the word timings when available,
+    // otherwise the segment's
+    pub start: f64,
+    pub end: f64,
+}
+
+pub fn low_confidence_spans(segments: &[Segment]) -> Vec<LowConfidenceSpan> {
+    let mut spans = Vec::new();
+    for (index, segment) in segments.iter().enumerate() {
+        let mut current: Option<LowConfidenceSpan> = None;
+        for (position, word) in segment.words.iter().enumerate() {
+            if !word.flagged {
+                spans.extend(current.take());
+                continue;
+            }
+            match current.as_mut() {
+                Some(span) => {
+                    span.last_word = position;
+                    span.text.push(' ');
+                    span.text.push_str(&word.text);
+                    span.confidence = span.confidence.min(word.confidence);
+                    span.end = word.end.unwrap_or(segment.end);
+                }
+                None => {
+                    current = Some(LowConfidenceSpan {
+                        segment: index,
+                        first_word: position,
+                        last_word: position,
+                        text: word.text.clone(),
+                        confidence: word.confidence,
+                        // Without word timings the whole segment is re-run
+                        start: word.start.unwrap_or(segment.start),
+                        end: word.end.unwrap_or(segment.end),
+                    })
+                }
+            }
+        }
+        spans.extend(current);
+    }
+    spans
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn token(text: &str, probability: f32, times: Option<(f64, f64)>) -> DecodedToken<'_> {
+        DecodedToken {
+            text,
+            probability,
+            times,
+        }
+    }
+
+    fn word(text: &str, confidence: f32, times: Option<(f64, f64)>) -> Word {
+        Word {
+            text: text.to_string(),
+            confidence,
+            flagged: false,
+            start: times.map(|(start, _)| start),
+            end: times.map(|(_, end)| end),
+        }
+    }
+
+    fn segment(start: f64, end: f64, words: Vec<Word>) -> Segment {
+        Segment {
+            start,
+            end,
+            text: String::new(),
+            speaker: None,
+            confidence: None,
+            no_speech_prob: None,
+            words,
+        }
+    }
+
+    // Confidences to three decimals, so the float math reads as plain numbers
+    fn rounded(confidence: f32) -> f32 {
+        (confidence * 1000.0).round() / 1000.0
+    }
+
+    #[test]
+    fn merges_tokens() {
+        let cases = [
+            (
+                vec![
+                    token(" Hello", 0.9, None),
+                    token(",", 0.4, None),
+                    token(" world", 0.8, None),
+                ],
+                vec![("Hello,", 0.6), ("world", 0.8)],
+            ),
+            // A leading token without a space still starts a word; blank tokens are dropped
+            (
+                vec![
+                    token("Sup", 0.5, None),
+                    token("av", 0.5, None),
+                    token("oice", 0.5, None),
+                    token(" ", 0.1, None),
+                ],
+                vec![("Supavoice", 0.5)],
+            ),
+            (
+                vec![
+                    token(" It", 1.0, None),
+                    token("'s", 0.25, None),
+                    token(" on", 0.9, None),
+                    token(".", 0.9, None),
+                ],
+                vec![("It's", 0.5), ("on.", 0.9)],
+            ),
+            (vec![], vec![]),
+        ];
+
+        for (tokens, expected) in cases {
+            let input: Vec<&str> = tokens.iter().map(|token| token.text).collect();
+            let words: Vec<(String, f32)> = merge_tokens(tokens)
+                .into_iter()
+                .map(|word| (word.text, rounded(word.confidence)))
+                .collect();
+            let expected: Vec<(String, f32)> = expected
+                .into_iter()
+                .map(|(text, confidence)| (text.to_string(), confidence))
+                .collect();
+            assert_eq!(words, expected, "input: {:?}", input);
+        }
+    }
+
+    #[test]
+    fn merged_words_span_their_tokens() {
+        let words = merge_tokens([
+            token(" Tau", 0.9, Some((1.0, 1.2))),
+            token("ri", 0.9, Some((1.2, 1.5))),
+            token(" app", 0.9, Some((1.6, 1.9))),
+            token("!", 0.9, None),
+        ]);
+        let times: Vec<(Option<f64>, Option<f64>)> = words.iter().map(|word| (word.start, word.end)).collect();
+        assert_eq!(times, vec![(Some(1.0), Some(1.5)), (Some(1.6), Some(1.9))]);
+    }
+
+    #[test]
+    fn segment_confidence_is_geometric() {
+        let cases = [
+            (vec![], None),
+            (vec![0.7], Some(0.7)),
+            (vec![0.9, 0.4], Some(0.6)),
+            (vec![1.0, 1.0, 0.125], Some(0.5)),
+        ];
+
+        for (confidences, expected) in cases {
+            let words: Vec<Word> = confidences
+                .iter()
+                .map(|confidence| word("word", *confidence, None))
+                .collect();
+            assert_eq!(
+                segment_confidence(&words).map(rounded),
+                expected,
+                "input: {:?}",
+                confidences
+            );
+        }
+    }
+
+    #[test]
+    fn spans() {
+        let timed = |text, confidence, start| word(text, confidence, Some((start, start + 0.5)));
+        let mut segments = vec![
+            // Adjacent flagged words make one span; a confident word ends it
+            segment(
+                0.0,
+                3.0,
+                vec![
+                    timed("the", 0.9, 0.0),
+                    timed("quick", 0.3, 0.5),
+                    timed("brwn", 0.2, 1.0),
+                    timed("fox", 0.9, 1.5),
+                    timed("jmps", 0.4, 2.0),
+                ],
+            ),
+            // Without word timings the span covers the whole segment
+            segment(
+                3.0,
+                5.0,
+                vec![word("over", 0.9, None), word("teh", 0.1, None), word("dog", 0.2, None)],
+            ),
+            segment(5.0, 6.0, vec![word("fine", 0.95, None)]),
+        ];
+        flag_words(&mut segments, 0.5);
+
+        let spans: Vec<(usize, usize, usize, String, f32, f64, f64)> = low_confidence_spans(&segments)
+            .into_iter()
+            .map(|span| {
+                let confidence = rounded(span.confidence);
+                (
+                    span.segment,
+                    span.first_word,
+                    span.last_word,
+                    span.text,
+                    confidence,
+                    span.start,
+                    span.end,
+                )
+            })
+            .collect();
+        assert_eq!(
+            spans,
+            vec![
+                (0, 1, 2, "quick brwn".to_string(), 0.2, 0.5, 1.5),
+                (0, 4, 4, "jmps".to_string(), 0.4, 2.0, 2.5),
+                (1, 1, 2, "teh dog".to_string(), 0.1, 3.0, 5.0),
+            ]
+        );
+    }
+}
@@ -0,0 +1,308 @@
+use super::transcriber::Segment;
+use crate::preferences::HallucinationGuardPreferences;
+use flate2::write::ZlibEncoder;
+use flate2::Compression;
+use serde::Serialize;
+use std::io::Write;
+
+// Stock phrases from video subtitles that Whisper produces on silence and music,
+// compared after normalization
+const KNOWN_HALLUCINATIONS: &[&str] = &[
+    "thanks for watching",
+    "thank you for watching",
+    "thank you so much for watching",
+    "thanks for watching and see you next time",
+    "please subscribe",
+    "please like and subscribe",
+    "like and subscribe",
+    "dont forget to like and subscribe",
+    "subscribe to my channel",
+    "see you in the next video",
+    "ill see you in the next video",
+    "subtitles by the amaraorg community",
+    "transcription by castingwords",
+];
+
+// Below Whisper's average log probability cutoff of -1, a likely no-speech segment
+// is treated as silence; above it the decoded text is trusted
+const CONFIDENT_SEGMENT: f32 = 0.37;
+
+// Longest segment the compression ratio is judged on, like Whisper's 30s window.
+// Longer text, such as a whole untimed remote transcript, compresses well just by
+// being long.
+const MAX_RATIO_DURATION: f64 = 30.0;
+
+// Frame length for the silence check, in seconds
+const SILENCE_FRAME: f64 = 0.03;
+
+#[derive(Serialize, Clone, Copy, Debug, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum Hallucination {
+    NoSpeech,
+    Repetition,
+    KnownPhrase,
+    Silence,
+}
+
+// The recording, for the silence guard
+pub struct GuardAudio<'a> {
+    pub samples: &'a [f32],
+    pub sample_rate: u32,
+}
+
+// Why `segment` looks invented, if it does
+pub fn detect_hallucination(
+    segment: &Segment,
+    audio: Option<&GuardAudio>,
+    guards: &HallucinationGuardPreferences,
+) -> Option<Hallucination> {
+    if let (Some(threshold), Some(no_speech_prob)) = (guards.no_speech_threshold, segment.no_speech_prob) {
+        if no_speech_prob > threshold && segment.confidence.is_none_or(|confidence| confidence < CONFIDENT_SEGMENT) {
+            return Some(Hallucination::NoSpeech);
+        }
+    }
+
+    // Untimed segments (end 0) hold a whole transcript of unknown length
+    let bounded = segment.end > 0.0 && segment.end - segment.start <= MAX_RATIO_DURATION;
+    if let (Some(threshold), true) = (guards.compression_ratio_threshold, bounded) {
+        if compression_ratio(&segment.text) > threshold {
+            return Some(Hallucination::Repetition);
+        }
+    }
+
+    if guards.filter_known_phrases {
+        let text = normalize(&segment.text);
+        let known = KNOWN_HALLUCINATIONS.iter().any(|phrase| *phrase == text)
+            || guards.extra_phrases.iter().any(|phrase| normalize(phrase) == text);
+        if !text.is_empty() && known {
+            return Some(Hallucination::KnownPhrase);
+        }
+    }
+
+    if let (Some(threshold), Some(audio)) = (guards.silence_threshold, audio) {
+        if is_silent(audio, segment.start, segment.end, threshold) {
+            return Some(Hallucination::Silence);
+        }
+    }
+
+    None
+}
+
+// Drops hallucinated segments, logging what was dropped and why
+pub fn filter_hallucinations(
+    segments: Vec<Segment>,
+    audio: Option<&GuardAudio>,
+    guards: &HallucinationGuardPreferences,
+) -> Vec<Segment> {
+    segments
+        .into_iter()
+        .filter(|segment| match detect_hallucination(segment, audio, guards) {
+            Some(reason) => {
+                eprintln!("👻 Dropped {:?} segment: {:?}", reason, segment.text);
+                false
+            }
+            None => true,
+        })
+        .collect()
+}
+
+// Size of the text over its zlib-compressed size; loops like "I'm sorry. I'm sorry.
+// I'm sorry." compress far better than speech
+pub fn compression_ratio(text: &str) -> f32 {
+    if text.is_empty() {
+        return 0.0;
+    }
+    let mut encoder = ZlibEncoder::new(Vec::new(), Compression::default());
+    // Writing to a Vec can't fail
+    let _ = encoder.write_all(text.as_bytes());
+    let compressed = encoder.finish().unwrap_or_default();
+    text.len() as f32 / compressed.len().max(1) as f32
+}
+
+// Lowercase words without punctuation, so "Thanks for watching!" matches
+fn normalize(text: &str) -> String {
+    text.to_lowercase()
+        .chars()
+        .filter(|c| c.is_alphanumeric() || c.is_whitespace())
+        .collect::<String>()
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+// True when no frame of `start..end` (seconds) is louder than `threshold` RMS
+fn is_silent(audio: &GuardAudio, start: f64, end: f64, threshold: f32) -> bool {
+    let rate = audio.sample_rate as f64;
+    let first = ((start.max(0.0) * rate) as usize).min(audio.samples.len());
+    let last = ((end * rate) as usize).clamp(first, audio.samples.len());
+    // Segments without timing (or outside the audio) can't be checked
+    if first == last {
+        return false;
+    }
+
+    let frame = ((SILENCE_FRAME * rate) as usize).max(1);
+    audio.samples[first..last].chunks(frame).all(|frame| {
+        let energy = frame.iter().map(|sample| sample * sample).sum::<f32>() / frame.len() as f32;
+        energy.sqrt() <= threshold
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn segment(start: f64, end: f64, text: &str) -> Segment {
+        Segment {
+            start,
+            end,
+            text: text.to_string(),
+            speaker: None,
+            confidence: None,
+            no_speech_prob: None,
+            words: Vec::new(),
+        }
+    }
+
+    fn texts(segments: &[Segment]) -> Vec<&str> {
+        segments.iter().map(|segment| segment.text.as_str()).collect()
+    }
+
+    fn disabled() -> HallucinationGuardPreferences {
+        HallucinationGuardPreferences {
+            no_speech_threshold: None,
+            compression_ratio_threshold: None,
+            filter_known_phrases: false,
+            extra_phrases: Vec::new(),
+            silence_threshold: None,
+        }
+    }
+
+    #[test]
+    fn no_speech_probability() {
+        let mut silent = segment(0.0, 2.0, "You");
+        silent.no_speech_prob = Some(0.9);
+        let mut confident = segment(2.0, 4.0, "Ship it on Friday.");
+        confident.no_speech_prob = Some(0.9);
+        confident.confidence = Some(0.8);
+        let mut speech = segment(4.0, 6.0, "Then review the notes.");
+        speech.no_speech_prob = Some(0.1);
+        let unknown = segment(6.0, 8.0, "No probability reported.");
+        let segments = vec![silent, confident, speech, unknown];
+
+        let guards = HallucinationGuardPreferences {
+            no_speech_threshold: Some(0.6),
+            ..disabled()
+        };
+        let kept = filter_hallucinations(segments.clone(), None, &guards);
+        assert_eq!(
+            texts(&kept),
+            ["Ship it on Friday.", "Then review the notes.", "No probability reported."]
+        );
+        assert_eq!(detect_hallucination(&segments[0], None, &guards), Some(Hallucination::NoSpeech));
+
+        assert_eq!(filter_hallucinations(segments, None, &disabled()).len(), 4);
+    }
+
+    #[test]
+    fn repetition_loops() {
+        let segments = vec![
+            segment(0.0, 3.0, "Let's move the launch to next week and tell the team."),
+            segment(3.0, 30.0, &"I'm going to go to the store. ".repeat(12)),
+            segment(30.0, 33.0, "Okay, okay."),
+            // Too long to judge: a remote transcript without timing, and a long segment
+            segment(0.0, 0.0, &"So we talked about the budget again and it's fine. ".repeat(50)),
+            segment(40.0, 100.0, &"Right, so next week then. ".repeat(40)),
+        ];
+
+        let guards = HallucinationGuardPreferences {
+            compression_ratio_threshold: Some(2.4),
+            ..disabled()
+        };
+        assert!(compression_ratio(&segments[1].text) > 2.4);
+        assert!(compression_ratio(&segments[0].text) < 2.4);
+        assert!(compression_ratio(&segments[3].text) > 2.4);
+        let kept = filter_hallucinations(segments, None, &guards);
+        assert_eq!(kept.len(), 4);
+        assert_eq!(
+            texts(&kept)[..2],
+            ["Let's move the launch to next week and tell the team.", "Okay, okay."]
+        );
+    }
+
+    #[test]
+    fn known_phrases() {
+        let segments = vec![
+            segment(0.0, 2.0, "Thanks for watching!"),
+            segment(2.0, 4.0, "Please like and subscribe."),
+            segment(4.0, 6.0, "Thanks for watching the demo, here's what's next."),
+            segment(6.0, 8.0, "Bye for now."),
+            segment(8.0, 10.0, "Subtitles by the Amara.org community"),
+        ];
+
+        let guards = HallucinationGuardPreferences {
+            filter_known_phrases: true,
+            ..disabled()
+        };
+        assert_eq!(
+            texts(&filter_hallucinations(segments.clone(), None, &guards)),
+            ["Thanks for watching the demo, here's what's next.", "Bye for now."]
+        );
+
+        let guards = HallucinationGuardPreferences {
+            filter_known_phrases: true,
+            extra_phrases: vec!["bye for now".to_string()],
+            ..disabled()
+        };
+        assert_eq!(
+            texts(&filter_hallucinations(segments, None, &guards)),
+            ["Thanks for watching the demo, here's what's next."]
+        );
+    }
+
+    #[test]
+    fn silent_regions() {
+        // One second of speech-level noise between two seconds of near silence
+        let sample_rate = 16000;
+        let mut samples = vec![0.001; 2 * sample_rate as usize];
+        samples.extend((0..sample_rate).map(|i| if i % 2 == 0 { 0.2 } else { -0.2 }));
+        samples.extend(vec![0.0; sample_rate as usize]);
+        let audio = GuardAudio {
+            samples: &samples,
+            sample_rate,
+        };
+
+        let segments = vec![
+            segment(0.0, 2.0, "Thank you."),
+            segment(1.5, 3.0, "Here's the plan."),
+            segment(3.0, 4.0, "You"),
+            // Past the end of the audio
+            segment(5.0, 6.0, "Unchecked."),
+        ];
+
+        let guards = HallucinationGuardPreferences {
+            silence_threshold: Some(0.005),
+            ..disabled()
+        };
+        assert_eq!(
+            texts(&filter_hallucinations(segments.clone(), Some(&audio), &guards)),
+            ["Here's the plan.", "Unchecked."]
+        );
+        // Without audio the guard can't run
+        assert_eq!(filter_hallucinations(segments, None, &guards).len(), 4);
+    }
+
+    #[test]
+    fn defaults_keep_ordinary_speech() {
+        let mut speech = segment(0.0, 3.0, "Remind me to call Dana about the budget.");
+        speech.no_speech_prob = Some(0.05);
+        speech.confidence = Some(0.9);
+        let samples = vec![0.1; 3 * 16000];
+        let audio = GuardAudio {
+            samples: &samples,
+            sample_rate: 16000,
+        };
+
+        let guards = HallucinationGuardPreferences::default();
+        assert_eq!(detect_hallucination(&speech, Some(&audio), &guards), None);
+    }
+}
@@ -0,0 +1,13 @@
+pub mod confidence;
+pub mod guards;
+pub mod remote;
+pub mod subtitles;
+pub mod transcriber;
+pub mod whisper;
+
+pub use confidence::{flag_words, low_confidence_spans, LowConfidenceSpan};
+pub use guards::{detect_hallucination, filter_hallucinations, GuardAudio, Hallucination};
+pub use remote::{OpenAiCompatibleTranscriber, RemoteTranscriptionError};
+pub use subtitles::{to_srt, to_vtt};
+pub use transcriber::{join_segments, Segment, Transcriber, Word};
+pub use whisper::{DecodeControl, WhisperTranscriber, LOCAL_LANGUAGE};
@@ -0,0 +1,453 @@
+use super::transcriber::{Segment, Transcriber};
+use anyhow::Result;
+use async_trait::async_trait;
+use reqwest::multipart::{Form, Part};
+use reqwest::{Client, StatusCode};
+use serde::Deserialize;
+use std::path::Path;
+
+#[derive(Debug, thiserror::Error)]
+pub enum RemoteTranscriptionError {
+    #[error("Failed to read audio file: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("Failed to reach transcription server: {0}")]
+    Connection(#[from] reqwest::Error),
+    #[error("Transcription server rejected the API key")]
+    Unauthorized,
+    #[error("Transcription server is rate limiting requests")]
+    RateLimited,
+    #[error("Transcription server rejected the request: {0}")]
+    InvalidRequest(String),
+    #[error("Transcription server returned error {status}: {message}")]
+    Server { status: u16, message: String },
+    #[error("Unexpected response from transcription server: {0}")]
+    InvalidResponse(String),
+}
+
+impl RemoteTranscriptionError {
+    fn from_status(status: StatusCode, body: String) -> Self {
+        // OpenAI-style servers put a readable message in error.message,
+        // faster-whisper servers typically use "detail"
+        let message = serde_json::from_str::<serde_json::Value>(&body)
+            .ok()
+            .and_then(|json| {
+                json["error"]["message"]
+                    .as_str()
+                    .or_else(|| json["detail"].as_str())
+                    .map(str::to_string)
+            })
+            .unwrap_or(body);
+
+        match status {
+            StatusCode::UNAUTHORIZED | StatusCode::FORBIDDEN => Self::Unauthorized,
+            StatusCode::TOO_MANY_REQUESTS => Self::RateLimited,
+            StatusCode::BAD_REQUEST | StatusCode::PAYLOAD_TOO_LARGE | StatusCode::UNPROCESSABLE_ENTITY => {
+                Self::InvalidRequest(message)
+            }
+            _ => Self::Server {
+                status: status.as_u16(),
+                message,
+            },
+        }
+    }
+}
+
+// Any server speaking OpenAI's /v1/audio/transcriptions API (OpenAI, faster-whisper-server, ...)
+pub struct OpenAiCompatibleTranscriber {
+    client: Client,
+    base_url: String,
+    api_key: Option<String>,
+    model: String,
+    language: Option<String>,
+}
+
+impl OpenAiCompatibleTranscriber {
+    pub fn new(base_url: &str, api_key: Option<String>, model: &str) -> Self {
+        Self {
+            client: Client::new(),
+            base_url: base_url.trim_end_matches('/').to_string(),
+            api_key,
+            model: model.to_string(),
+            language: None,
+        }
+    }
+
+    pub fn with_language(mut self, language: Option<String>) -> Self {
+        self.language = language;
+        self
+    }
+
+    pub async fn transcribe_remote(
+        &self,
+        audio_path: &str,
+        prompt: Option<&str>,
+    ) -> Result<String, RemoteTranscriptionError> {
+        let body = self.upload(audio_path, prompt, "json").await?;
+        let json: serde_json::Value =
+            serde_json::from_str(&body).map_err(|e| RemoteTranscriptionError::InvalidResponse(e.to_string()))?;
+        let text = json["text"].as_str().ok_or_else(|| {
+            RemoteTranscriptionError::InvalidResponse("missing \"text\" field".to_string())
+        })?;
+
+        Ok(text.trim().to_string())
+    }
+
+    // Segments with timing and the scores the hallucination guards need. Servers that
+    // only return text (or reject verbose_json, like OpenAI's gpt-4o models) give one
+    // untimed segment.
+    pub async fn transcribe_remote_segments(
+        &self,
+        audio_path: &str,
+        prompt: Option<&str>,
+    ) -> Result<Vec<Segment>, RemoteTranscriptionError> {
+        let body = match self.upload(audio_path, prompt, "verbose_json").await {
+            Ok(body) => body,
+            Err(RemoteTranscriptionError::InvalidRequest(message)) if message.contains("response_format") => {
+                let text = self.transcribe_remote(audio_path, prompt).await?;
+                return Ok(vec![untimed_segment(text)]);
+            }
+            Err(e) => return Err(e),
+        };
+
+        let transcription: VerboseTranscription =
+            serde_json::from_str(&body).map_err(|e| RemoteTranscriptionError::InvalidResponse(e.to_string()))?;
+        if transcription.segments.is_empty() {
+            return Ok(vec![untimed_segment(transcription.text.trim().to_string())]);
+        }
+
+        Ok(transcription
+            .segments
+            .into_iter()
+            .map(|segment| Segment {
+                start: segment.start,
+                end: segment.end,
+                text: segment.text.trim().to_string(),
+                speaker: None,
+                confidence: segment.avg_logprob.map(f32::exp),
+                no_speech_prob: segment.no_speech_prob,
+                words: Vec::new(),
+            })
+            .collect())
+    }
+
+    // Posts the recording and returns the response body
+    async fn upload(
+        &self,
+        audio_path: &str,
+        prompt: Option<&str>,
+        response_format: &str,
+    ) -> Result<String, RemoteTranscriptionError> {
+        let audio = tokio::fs::read(audio_path).await?;
+        let file_name = Path::new(audio_path)
+            .file_name()
+            .map(|name| name.to_string_lossy().to_string())
+            .unwrap_or_else(|| "audio.wav".to_string());
+
+        let mime_type = audio_mime_type(Path::new(audio_path));
+
+        let mut form = Form::new()
+            .part("file", Part::bytes(audio).file_name(file_name).mime_str(mime_type)?)
+            .text("model", self.model.clone())
+            .text("response_format", response_format.to_string());
+
+        if let Some(language) = &self.language {
+            form = form.text("language", language.clone());
+        }
+        if let Some(prompt_text) = prompt {
+            form = form.text("prompt", prompt_text.to_string());
+        }
+
+        eprintln!("🔄 Uploading audio to {}...", self.base_url);
+
+        let mut request = self
+            .client
+            .post(format!("{}/audio/transcriptions", self.base_url))
+            .multipart(form)
+            .timeout(std::time::Duration::from_secs(300));
+
+        if let Some(api_key) = &self.api_key {
+            request = request.bearer_auth(api_key);
+        }
+
+        let response = request.send().await?;
+
+        let status = response.status();
+        if !status.is_success() {
+            let body = response.text().await.unwrap_or_default();
+            return Err(RemoteTranscriptionError::from_status(status, body));
+        }
+
+        Ok(response.text().await?)
+    }
+}
+
+// The parts of OpenAI's verbose_json response that Supavoice uses
+#[derive(Deserialize)]
+struct VerboseTranscription {
+    text: String,
+    #[serde(default)]
+    segments: Vec<VerboseSegment>,
+}
+
+#[derive(Deserialize)]
+struct VerboseSegment {
+    start: f64,
+    end: f64,
+    text: String,
+    #[serde(default)]
+    avg_logprob: Option<f32>,
+    #[serde(default)]
+    no_speech_prob: Option<f32>,
+}
+
+fn untimed_segment(text: String) -> Segment {
+    Segment {
+        start: 0.0,
+        end: 0.0,
+        text,
+        speaker: None,
+        confidence: None,
+        no_speech_prob: None,
+        words: Vec::new(),
+    }
+}
+
+// Servers check the upload's type, so it follows the file extension
+fn audio_mime_type(path: &Path) -> &'static str {
+    let extension = path
+        .extension()
+        .map(|extension| extension.to_string_lossy().to_lowercase())
+        .unwrap_or_default();
+
+    match extension.as_str() {
+        "wav" => "audio/wav",
+        "mp3" | "mpga" | "mpeg" => "audio/mpeg",
+        "m4a" | "mp4" => "audio/mp4",
+        "ogg" | "oga" => "audio/ogg",
+        "flac" => "audio/flac",
+        "webm" => "audio/webm",
+        _ => "application/octet-stream",
+    }
+}
+
+#[async_trait]
+impl Transcriber for OpenAiCompatibleTranscriber {
+    fn name(&self) -> &str {
+        "openai-compatible"
+    }
+
+    async fn transcribe_file(&self, audio_path: &str, prompt: Option<&str>) -> Result<String> {
+        Ok(self.transcribe_remote(audio_path, prompt).await?)
+    }
+
+    async fn transcribe_segments(&self, audio_path: &str, prompt: Option<&str>) -> Result<Vec<Segment>> {
+        Ok(self.transcribe_remote_segments(audio_path, prompt).await?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::extract::{Multipart, State};
+    use axum::routing::post;
+    use axum::Router;
+    use std::sync::{Arc, Mutex};
+
+    #[derive(Debug, PartialEq)]
+    struct Upload {
+        name: String,
+        file_name: Option<String>,
+        content_type: Option<String>,
+        body: Vec<u8>,
+    }
+
+    type Uploads = Arc<Mutex<Vec<Upload>>>;
+    type ErrorCheck = fn(&RemoteTranscriptionError) -> bool;
+
+    // Answers every transcription request with `status` and `body`, recording the multipart parts
+    async fn mock_server(status: u16, body: &'static str) -> (String, Uploads) {
+        mock_server_with(move |_| (status, body)).await
+    }
+
+    // Answers with the status and body `respond` picks for the requested response_format
+    async fn mock_server_with(
+        respond: impl Fn(&str) -> (u16, &'static str) + Clone + Send + Sync + 'static,
+    ) -> (String, Uploads) {
+        let uploads: Uploads = Arc::default();
+        let handler = move |State(uploads): State<Uploads>, mut multipart: Multipart| async move {
+            let mut response_format = String::new();
+            while let Some(field) = multipart.next_field().await.unwrap() {
+                let upload = Upload {
+                    name: field.name().unwrap_or_default().to_string(),
+                    file_name: field.file_name().map(str::to_string),
+                    content_type: field.content_type().map(str::to_string),
+                    body: field.bytes().await.unwrap().to_vec(),
+                };
+                if upload.name == "response_format" {
+                    response_format = String::from_utf8_lossy(&upload.body).to_string();
+                }
+                uploads.lock().unwrap().push(upload);
+            }
+            let (status, body) = respond(&response_format);
+            (axum::http::StatusCode::from_u16(status).unwrap(), body)
+        };
+        let app = Router::new()
+            .route("/v1/audio/transcriptions", post(handler))
+            .with_state(uploads.clone());
+
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let address = listener.local_addr().unwrap();
+        tokio::spawn(async move { axum::serve(listener, app).await.unwrap() });
+        (format!("http://{}/v1/", address), uploads)
+    }
+
+    fn audio_file(name: &str) -> String {
+        let path = std::env::temp_dir().join(format!("supavoice-remote-{}-{}", std::process::id(), name));
+        std::fs::write(&path, b"not really audio").unwrap();
+        path.to_string_lossy().to_string()
+    }
+
+    fn text_part<'u>(uploads: &'u [Upload], name: &str) -> Option<&'u str> {
+        uploads
+            .iter()
+            .find(|upload| upload.name == name)
+            .map(|upload| std::str::from_utf8(&upload.body).unwrap())
+    }
+
+    #[tokio::test]
+    async fn uploads_file_model_prompt_and_language() {
+        let (base_url, uploads) = mock_server(200, r#"{"text": " Hello there. "}"#).await;
+        let audio_path = audio_file("clip.mp3");
+        let transcriber = OpenAiCompatibleTranscriber::new(&base_url, Some("sk-test".to_string()), "whisper-1")
+            .with_language(Some("de".to_string()));
+
+        let text = transcriber.transcribe_remote(&audio_path, Some("Supavoice, Tauri")).await.unwrap();
+        assert_eq!(text, "Hello there.");
+
+        let uploads = uploads.lock().unwrap();
+        let file = uploads.iter().find(|upload| upload.name == "file").unwrap();
+        assert!(file.file_name.as_deref().unwrap().ends_with("clip.mp3"));
+        assert_eq!(file.content_type.as_deref(), Some("audio/mpeg"));
+        assert_eq!(file.body, b"not really audio");
+        assert_eq!(text_part(&uploads, "model"), Some("whisper-1"));
+        assert_eq!(text_part(&uploads, "prompt"), Some("Supavoice, Tauri"));
+        assert_eq!(text_part(&uploads, "language"), Some("de"));
+        assert_eq!(text_part(&uploads, "response_format"), Some("json"));
+    }
+
+    #[tokio::test]
+    async fn leaves_out_unset_prompt_and_language() {
+        let (base_url, uploads) = mock_server(200, r#"{"text": "Hi"}"#).await;
+        let audio_path = audio_file("clip.wav");
+        let transcriber = OpenAiCompatibleTranscriber::new(&base_url, None, "whisper-1");
+
+        assert_eq!(transcriber.transcribe_remote(&audio_path, None).await.unwrap(), "Hi");
+
+        let uploads = uploads.lock().unwrap();
+        let file = uploads.iter().find(|upload| upload.name == "file").unwrap();
+        assert_eq!(file.content_type.as_deref(), Some("audio/wav"));
+        assert_eq!(text_part(&uploads, "prompt"), None);
+        assert_eq!(text_part(&uploads, "language"), None);
+    }
+
+    #[tokio::test]
+    async fn returns_timed_segments() {
+        let body = r#"{
+            "text": "Hello there. Thanks for watching!",
+            "segments": [
+                {"id": 0, "start": 0.0, "end": 1.5, "text": " Hello there.", "avg_logprob": -0.25, "no_speech_prob": 0.01},
+                {"id": 1, "start": 1.5, "end": 3.0, "text": " Thanks for watching!", "avg_logprob": -1.5, "no_speech_prob": 0.9}
+            ]
+        }"#;
+        let (base_url, uploads) = mock_server(200, body).await;
+        let transcriber = OpenAiCompatibleTranscriber::new(&base_url, None, "whisper-1");
+
+        let segments = transcriber.transcribe_remote_segments(&audio_file("segments.wav"), None).await.unwrap();
+        let summary: Vec<(f64, f64, &str, Option<f32>)> = segments
+            .iter()
+            .map(|segment| (segment.start, segment.end, segment.text.as_str(), segment.no_speech_prob))
+            .collect();
+        assert_eq!(
+            summary,
+            vec![(0.0, 1.5, "Hello there.", Some(0.01)), (1.5, 3.0, "Thanks for watching!", Some(0.9))]
+        );
+        assert_eq!(segments[0].confidence, Some((-0.25f32).exp()));
+        assert_eq!(segments[1].confidence, Some((-1.5f32).exp()));
+        assert_eq!(text_part(&uploads.lock().unwrap(), "response_format"), Some("verbose_json"));
+    }
+
+    #[tokio::test]
+    async fn returns_untimed_segment() {
+        let cases = [
+            // A server that ignores response_format
+            (r#"{"text": " Hi "}"#, 1),
+            (r#"{"text": " Hi ", "segments": []}"#, 1),
+            // One that only accepts json, like OpenAI's gpt-4o models
+            (r#"{"error": {"message": "response_format 'verbose_json' is not compatible with this model"}}"#, 2),
+        ];
+
+        for (verbose_body, requests) in cases {
+            let (base_url, uploads) = mock_server_with(move |format| match format {
+                "verbose_json" if verbose_body.contains("error") => (400, verbose_body),
+                "verbose_json" => (200, verbose_body),
+                _ => (200, r#"{"text": " Hi "}"#),
+            })
+            .await;
+            let transcriber = OpenAiCompatibleTranscriber::new(&base_url, None, "gpt-4o-transcribe");
+
+            let segments = transcriber.transcribe_remote_segments(&audio_file("untimed.wav"), None).await.unwrap();
+            assert_eq!(segments, vec![untimed_segment("Hi".to_string())], "input: {:?}", verbose_body);
+            let uploads = uploads.lock().unwrap();
+            let files = uploads.iter().filter(|upload| upload.name == "file").count();
+            assert_eq!(files, requests, "input: {:?}", verbose_body);
+        }
+    }
+
+    #[tokio::test]
+    async fn maps_errors() {
+        let audio_path = audio_file("errors.wav");
+        let cases: &[(u16, &'static str, ErrorCheck)] = &[
+            (401, r#"{"error": {"message": "Invalid API key"}}"#, |e| {
+                matches!(e, RemoteTranscriptionError::Unauthorized)
+            }),
+            (403, "", |e| matches!(e, RemoteTranscriptionError::Unauthorized)),
+            (429, "", |e| matches!(e, RemoteTranscriptionError::RateLimited)),
+            (400, r#"{"error": {"message": "Invalid file format."}}"#, |e| {
+                matches!(e, RemoteTranscriptionError::InvalidRequest(message) if message == "Invalid file format.")
+            }),
+            (422, r#"{"detail": "Unknown model"}"#, |e| {
+                matches!(e, RemoteTranscriptionError::InvalidRequest(message) if message == "Unknown model")
+            }),
+            (413, "Too large", |e| {
+                matches!(e, RemoteTranscriptionError::InvalidRequest(message) if message == "Too large")
+            }),
+            (500, "boom", |e| {
+                matches!(e, RemoteTranscriptionError::Server { status: 500, message } if message == "boom")
+            }),
+            (503, r#"{"error": {"message": "Overloaded"}}"#, |e| {
+                matches!(e, RemoteTranscriptionError::Server { status: 503, message } if message == "Overloaded")
+            }),
+            (200, r#"{"transcript": "wrong field"}"#, |e| {
+                matches!(e, RemoteTranscriptionError::InvalidResponse(_))
+            }),
+        ];
+
+        for (status, body, expected) in cases {
+            let (base_url, _) = mock_server(*status, body).await;
+            let transcriber = OpenAiCompatibleTranscriber::new(&base_url, None, "whisper-1");
+            let error = transcriber.transcribe_remote(&audio_path, None).await.unwrap_err();
+            assert!(expected(&error), "status {}: {:?}", status, error);
+        }
+    }
+
+    #[tokio::test]
+    async fn maps_unreachable_server() {
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let address = listener.local_addr().unwrap();
+        drop(listener);
+
+        let transcriber = OpenAiCompatibleTranscriber::new(&format!("http://{}/v1", address), None, "whisper-1");
+        let error = transcriber.transcribe_remote(&audio_file("offline.wav"), None).await.unwrap_err();
+        assert!(matches!(error, RemoteTranscriptionError::Connection(_)), "{:?}", error);
+    }
+}
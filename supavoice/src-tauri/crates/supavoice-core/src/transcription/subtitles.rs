@@ -0,0 +1,48 @@
+use super::transcriber::Segment;
+
+// SubRip and WebVTT renderings of timed segments, as served by OpenAI's
+// response_format=srt|vtt
+
+pub fn to_srt(segments: &[Segment]) -> String {
+    let mut output = String::new();
+    for (index, segment) in timed(segments).enumerate() {
+        output.push_str(&format!(
+            "{}\n{} --> {}\n{}\n\n",
+            index + 1,
+            timestamp(segment.start, ','),
+            timestamp(segment.end, ','),
+            segment.text.trim()
+        ));
+    }
+    output
+}
+
+pub fn to_vtt(segments: &[Segment]) -> String {
+    let mut output = String::from("WEBVTT\n\n");
+    for segment in timed(segments) {
+        output.push_str(&format!(
+            "{} --> {}\n{}\n\n",
+            timestamp(segment.start, '.'),
+            timestamp(segment.end, '.'),
+            segment.text.trim()
+        ));
+    }
+    output
+}
+
+fn timed(segments: &[Segment]) -> impl Iterator<Item = &Segment> {
+    segments.iter().filter(|segment| !segment.text.trim().is_empty())
+}
+
+// SRT separates milliseconds with a comma, VTT with a dot
+fn timestamp(seconds: f64, separator: char) -> String {
+    let millis = (seconds.max(0.0) * 1000.0).round() as u64;
+    format!(
+        "{:02}:{:02}:{:02}{}{:03}",
+        millis / 3_600_000,
+        millis / 60_000 % 60,
+        millis / 1000 % 60,
+        separator,
+        millis % 1000
+    )
+}
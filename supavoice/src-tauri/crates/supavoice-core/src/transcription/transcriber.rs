@@ -0,0 +1,73 @@
+use anyhow::Result;
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct Segment {
+    // Seconds from the start of the recording
+    pub start: f64,
+    pub end: f64,
+    pub text: String,
+    // Index of the speaker when the recording was diarized
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub speaker: Option<usize>,
+    // Geometric mean of the word confidences (0-1), from backends that report token probabilities
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub confidence: Option<f32>,
+    // Probability (0-1) that the segment isn't speech, from backends that report it
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub no_speech_prob: Option<f32>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub words: Vec<Word>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct Word {
+    pub text: String,
+    // Geometric mean of the word's token probabilities (0-1)
+    pub confidence: f32,
+    // Below the confidence threshold; worth double-checking
+    #[serde(default, skip_serializing_if = "is_false")]
+    pub flagged: bool,
+    // Seconds from the start of the recording, when word timestamps are enabled
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub start: Option<f64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub end: Option<f64>,
+}
+
+fn is_false(value: &bool) -> bool {
+    !value
+}
+
+// Joins segment texts the way Whisper's own output reads
+pub fn join_segments(segments: &[Segment]) -> String {
+    segments
+        .iter()
+        .map(|segment| segment.text.trim())
+        .filter(|text| !text.is_empty())
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+#[async_trait]
+pub trait Transcriber: Send + Sync {
+    fn name(&self) -> &str;
+
+    // `prompt` biases decoding towards custom vocabulary where the backend supports it
+    async fn transcribe_file(&self, audio_path: &str, prompt: Option<&str>) -> Result<String>;
+
+    // Backends without segment timing return the whole transcript as one segment
+    async fn transcribe_segments(&self, audio_path: &str, prompt: Option<&str>) -> Result<Vec<Segment>> {
+        let text = self.transcribe_file(audio_path, prompt).await?;
+        Ok(vec![Segment {
+            start: 0.0,
+            end: 0.0,
+            text,
+            speaker: None,
+            confidence: None,
+            no_speech_prob: None,
+            words: Vec::new(),
+        }])
+    }
+}
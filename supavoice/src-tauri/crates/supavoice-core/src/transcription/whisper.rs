@@ -0,0 +1,302 @@
+use super::confidence::{merge_tokens, segment_confidence, DecodedToken};
+use super::transcriber::{join_segments, Segment, Transcriber};
+use crate::audio::load_wav_at;
+use anyhow::{Context, Result};
+use async_trait::async_trait;
+use std::path::Path;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use whisper_rs::{FullParams, SamplingStrategy, WhisperContext, WhisperContextParameters};
+use rayon::prelude::*;
+
+// Local decoding is English-only for now
+pub const LOCAL_LANGUAGE: &str = "en";
+
+pub struct WhisperTranscriber {
+    ctx: WhisperContext,
+    // Token timestamps cost extra decoding time, so they're only on when the UI
+    // needs word timings
+    word_timestamps: AtomicBool,
+}
+
+// Progress reporting and cancellation for long decodes such as batch jobs
+#[derive(Clone, Default)]
+pub struct DecodeControl {
+    // Percent complete (0-100) across the whole file
+    pub on_progress: Option<Arc<dyn Fn(f32) + Send + Sync>>,
+    pub cancel: Option<Arc<AtomicBool>>,
+}
+
+impl DecodeControl {
+    pub fn is_cancelled(&self) -> bool {
+        self.cancel
+            .as_ref()
+            .is_some_and(|cancel| cancel.load(Ordering::Relaxed))
+    }
+}
+
+// Whisper reports progress per state; chunks decode in parallel, so each keeps its own
+// percentage and the mean is reported
+struct ChunkProgress {
+    control: DecodeControl,
+    chunks: Mutex<Vec<i32>>,
+}
+
+impl ChunkProgress {
+    fn new(control: &DecodeControl, chunk_count: usize) -> Arc<Self> {
+        Arc::new(Self {
+            control: control.clone(),
+            chunks: Mutex::new(vec![0; chunk_count]),
+        })
+    }
+
+    fn update(&self, chunk: usize, percent: i32) {
+        let Some(on_progress) = &self.control.on_progress else {
+            return;
+        };
+        let mean = {
+            let mut chunks = self.chunks.lock().unwrap();
+            chunks[chunk] = percent.clamp(0, 100);
+            chunks.iter().sum::<i32>() as f32 / chunks.len() as f32
+        };
+        on_progress(mean);
+    }
+}
+
+impl WhisperTranscriber {
+    pub fn new<P: AsRef<Path>>(model_path: P) -> Result<Self> {
+        eprintln!("Loading Whisper model from: {:?}", model_path.as_ref());
+
+        let ctx = WhisperContext::new_with_params(
+            model_path.as_ref().to_str().context("Invalid model path")?,
+            WhisperContextParameters::default(),
+        )
+        .context("Failed to load Whisper model")?;
+
+        Ok(Self {
+            ctx,
+            word_timestamps: AtomicBool::new(false),
+        })
+    }
+
+    pub fn set_word_timestamps(&self, enabled: bool) {
+        self.word_timestamps.store(enabled, Ordering::Relaxed);
+    }
+
+    pub fn transcribe(&self, audio_path: &str) -> Result<String> {
+        self.transcribe_with_prompt(audio_path, None)
+    }
+
+    pub fn transcribe_with_prompt(&self, audio_path: &str, prompt: Option<&str>) -> Result<String> {
+        let segments = self.transcribe_segments_with_prompt(audio_path, prompt)?;
+        Ok(join_segments(&segments))
+    }
+
+    pub fn transcribe_segments_with_prompt(&self, audio_path: &str, prompt: Option<&str>) -> Result<Vec<Segment>> {
+        self.transcribe_segments_with_control(audio_path, prompt, &DecodeControl::default())
+    }
+
+    pub fn transcribe_segments_with_control(
+        &self,
+        audio_path: &str,
+        prompt: Option<&str>,
+        control: &DecodeControl,
+    ) -> Result<Vec<Segment>> {
+        // Load and convert audio
+        let sample_rate = 16000;
+        let audio_data = load_wav_at(audio_path, sample_rate)?;
+
+        // For short audio (<30s), use single-pass transcription
+        let duration_secs = audio_data.len() as f32 / sample_rate as f32;
+
+        if duration_secs < 30.0 {
+            let progress = ChunkProgress::new(control, 1);
+            return self.transcribe_single(&audio_data, 0.0, prompt, &progress, 0);
+        }
+
+        // For long audio, split into chunks and process in parallel
+        self.transcribe_chunked(&audio_data, prompt, control)
+    }
+
+    // Re-decodes part of a recording, e.g. a span flagged as low confidence.
+    // Segment times stay relative to the whole recording.
+    pub fn transcribe_range(&self, audio_path: &str, start: f64, end: f64, prompt: Option<&str>) -> Result<Vec<Segment>> {
+        let audio_data = load_wav_at(audio_path, 16000)?;
+
+        let sample_rate = 16000.0;
+        let first = ((start.max(0.0) * sample_rate) as usize).min(audio_data.len());
+        let last = ((end * sample_rate) as usize).clamp(first, audio_data.len());
+        if first == last {
+            anyhow::bail!("Range {:.2}s-{:.2}s is outside the recording", start, end);
+        }
+
+        let progress = ChunkProgress::new(&DecodeControl::default(), 1);
+        self.transcribe_single(&audio_data[first..last], first as f64 / sample_rate, prompt, &progress, 0)
+    }
+
+    // `offset` is where `audio_data` starts in the recording, in seconds
+    fn transcribe_single(
+        &self,
+        audio_data: &[f32],
+        offset: f64,
+        prompt: Option<&str>,
+        progress: &Arc<ChunkProgress>,
+        chunk: usize,
+    ) -> Result<Vec<Segment>> {
+        if progress.control.is_cancelled() {
+            anyhow::bail!("Transcription cancelled");
+        }
+
+        // Create transcription state
+        let mut state = self.ctx.create_state()
+            .context("Failed to create Whisper state")?;
+
+        let word_timestamps = self.word_timestamps.load(Ordering::Relaxed);
+        let mut params = self.create_params(prompt, word_timestamps);
+        let reporter = progress.clone();
+        params.set_progress_callback_safe(move |percent: i32| reporter.update(chunk, percent));
+        if let Some(cancel) = progress.control.cancel.clone() {
+            params.set_abort_callback_safe(move || cancel.load(Ordering::Relaxed));
+        }
+
+        // Run transcription
+        let result = state.full(params, audio_data);
+        if progress.control.is_cancelled() {
+            anyhow::bail!("Transcription cancelled");
+        }
+        result.context("Failed to run Whisper transcription")?;
+        progress.update(chunk, 100);
+
+        // Extract text from all segments
+        let num_segments = state
+            .full_n_segments()
+            .context("Failed to get number of segments")?;
+
+        let eot = self.ctx.token_eot();
+        let mut segments = Vec::with_capacity(num_segments as usize);
+        for i in 0..num_segments {
+            let text = state
+                .full_get_segment_text(i)
+                .context(format!("Failed to get segment {}", i))?;
+            // Whisper timestamps are in centiseconds
+            let t0 = state.full_get_segment_t0(i).context("Failed to get segment start")?;
+            let t1 = state.full_get_segment_t1(i).context("Failed to get segment end")?;
+
+            // Timestamps and other special tokens sort after end-of-text
+            let mut tokens = Vec::new();
+            for j in 0..state.full_n_tokens(i).context("Failed to get number of tokens")? {
+                if state.full_get_token_id(i, j)? >= eot {
+                    continue;
+                }
+                let token = state.full_get_token_text_lossy(i, j)?;
+                let data = state.full_get_token_data(i, j)?;
+                let times = word_timestamps.then(|| (offset + data.t0 as f64 / 100.0, offset + data.t1 as f64 / 100.0));
+                tokens.push((token, data.p, times));
+            }
+            let words = merge_tokens(tokens.iter().map(|(text, probability, times)| DecodedToken {
+                text,
+                probability: *probability,
+                times: *times,
+            }));
+
+            segments.push(Segment {
+                start: offset + t0 as f64 / 100.0,
+                end: offset + t1 as f64 / 100.0,
+                text: text.trim().to_string(),
+                speaker: None,
+                confidence: segment_confidence(&words),
+                // Not exposed by the whisper.cpp bundled with whisper-rs 0.12
+                no_speech_prob: None,
+                words,
+            });
+        }
+
+        Ok(segments)
+    }
+
+    fn transcribe_chunked(&self, audio_data: &[f32], prompt: Option<&str>, control: &DecodeControl) -> Result<Vec<Segment>> {
+        // Split audio into 30-second chunks with 1s overlap for context
+        let sample_rate = 16000;
+        let chunk_size = 30 * sample_rate; // 30 seconds
+        let overlap = sample_rate; // 1 second overlap
+
+        // Each chunk keeps its start offset (in samples) so segment times stay absolute
+        let chunks: Vec<(usize, Vec<f32>)> = audio_data
+            .chunks(chunk_size - overlap)
+            .enumerate()
+            .map(|(i, chunk)| {
+                if i > 0 && audio_data.len() > chunk_size {
+                    // Add overlap from previous chunk
+                    let start = (i * (chunk_size - overlap)).saturating_sub(overlap);
+                    (start, audio_data[start..std::cmp::min(start + chunk_size, audio_data.len())].to_vec())
+                } else {
+                    (i * (chunk_size - overlap), chunk.to_vec())
+                }
+            })
+            .collect();
+
+        eprintln!("🔪 Split audio into {} chunks for parallel processing", chunks.len());
+        let progress = ChunkProgress::new(control, chunks.len());
+
+        // Process chunks in parallel (whisper_rs context is Send + Sync)
+        let transcripts: Result<Vec<Vec<Segment>>> = chunks
+            .par_iter()
+            .enumerate()
+            .map(|(i, (start, chunk))| {
+                eprintln!("🧵 Processing chunk {}/{}", i + 1, chunks.len());
+                self.transcribe_single(chunk, *start as f64 / sample_rate as f64, prompt, &progress, i)
+            })
+            .collect();
+
+        let transcripts = transcripts?;
+
+        // Stitch transcripts together
+        Ok(transcripts.into_iter().flatten().collect())
+    }
+
+    fn create_params(&self, prompt: Option<&str>, word_timestamps: bool) -> FullParams {
+        // Setup transcription parameters - greedy decoding for speed
+        let mut params = FullParams::new(SamplingStrategy::Greedy { best_of: 1 });
+
+        // Speed optimizations
+        params.set_n_threads(2); // Lower per-chunk since we're running in parallel
+        params.set_translate(false);
+        params.set_language(Some(LOCAL_LANGUAGE));
+        params.set_print_special(false);
+        params.set_print_progress(false);
+        params.set_print_realtime(false);
+        params.set_print_timestamps(false);
+        params.set_token_timestamps(word_timestamps);
+        params.set_max_len(0);
+        params.set_suppress_blank(true);
+        params.set_suppress_non_speech_tokens(true);
+
+        // Add custom vocabulary as initial prompt to bias Whisper towards these words
+        if let Some(prompt_text) = prompt {
+            params.set_initial_prompt(prompt_text);
+        }
+
+        params
+    }
+}
+
+// Implemented on the shared handle: decoding is CPU-bound for seconds at a time, so it
+// runs on the blocking pool, which needs an owned reference to the model. Unlike
+// block_in_place, this also works on a current-thread runtime.
+#[async_trait]
+impl Transcriber for Arc<WhisperTranscriber> {
+    fn name(&self) -> &str {
+        "whisper"
+    }
+
+    async fn transcribe_file(&self, audio_path: &str, prompt: Option<&str>) -> Result<String> {
+        let (transcriber, audio_path, prompt) = (self.clone(), audio_path.to_string(), prompt.map(str::to_string));
+        tokio::task::spawn_blocking(move || transcriber.transcribe_with_prompt(&audio_path, prompt.as_deref())).await?
+    }
+
+    async fn transcribe_segments(&self, audio_path: &str, prompt: Option<&str>) -> Result<Vec<Segment>> {
+        let (transcriber, audio_path, prompt) = (self.clone(), audio_path.to_string(), prompt.map(str::to_string));
+        tokio::task::spawn_blocking(move || transcriber.transcribe_segments_with_prompt(&audio_path, prompt.as_deref()))
+            .await?
+    }
+}
@@ -0,0 +1,179 @@
+use super::entry::VocabularyEntry;
+use anyhow::{Context, Result};
+
+const COLUMNS: [&str; 5] = ["canonical", "aliases", "category", "case_sensitive", "weight"];
+// Aliases share one column; commas would clash with the CSV delimiter
+const ALIAS_SEPARATOR: char = '|';
+
+// Parses entries from CSV. A header row (one with a "canonical" cell) selects
+// columns by name; without one, columns are read in the order of COLUMNS, so a
+// plain one-word-per-line list also imports.
+pub fn import_csv(content: &str) -> Result<Vec<VocabularyEntry>> {
+    let mut reader = ::csv::ReaderBuilder::new()
+        .has_headers(false)
+        .flexible(true)
+        .trim(::csv::Trim::All)
+        .from_reader(content.as_bytes());
+
+    let mut columns: Vec<Option<usize>> = (0..COLUMNS.len()).map(Some).collect();
+    let mut entries = Vec::new();
+
+    for (index, record) in reader.records().enumerate() {
+        let line = index + 1;
+        let record = record.with_context(|| format!("Invalid CSV on line {}", line))?;
+
+        if line == 1 && record.iter().any(|cell| cell.eq_ignore_ascii_case(COLUMNS[0])) {
+            columns = COLUMNS
+                .iter()
+                .map(|name| record.iter().position(|cell| cell.eq_ignore_ascii_case(name)))
+                .collect();
+            continue;
+        }
+
+        let cell = |column: usize| {
+            columns[column]
+                .and_then(|index| record.get(index))
+                .filter(|value| !value.is_empty())
+        };
+
+        let Some(canonical) = cell(0) else {
+            // Blank lines
+            continue;
+        };
+
+        let mut entry = VocabularyEntry::new(canonical);
+        if let Some(aliases) = cell(1) {
+            entry.aliases = aliases.split(ALIAS_SEPARATOR).map(str::to_string).collect();
+        }
+        entry.category = cell(2).map(str::to_string);
+        if let Some(case_sensitive) = cell(3) {
+            entry.case_sensitive = parse_bool(case_sensitive)
+                .with_context(|| format!("Invalid case_sensitive value '{}' on line {}", case_sensitive, line))?;
+        }
+        if let Some(weight) = cell(4) {
+            entry.weight = weight
+                .parse()
+                .with_context(|| format!("Invalid weight '{}' on line {}", weight, line))?;
+        }
+
+        entries.push(
+            entry
+                .normalized()
+                .with_context(|| format!("Invalid entry on line {}", line))?,
+        );
+    }
+
+    Ok(entries)
+}
+
+pub fn export_csv(entries: &[VocabularyEntry]) -> Result<String> {
+    let mut writer = ::csv::Writer::from_writer(Vec::new());
+    writer.write_record(COLUMNS)?;
+
+    for entry in entries {
+        writer.write_record([
+            entry.canonical.clone(),
+            entry.aliases.join(&ALIAS_SEPARATOR.to_string()),
+            entry.category.clone().unwrap_or_default(),
+            entry.case_sensitive.to_string(),
+            entry.weight.to_string(),
+        ])?;
+    }
+
+    let bytes = writer.into_inner().map_err(|e| anyhow::anyhow!(e.to_string()))?;
+    Ok(String::from_utf8(bytes)?)
+}
+
+fn parse_bool(value: &str) -> Result<bool> {
+    match value.to_ascii_lowercase().as_str() {
+        "true" | "yes" | "1" => Ok(true),
+        "false" | "no" | "0" => Ok(false),
+        _ => Err(anyhow::anyhow!("expected true or false")),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // canonical, aliases, category, case_sensitive, weight
+    type Fields = (String, Vec<String>, Option<String>, bool, f32);
+
+    // Ids are random, so compare everything else
+    fn fields(entries: &[VocabularyEntry]) -> Vec<Fields> {
+        entries
+            .iter()
+            .map(|entry| {
+                (
+                    entry.canonical.clone(),
+                    entry.aliases.clone(),
+                    entry.category.clone(),
+                    entry.case_sensitive,
+                    entry.weight,
+                )
+            })
+            .collect()
+    }
+
+    #[test]
+    fn round_trip() {
+        let entries = vec![
+            VocabularyEntry {
+                aliases: vec!["sue pa voice".to_string(), "supa voice".to_string()],
+                category: Some("products, apps".to_string()),
+                weight: 2.5,
+                ..VocabularyEntry::new("Supavoice")
+            },
+            VocabularyEntry {
+                case_sensitive: true,
+                ..VocabularyEntry::new("Go")
+            },
+            VocabularyEntry::new("O'Brien \"Bob\""),
+        ];
+
+        let csv = export_csv(&entries).unwrap();
+        assert!(csv.starts_with("canonical,aliases,category,case_sensitive,weight\n"), "{}", csv);
+        assert_eq!(fields(&import_csv(&csv).unwrap()), fields(&entries));
+    }
+
+    #[test]
+    fn imports() {
+        let s = |value: &str| value.to_string();
+        let cases: &[(&str, Vec<Fields>)] = &[
+            // Plain word list
+            (
+                "Tauri\n\nKubernetes\n",
+                vec![
+                    (s("Tauri"), vec![], None, false, 1.0),
+                    (s("Kubernetes"), vec![], None, false, 1.0),
+                ],
+            ),
+            // Header columns in any order and case, with unknown ones ignored
+            (
+                "Weight,notes,CANONICAL,aliases\n3,ignored,GitHub,get hub|git hub\n",
+                vec![(s("GitHub"), vec![s("get hub"), s("git hub")], None, false, 3.0)],
+            ),
+            // Quoted cells with commas, whitespace trimmed, no header
+            (
+                "\"Smith, J.\" , jay smith , people , yes\n",
+                vec![(s("Smith, J."), vec![s("jay smith")], Some(s("people")), true, 1.0)],
+            ),
+        ];
+
+        for (input, expected) in cases {
+            assert_eq!(fields(&import_csv(input).unwrap()), *expected, "input: {:?}", input);
+        }
+    }
+
+    #[test]
+    fn rejects_bad_values() {
+        let cases = [
+            "canonical,case_sensitive\nGo,maybe\n",
+            "canonical,weight\nGo,heavy\n",
+            "canonical,weight\nGo,0\n",
+        ];
+        for input in cases {
+            assert!(import_csv(input).is_err(), "input: {:?}", input);
+        }
+    }
+}
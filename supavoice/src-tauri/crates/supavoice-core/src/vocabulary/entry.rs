@@ -0,0 +1,207 @@
+use rand::distributions::Alphanumeric;
+use rand::Rng;
+use serde::{Deserialize, Deserializer, Serialize};
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct VocabularyEntry {
+    #[serde(default = "new_entry_id")]
+    pub id: String,
+    // The spelling that ends up in transcripts
+    pub canonical: String,
+    // Spoken forms that should be rewritten to `canonical`, e.g. "sue pa voice"
+    #[serde(default)]
+    pub aliases: Vec<String>,
+    // Free-form grouping for the UI ("names", "products", "jargon", ...)
+    #[serde(default)]
+    pub category: Option<String>,
+    // Only rewrite text whose case already matches, e.g. "Go" but not the verb "go"
+    #[serde(default)]
+    pub case_sensitive: bool,
+    // Higher weights win ties between entries and go first in the Whisper prompt
+    #[serde(default = "default_weight")]
+    pub weight: f32,
+}
+
+impl VocabularyEntry {
+    pub fn new(canonical: &str) -> Self {
+        Self {
+            id: new_entry_id(),
+            canonical: canonical.trim().to_string(),
+            aliases: Vec::new(),
+            category: None,
+            case_sensitive: false,
+            weight: default_weight(),
+        }
+    }
+
+    // Trims fields and drops empty or duplicate aliases
+    pub fn normalized(mut self) -> anyhow::Result<Self> {
+        self.canonical = self.canonical.trim().to_string();
+        if self.canonical.is_empty() {
+            return Err(anyhow::anyhow!("Vocabulary entry needs a spelling"));
+        }
+        if !self.weight.is_finite() || self.weight <= 0.0 {
+            return Err(anyhow::anyhow!("Weight for '{}' must be greater than zero", self.canonical));
+        }
+
+        let mut aliases: Vec<String> = Vec::with_capacity(self.aliases.len());
+        for alias in self.aliases.drain(..) {
+            let alias = alias.trim().to_string();
+            if !alias.is_empty() && alias != self.canonical && !aliases.contains(&alias) {
+                aliases.push(alias);
+            }
+        }
+        self.aliases = aliases;
+
+        self.category = self
+            .category
+            .take()
+            .map(|category| category.trim().to_string())
+            .filter(|category| !category.is_empty());
+
+        Ok(self)
+    }
+}
+
+pub fn new_entry_id() -> String {
+    rand::thread_rng()
+        .sample_iter(&Alphanumeric)
+        .take(12)
+        .map(char::from)
+        .collect()
+}
+
+fn default_weight() -> f32 {
+    1.0
+}
+
+// Older preferences stored the vocabulary as plain strings; accept both so
+// existing word lists carry over and are rewritten as entries on the next save
+pub fn deserialize_vocabulary<'de, D>(deserializer: D) -> Result<Vec<VocabularyEntry>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum StoredEntry {
+        Word(String),
+        Entry(VocabularyEntry),
+    }
+
+    let stored = Vec::<StoredEntry>::deserialize(deserializer)?;
+    Ok(stored
+        .into_iter()
+        .map(|entry| match entry {
+            StoredEntry::Word(word) => VocabularyEntry::new(&word),
+            StoredEntry::Entry(entry) => entry,
+        })
+        .filter(|entry| !entry.canonical.trim().is_empty())
+        .collect())
+}
+
+// Whisper only looks at roughly the last 224 prompt tokens, so keep the prompt
+// short and list the highest-weighted spellings
+const MAX_PROMPT_CHARS: usize = 600;
+
+pub fn vocabulary_prompt(entries: &[VocabularyEntry]) -> Option<String> {
+    let mut sorted: Vec<&VocabularyEntry> = entries.iter().collect();
+    sorted.sort_by(|a, b| b.weight.total_cmp(&a.weight));
+
+    let mut words: Vec<&str> = Vec::new();
+    let mut length = 0;
+    for entry in sorted {
+        length += entry.canonical.len() + 2;
+        if length > MAX_PROMPT_CHARS {
+            break;
+        }
+        words.push(&entry.canonical);
+    }
+
+    if words.is_empty() {
+        return None;
+    }
+    Some(format!("Custom vocabulary: {}", words.join(", ")))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn s(value: &str) -> String {
+        value.to_string()
+    }
+
+    #[derive(Deserialize)]
+    struct Stored {
+        #[serde(default, deserialize_with = "deserialize_vocabulary")]
+        vocabulary: Vec<VocabularyEntry>,
+    }
+
+    // canonical, aliases, weight
+    type Fields = (String, Vec<String>, f32);
+
+    fn load(json: &str) -> Vec<Fields> {
+        serde_json::from_str::<Stored>(json)
+            .unwrap()
+            .vocabulary
+            .into_iter()
+            .map(|entry| (entry.canonical, entry.aliases, entry.weight))
+            .collect()
+    }
+
+    #[test]
+    fn migrates_word_lists() {
+        let cases: &[(&str, Vec<Fields>)] = &[
+            (r#"{}"#, vec![]),
+            // Legacy plain strings, blank ones dropped
+            (
+                r#"{"vocabulary": ["Tauri", "  ", "Kubernetes"]}"#,
+                vec![(s("Tauri"), vec![], 1.0), (s("Kubernetes"), vec![], 1.0)],
+            ),
+            // Entries without the newer fields get defaults
+            (
+                r#"{"vocabulary": [{"canonical": "GitHub", "aliases": ["get hub"]}]}"#,
+                vec![(s("GitHub"), vec![s("get hub")], 1.0)],
+            ),
+            // A half-migrated list
+            (
+                r#"{"vocabulary": ["Tauri", {"id": "abc", "canonical": "Go", "case_sensitive": true, "weight": 2.0}]}"#,
+                vec![(s("Tauri"), vec![], 1.0), (s("Go"), vec![], 2.0)],
+            ),
+        ];
+        for (json, expected) in cases {
+            assert_eq!(load(json), *expected, "json: {}", json);
+        }
+    }
+
+    #[test]
+    fn migrated_entries_get_ids() {
+        let stored: Stored = serde_json::from_str(r#"{"vocabulary": ["Tauri", {"canonical": "Go"}, {"id": "abc", "canonical": "Rust"}]}"#).unwrap();
+        let ids: Vec<&str> = stored.vocabulary.iter().map(|entry| entry.id.as_str()).collect();
+        assert_eq!(ids[0].len(), 12);
+        assert_eq!(ids[1].len(), 12);
+        assert_ne!(ids[0], ids[1]);
+        assert_eq!(ids[2], "abc");
+    }
+
+    #[test]
+    fn normalizes() {
+        let entry = VocabularyEntry {
+            aliases: vec![s(" get hub "), s(""), s("get hub"), s("GitHub")],
+            category: Some(s("  ")),
+            ..VocabularyEntry::new(" GitHub ")
+        }
+        .normalized()
+        .unwrap();
+        assert_eq!(entry.canonical, "GitHub");
+        assert_eq!(entry.aliases, ["get hub"]);
+        assert_eq!(entry.category, None);
+
+        assert!(VocabularyEntry::new("  ").normalized().is_err());
+        let heavy = VocabularyEntry {
+            weight: f32::NAN,
+            ..VocabularyEntry::new("Go")
+        };
+        assert!(heavy.normalized().is_err());
+    }
+}
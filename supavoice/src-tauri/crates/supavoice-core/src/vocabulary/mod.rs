@@ -0,0 +1,5 @@
+pub mod csv;
+pub mod entry;
+
+pub use csv::{export_csv, import_csv};
+pub use entry::{deserialize_vocabulary, new_entry_id, vocabulary_prompt, VocabularyEntry};
@@ -0,0 +1,296 @@
+use crate::jobs::queue::{collect_audio_files, is_wav};
+use crate::jobs::{JobQueue, Sidecars};
+use crate::preferences::WatchFolder;
+use anyhow::Result;
+use notify::{Event, EventKind, RecommendedWatcher, RecursiveMode, Watcher};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::{Duration, Instant, SystemTime};
+use tokio::sync::mpsc;
+use tokio::task::JoinHandle;
+
+// Recorders and sync clients write files over seconds; a file is only picked up
+// once its size and modification time have stopped changing for this long
+const SETTLE_TIME: Duration = Duration::from_secs(3);
+const POLL_INTERVAL: Duration = Duration::from_secs(1);
+
+// Watches folders for new WAV recordings and queues them as jobs that write their
+// results beside the audio. Watching stops when this is dropped.
+pub struct FolderWatcher {
+    _watcher: RecommendedWatcher,
+    task: JoinHandle<()>,
+}
+
+struct Candidate {
+    size: u64,
+    modified: Option<SystemTime>,
+    stable_since: Instant,
+}
+
+impl FolderWatcher {
+    pub fn start(mut folders: Vec<WatchFolder>, jobs: Arc<JobQueue>) -> Result<Self> {
+        // Events carry resolved paths (e.g. /private/var on macOS), so match against those
+        for folder in folders.iter_mut() {
+            if let Ok(path) = folder.path.canonicalize() {
+                folder.path = path;
+            }
+        }
+
+        let (tx, rx) = mpsc::unbounded_channel();
+        let mut watcher = notify::recommended_watcher(move |result: notify::Result<Event>| match result {
+            // Renames show up as modifications, which covers sync clients that
+            // download to a temp name first
+            Ok(event) if matches!(event.kind, EventKind::Create(_) | EventKind::Modify(_)) => {
+                for path in event.paths {
+                    let _ = tx.send(path);
+                }
+            }
+            Ok(_) => {}
+            Err(e) => eprintln!("⚠️  Watch folder error: {}", e),
+        })?;
+
+        // A folder on an unplugged card or drive is skipped until the next restart
+        folders.retain(|folder| match watcher.watch(&folder.path, RecursiveMode::Recursive) {
+            Ok(()) => {
+                eprintln!("👀 Watching {}", folder.path.display());
+                true
+            }
+            Err(e) => {
+                eprintln!("⚠️  Can't watch {}: {}", folder.path.display(), e);
+                false
+            }
+        });
+
+        let task = tokio::spawn(settle(folders, jobs, rx));
+        Ok(Self {
+            _watcher: watcher,
+            task,
+        })
+    }
+}
+
+impl Drop for FolderWatcher {
+    fn drop(&mut self) {
+        self.task.abort();
+    }
+}
+
+async fn settle(folders: Vec<WatchFolder>, jobs: Arc<JobQueue>, mut events: mpsc::UnboundedReceiver<PathBuf>) {
+    let mut candidates = HashMap::new();
+
+    // Recordings that arrived while the app wasn't running
+    let roots: Vec<PathBuf> = folders.iter().map(|folder| folder.path.clone()).collect();
+    let existing = tokio::task::spawn_blocking(move || {
+        let mut files = Vec::new();
+        for root in &roots {
+            if let Err(e) = collect_audio_files(root, &mut files) {
+                eprintln!("⚠️  Failed to scan {}: {}", root.display(), e);
+            }
+        }
+        files
+    })
+    .await
+    .unwrap_or_default();
+    for path in existing {
+        observe(&mut candidates, path);
+    }
+
+    let mut poll = tokio::time::interval(POLL_INTERVAL);
+    loop {
+        tokio::select! {
+            path = events.recv() => match path {
+                Some(path) => observe(&mut candidates, path),
+                None => return,
+            },
+            _ = poll.tick() => {
+                for path in settled(&mut candidates) {
+                    if let Some(folder) = folder_for(&folders, &path) {
+                        let sidecars = Sidecars {
+                            template: folder.template.clone(),
+                            subtitles: folder.subtitles,
+                        };
+                        if !jobs.enqueue_watched(vec![path.clone()], sidecars).is_empty() {
+                            eprintln!("📥 New recording {}", path.display());
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
+
+// A recording counts as transcribed once its `.txt` exists; delete it to redo one.
+// One that failed stays in the queue until it's retried there.
+fn observe(candidates: &mut HashMap<PathBuf, Candidate>, path: PathBuf) {
+    if !is_wav(&path) || path.with_extension("txt").exists() {
+        return;
+    }
+    match std::fs::metadata(&path) {
+        Ok(metadata) if metadata.is_file() => {
+            candidates.insert(
+                path,
+                Candidate {
+                    size: metadata.len(),
+                    modified: metadata.modified().ok(),
+                    stable_since: Instant::now(),
+                },
+            );
+        }
+        _ => {
+            candidates.remove(&path);
+        }
+    }
+}
+
+// Removes and returns the candidates that haven't changed for SETTLE_TIME.
+// Polling catches writes that raise no events, e.g. on network shares.
+fn settled(candidates: &mut HashMap<PathBuf, Candidate>) -> Vec<PathBuf> {
+    let mut ready = Vec::new();
+    candidates.retain(|path, candidate| {
+        let Ok(metadata) = std::fs::metadata(path) else {
+            return false;
+        };
+        let modified = metadata.modified().ok();
+        if metadata.len() != candidate.size || modified != candidate.modified {
+            candidate.size = metadata.len();
+            candidate.modified = modified;
+            candidate.stable_since = Instant::now();
+            return true;
+        }
+        // Empty files are placeholders that haven't been written yet
+        if candidate.size == 0 || candidate.stable_since.elapsed() < SETTLE_TIME {
+            return true;
+        }
+        ready.push(path.clone());
+        false
+    });
+    ready.sort();
+    ready
+}
+
+// With nested watch folders the innermost one's settings apply
+fn folder_for<'a>(folders: &'a [WatchFolder], path: &Path) -> Option<&'a WatchFolder> {
+    folders
+        .iter()
+        .filter(|folder| path.starts_with(&folder.path))
+        .max_by_key(|folder| folder.path.components().count())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::Rng;
+
+    // A scratch folder of recordings, removed when dropped
+    struct Folder(PathBuf);
+
+    impl Folder {
+        fn new() -> Self {
+            let id: String = rand::thread_rng()
+                .sample_iter(&rand::distributions::Alphanumeric)
+                .take(12)
+                .map(char::from)
+                .collect();
+            let path = std::env::temp_dir().join(format!("supavoice-watch-{}", id));
+            std::fs::create_dir_all(&path).unwrap();
+            Self(path)
+        }
+
+        fn write(&self, name: &str, bytes: usize) -> PathBuf {
+            let path = self.0.join(name);
+            std::fs::write(&path, vec![0u8; bytes]).unwrap();
+            path
+        }
+    }
+
+    impl Drop for Folder {
+        fn drop(&mut self) {
+            let _ = std::fs::remove_dir_all(&self.0);
+        }
+    }
+
+    fn watch_folder(path: &str, template: Option<&str>) -> WatchFolder {
+        WatchFolder {
+            path: PathBuf::from(path),
+            template: template.map(str::to_string),
+            subtitles: true,
+        }
+    }
+
+    #[test]
+    fn observes_untranscribed_recordings() {
+        let folder = Folder::new();
+        let new = folder.write("new.wav", 10);
+        let done = folder.write("done.wav", 10);
+        folder.write("done.txt", 10);
+        let notes = folder.write("notes.txt", 10);
+
+        let mut candidates = HashMap::new();
+        for path in [new.clone(), done, notes, folder.0.join("missing.wav")] {
+            observe(&mut candidates, path);
+        }
+        assert_eq!(candidates.keys().collect::<Vec<_>>(), [&new]);
+    }
+
+    #[test]
+    fn settles_once_unchanged() {
+        let folder = Folder::new();
+        let long_ago = Instant::now() - SETTLE_TIME;
+        let mut candidates = HashMap::new();
+        let mut add = |name: &str, bytes: usize, size: u64, stable_since: Instant| {
+            let path = folder.write(name, bytes);
+            let modified = std::fs::metadata(&path).unwrap().modified().ok();
+            candidates.insert(
+                path.clone(),
+                Candidate {
+                    size,
+                    modified,
+                    stable_since,
+                },
+            );
+            path
+        };
+
+        let ready = add("ready.wav", 10, 10, long_ago);
+        let recent = add("recent.wav", 10, 10, Instant::now());
+        let empty = add("empty.wav", 0, 0, long_ago);
+        let growing = add("growing.wav", 20, 10, long_ago);
+        let deleted = add("deleted.wav", 10, 10, long_ago);
+        std::fs::remove_file(&deleted).unwrap();
+
+        assert_eq!(settled(&mut candidates), [ready.as_path()]);
+        assert!(!candidates.contains_key(&ready));
+        assert!(!candidates.contains_key(&deleted));
+        assert!(candidates.contains_key(&recent));
+        assert!(candidates.contains_key(&empty));
+
+        // A change restarts the wait
+        let growing = &candidates[&growing];
+        assert_eq!(growing.size, 20);
+        assert!(growing.stable_since.elapsed() < SETTLE_TIME);
+    }
+
+    #[test]
+    fn innermost_folder_applies() {
+        let folders = [
+            watch_folder("/recordings", None),
+            watch_folder("/recordings/meetings", Some("notes")),
+            watch_folder("/recordings/meetings/standups", Some("action_items")),
+        ];
+        let cases: &[(&str, Option<&str>)] = &[
+            ("/recordings/memo.wav", Some("/recordings")),
+            ("/recordings/meetings/monday.wav", Some("/recordings/meetings")),
+            ("/recordings/meetings/2024/monday.wav", Some("/recordings/meetings")),
+            ("/recordings/meetings/standups/tuesday.wav", Some("/recordings/meetings/standups")),
+            // Components, not string prefixes
+            ("/recordings-old/memo.wav", None),
+            ("/elsewhere/memo.wav", None),
+        ];
+
+        for (path, expected) in cases {
+            let folder = folder_for(&folders, Path::new(path));
+            assert_eq!(folder.map(|folder| folder.path.as_path()), expected.map(Path::new), "path: {:?}", path);
+        }
+    }
+}
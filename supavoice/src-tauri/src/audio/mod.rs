@@ -1,3 +0,0 @@
-pub mod recorder;
-
-pub use recorder::AudioRecorder;
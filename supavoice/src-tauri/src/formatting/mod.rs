@@ -1,3 +0,0 @@
-mod llm_formatter;
-
-pub use llm_formatter::LlmFormatter;
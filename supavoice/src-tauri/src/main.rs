@@ -1,23 +1,46 @@
 // Prevents additional console window on Windows in release, DO NOT REMOVE!!
 #![cfg_attr(not(debug_assertions), windows_subsystem = "windows")]
 
-mod audio;
-mod models;
-mod transcription;
-mod formatting;
-mod preferences;
+mod shortcuts;
+
+use supavoice_core::{
+    audio, diarization, formatting, jobs, models, output, pipeline, postprocess, preferences, secrets, server,
+    transcription, vocabulary, watch,
+};
 
 use audio::AudioRecorder;
-use formatting::LlmFormatter;
-use models::{ModelDownloader, ModelRecord, ModelRegistry};
-use preferences::{AppPreferences, PreferencesManager};
+use diarization::speaker_labels;
+use formatting::{
+    parse_structured_notes, FormatTemplate, FormatterStatus, FormattingBackend, LlmFormatter,
+    LocalLlamaBackend, OpenAiCompatibleBackend, StructuredNotes, TemplateLimits,
+};
+#[cfg(feature = "llama-inprocess")]
+use formatting::{InProcessLlama, InProcessLlamaBackend};
+use jobs::{Job, JobEvent, JobHold, JobQueue};
+use models::{DownloadEvent, ModelDownloader, ModelRecord, ModelRegistry};
+use output::OutputMode;
+use postprocess::{clean_text, CleanupLevel, CorrectedTranscript, DictationCommand, Snippet, VocabularyCorrector};
+use preferences::{
+    ApiServerPreferences, AppPreferences, ConfidencePreferences, DiarizationPreferences, FormattingBackendPreference,
+    HallucinationGuardPreferences, PreferencesManager, ShortcutPreferences, TranscriptionBackendPreference,
+    VocabularyImportSummary, WatchFolder,
+};
+use server::{generate_token, ApiServer, ServerBackend, ServerConfig};
+use watch::FolderWatcher;
+use shortcuts::{
+    parse_accelerator, register_shortcuts, ShortcutAction, ShortcutBindings, ShortcutConflict, ShortcutConflicts,
+};
+use async_trait::async_trait;
+use serde::Serialize;
 use std::sync::Arc;
 use tauri::{
     tray::{TrayIconBuilder, TrayIconEvent},
     Emitter, Manager, State, WindowEvent,
 };
 use tauri_plugin_sql::{Migration, MigrationKind};
-use transcription::WhisperTranscriber;
+use tokio::sync::broadcast::error::RecvError;
+use transcription::{low_confidence_spans, LowConfidenceSpan, Segment, WhisperTranscriber};
+use vocabulary::{vocabulary_prompt, VocabularyEntry};
 use std::sync::Mutex;
 use std::sync::atomic::{AtomicBool, Ordering};
 use std::path::PathBuf;
@@ -262,17 +285,46 @@ fn apply_window_vibrancy(window: tauri::WebviewWindow) -> Result<(), String> {
 struct RecordingState {
     path: PathBuf,
     stop_flag: Arc<AtomicBool>,
-    thread: Option<std::thread::JoinHandle<()>>,
+    thread: Option<std::thread::JoinHandle<anyhow::Result<()>>>,
+    // No new batch job starts while recording
+    _jobs_hold: JobHold,
 }
 
 // App state for model management
+#[derive(Clone)]
 struct AppState {
     registry: Arc<ModelRegistry>,
     downloader: Arc<ModelDownloader>,
-    transcriber_cache: Arc<Mutex<Option<WhisperTranscriber>>>,
+    // Async so callers wait for a model being loaded instead of loading it again
+    transcriber_cache: Arc<tokio::sync::Mutex<Option<(PathBuf, Arc<WhisperTranscriber>)>>>,
     formatter_cache: Arc<Mutex<Option<Arc<LlmFormatter>>>>,
+    #[cfg(feature = "llama-inprocess")]
+    inprocess_llama: Arc<InProcessLlama>,
     recording: Arc<Mutex<Option<RecordingState>>>,
     preferences: Arc<PreferencesManager>,
+    api_server: Arc<Mutex<Option<ApiServer>>>,
+    jobs: Arc<JobQueue>,
+    folder_watcher: Arc<Mutex<Option<FolderWatcher>>>,
+}
+
+// The local API server transcribes and formats with the same cached models as the app
+#[async_trait]
+impl ServerBackend for AppState {
+    async fn preferences(&self) -> AppPreferences {
+        self.preferences.get_preferences().await
+    }
+
+    async fn transcriber(&self, prefs: &AppPreferences) -> anyhow::Result<Arc<WhisperTranscriber>> {
+        local_transcriber(self, prefs).await.map_err(anyhow::Error::msg)
+    }
+
+    async fn formatting_backend(&self, prefs: &AppPreferences) -> anyhow::Result<Box<dyn FormattingBackend>> {
+        formatting_backend(self, prefs).await.map_err(anyhow::Error::msg)
+    }
+
+    async fn models(&self) -> anyhow::Result<Vec<ModelRecord>> {
+        self.registry.list_models().await
+    }
 }
 
 #[tauri::command]
@@ -295,7 +347,16 @@ async fn start_download(
 
     // Spawn download task
     tauri::async_runtime::spawn(async move {
-        if let Err(e) = downloader.download_model(model_id_clone.clone(), app.clone()).await {
+        let events = app.clone();
+        let on_event = move |event: DownloadEvent| {
+            let name = match event {
+                DownloadEvent::Progress { .. } => "download_progress",
+                DownloadEvent::Complete { .. } => "download_complete",
+            };
+            let _ = events.emit(name, event);
+        };
+
+        if let Err(e) = downloader.download_model(model_id_clone.clone(), on_event).await {
             eprintln!("Download failed for {}: {}", model_id_clone, e);
             // Emit error event
             let _ = app.emit(
@@ -338,11 +399,8 @@ async fn set_active_whisper_model(
         .map_err(|e| e.to_string())?;
 
     // Clear the cached transcriber
-    {
-        let mut cache = state.transcriber_cache.lock().unwrap();
-        *cache = None;
-        println!("🔄 Cleared Whisper model cache due to preference change");
-    }
+    *state.transcriber_cache.lock().await = None;
+    println!("🔄 Cleared Whisper model cache due to preference change");
 
     // Preload the new model in background
     let registry_clone = state.registry.clone();
@@ -375,9 +433,11 @@ async fn set_active_whisper_model(
             if let Ok(model) = runtime.block_on(registry_clone.get_model(&id)) {
                 if let Some(path) = model.path {
                     println!("📦 Preloading new Whisper model: {}", id);
-                    match WhisperTranscriber::new(path) {
+                    // Held while loading, so a transcription waits for this model
+                    let mut cache = cache_clone.blocking_lock();
+                    match WhisperTranscriber::new(path.clone()) {
                         Ok(transcriber) => {
-                            *cache_clone.lock().unwrap() = Some(transcriber);
+                            *cache = Some((path, Arc::new(transcriber)));
                             println!("✅ New Whisper model preloaded!");
                         }
                         Err(e) => println!("⚠️  Failed to preload model: {}", e),
@@ -402,14 +462,8 @@ async fn set_active_llm_model(
         .await
         .map_err(|e| e.to_string())?;
 
-    // Clear the cached formatter
-    {
-        let mut cache = state.formatter_cache.lock().unwrap();
-        *cache = None;
-        println!("🔄 Cleared LLM formatter cache due to preference change");
-    }
-
-    // Preload the new model in background
+    // Swap the model in background; the cached formatter restarts its server
+    // with the new model instead of answering with the old one
     let registry_clone = state.registry.clone();
     let cache_clone = state.formatter_cache.clone();
     let model_id_clone = model_id.clone();
@@ -441,17 +495,25 @@ async fn set_active_llm_model(
 
         if let Some(model) = selected_model {
             if let Some(model_path) = model.path {
-                println!("📦 Starting LLM server with new model: {}", model.id);
-                match LlmFormatter::new() {
-                    Ok(formatter) => {
-                        if let Err(e) = formatter.start_server_if_needed(&model_path) {
-                            println!("⚠️  Failed to start LLM server: {}", e);
-                        } else {
-                            *cache_clone.lock().unwrap() = Some(Arc::new(formatter));
-                            println!("✅ New LLM model preloaded!");
+                println!("📦 Switching LLM server to model: {}", model.id);
+
+                let cached = cache_clone.lock().unwrap().clone();
+                let formatter = match cached {
+                    Some(formatter) => formatter,
+                    None => match LlmFormatter::new() {
+                        Ok(formatter) => Arc::new(formatter),
+                        Err(e) => {
+                            println!("⚠️  Failed to initialize LLM formatter: {}", e);
+                            return;
                         }
-                    }
-                    Err(e) => println!("⚠️  Failed to initialize LLM formatter: {}", e),
+                    },
+                };
+
+                if let Err(e) = formatter.start_server_if_needed(&model_path) {
+                    println!("⚠️  Failed to start LLM server: {}", e);
+                } else {
+                    *cache_clone.lock().unwrap() = Some(formatter);
+                    println!("✅ New LLM model loaded!");
                 }
             }
         }
@@ -461,26 +523,73 @@ async fn set_active_llm_model(
 }
 
 #[tauri::command]
-async fn add_vocabulary_word(state: State<'_, AppState>, word: String) -> Result<(), String> {
+async fn get_formatter_status(state: State<'_, AppState>) -> Result<Option<FormatterStatus>, String> {
+    let formatter = state.formatter_cache.lock().unwrap().clone();
+    Ok(formatter.map(|formatter| formatter.status()))
+}
+
+#[tauri::command]
+async fn get_vocabulary(state: State<'_, AppState>) -> Result<Vec<VocabularyEntry>, String> {
+    Ok(state.preferences.get_vocabulary().await)
+}
+
+#[tauri::command]
+async fn add_vocabulary_entry(
+    state: State<'_, AppState>,
+    entry: VocabularyEntry,
+) -> Result<VocabularyEntry, String> {
     state
         .preferences
-        .add_vocabulary_word(word)
+        .add_vocabulary_entry(entry)
         .await
         .map_err(|e| e.to_string())
 }
 
 #[tauri::command]
-async fn remove_vocabulary_word(state: State<'_, AppState>, word: String) -> Result<(), String> {
+async fn update_vocabulary_entry(
+    state: State<'_, AppState>,
+    entry: VocabularyEntry,
+) -> Result<VocabularyEntry, String> {
     state
         .preferences
-        .remove_vocabulary_word(word)
+        .update_vocabulary_entry(entry)
         .await
         .map_err(|e| e.to_string())
 }
 
 #[tauri::command]
-async fn get_vocabulary(state: State<'_, AppState>) -> Result<Vec<String>, String> {
-    Ok(state.preferences.get_vocabulary().await)
+async fn remove_vocabulary_entry(state: State<'_, AppState>, id: String) -> Result<(), String> {
+    state
+        .preferences
+        .remove_vocabulary_entry(id)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+async fn import_vocabulary_csv(
+    state: State<'_, AppState>,
+    csv: String,
+    replace: bool,
+) -> Result<VocabularyImportSummary, String> {
+    let entries = vocabulary::import_csv(&csv).map_err(|e| format!("{:#}", e))?;
+    let summary = state
+        .preferences
+        .import_vocabulary(entries, replace)
+        .await
+        .map_err(|e| e.to_string())?;
+
+    println!(
+        "📚 Imported vocabulary: {} added, {} updated",
+        summary.added, summary.updated
+    );
+    Ok(summary)
+}
+
+#[tauri::command]
+async fn export_vocabulary_csv(state: State<'_, AppState>) -> Result<String, String> {
+    let vocabulary = state.preferences.get_vocabulary().await;
+    vocabulary::export_csv(&vocabulary).map_err(|e| e.to_string())
 }
 
 #[tauri::command]
@@ -510,7 +619,7 @@ async fn get_disk_space() -> Result<u64, String> {
 }
 
 #[tauri::command]
-async fn start_recording_toggle(state: State<'_, AppState>) -> Result<(), String> {
+async fn start_recording_toggle(app: tauri::AppHandle, state: State<'_, AppState>) -> Result<(), String> {
     // Use temp directory instead of Desktop to avoid cluttering user's Desktop
     let temp_dir = std::env::temp_dir();
 
@@ -531,13 +640,24 @@ async fn start_recording_toggle(state: State<'_, AppState>) -> Result<(), String
     let stop_flag = Arc::new(AtomicBool::new(false));
     let stop_flag_clone = stop_flag.clone();
     let path_clone = audio_path.clone();
+    let jobs_hold = state.jobs.hold();
 
     let thread = std::thread::spawn(move || {
-        let recorder = AudioRecorder::new();
+        // Drives the input meter, clipping and muted-mic warnings in the UI
+        let level_app = app.clone();
+        let recorder = AudioRecorder::new().with_level_callback(move |level| {
+            let _ = level_app.emit("audio_level", level);
+        });
         // No max duration - record until stopped
-        if let Err(e) = recorder.record_to_file_cancellable(path_clone, None, stop_flag_clone) {
+        let result = recorder.record_to_file_cancellable(path_clone, None, stop_flag_clone.clone());
+        if let Err(e) = &result {
             eprintln!("❌ Recording error: {}", e);
+            // Stopped on its own (e.g. the disk filled up), while the UI still shows a recording
+            if !stop_flag_clone.load(Ordering::Relaxed) {
+                let _ = app.emit("recording_failed", format!("{:#}", e));
+            }
         }
+        result
     });
 
     let mut recording = state.recording.lock().unwrap();
@@ -545,6 +665,7 @@ async fn start_recording_toggle(state: State<'_, AppState>) -> Result<(), String
         path: audio_path,
         stop_flag,
         thread: Some(thread),
+        _jobs_hold: jobs_hold,
     });
 
     Ok(())
@@ -564,8 +685,10 @@ async fn stop_recording(state: State<'_, AppState>) -> Result<String, String> {
         drop(recording_guard);
 
         // Wait for thread to finish
-        if let Some(thread) = rec_state.thread.take() {
-            thread.join().map_err(|_| "Failed to join recording thread".to_string())?;
+        match rec_state.thread.take().map(|thread| thread.join()) {
+            Some(Err(_)) => return Err("Failed to join recording thread".to_string()),
+            Some(Ok(Err(e))) => return Err(format!("Recording failed: {:#}", e)),
+            _ => {}
         }
 
         println!("✅ Recording saved: {:?}", rec_state.path);
@@ -606,157 +729,795 @@ async fn start_recording(duration: u64) -> Result<String, String> {
 }
 
 #[tauri::command]
-async fn transcribe_audio(state: State<'_, AppState>, audio_path: String) -> Result<String, String> {
+async fn set_transcription_backend(
+    state: State<'_, AppState>,
+    backend: TranscriptionBackendPreference,
+) -> Result<(), String> {
+    state
+        .preferences
+        .set_transcription_backend(backend)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+async fn set_transcription_api_key(api_key: Option<String>) -> Result<(), String> {
+    secrets::set_api_key(secrets::TRANSCRIPTION_API_KEY, api_key.as_deref()).map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+async fn has_transcription_api_key() -> Result<bool, String> {
+    secrets::get_api_key(secrets::TRANSCRIPTION_API_KEY)
+        .map(|key| key.is_some())
+        .map_err(|e| e.to_string())
+}
+
+async fn local_transcriber(
+    state: &AppState,
+    prefs: &AppPreferences,
+) -> Result<Arc<WhisperTranscriber>, String> {
+    let model_path = pipeline::whisper_model_path(&state.registry, prefs)
+        .await
+        .map_err(|e| e.to_string())?;
+
+    // The job worker, API server and live dictation all get here; the async lock
+    // makes them wait for one load, which runs off the runtime's worker threads
+    let mut cache = state.transcriber_cache.lock().await;
+    if let Some((cached_path, transcriber)) = cache.as_ref() {
+        if *cached_path == model_path {
+            println!("⚡ Using cached model (FAST!)");
+            return Ok(transcriber.clone());
+        }
+    }
+
+    println!("🔄 Loading model into memory...");
+    let path = model_path.clone();
+    let transcriber = tokio::task::spawn_blocking(move || WhisperTranscriber::new(path))
+        .await
+        .map_err(|e| e.to_string())?
+        .map_err(|e| e.to_string())?;
+    let transcriber = Arc::new(transcriber);
+    *cache = Some((model_path, transcriber.clone()));
+    println!("✅ Model loaded and cached!");
+
+    Ok(transcriber)
+}
+
+#[tauri::command]
+async fn transcribe_audio(
+    state: State<'_, AppState>,
+    app: tauri::AppHandle,
+    audio_path: String,
+) -> Result<String, String> {
+    // Batch jobs wait so live dictation gets the CPU
+    let _hold = state.jobs.hold();
+    transcribe_live(&state, &app, audio_path).await
+}
+
+async fn transcribe_live(
+    state: &State<'_, AppState>,
+    app: &tauri::AppHandle,
+    audio_path: String,
+) -> Result<String, String> {
     // Check user preference first
     let prefs = state.preferences.get_preferences().await;
 
-    let model_id = if let Some(preferred_model) = prefs.active_whisper_model {
-        // Use user's preferred model if it's installed
-        if let Ok(model) = state.registry.get_model(&preferred_model).await {
-            if model.path.is_some() {
-                preferred_model
-            } else {
-                return Err(format!("Selected model '{}' is not installed", preferred_model));
-            }
-        } else {
-            return Err(format!("Selected model '{}' not found", preferred_model));
+    // Build prompt from custom vocabulary
+    let vocabulary = state.preferences.get_vocabulary().await;
+    let prompt = vocabulary_prompt(&vocabulary);
+    if let Some(prompt_text) = &prompt {
+        println!("📚 Using custom vocabulary: {}", prompt_text);
+    }
+
+    let segments = pipeline::transcribe_segments(&prefs, &audio_path, prompt.as_deref(), || async {
+        local_transcriber(state, &prefs).await.map_err(anyhow::Error::msg)
+    })
+    .await
+    .map_err(|e| e.to_string())?;
+
+    let processed = pipeline::postprocess_segments(&prefs, &vocabulary, &segments);
+    let spans = low_confidence_spans(&segments);
+    if !spans.is_empty() {
+        println!("🔍 {} low-confidence span(s) to review", spans.len());
+        // The UI highlights these and can re-run them with `retranscribe_span`
+        let _ = app.emit(
+            "low_confidence_words",
+            &LowConfidenceReport { audio_path, spans },
+        );
+    }
+    let speakers = speaker_labels(&segments, &[]);
+    if !speakers.is_empty() {
+        println!("🗣️  Transcript has {} speakers", speakers.len());
+        // The UI lets the user name them, then relabels with `label_speakers`
+        let _ = app.emit("speakers_detected", &DiarizedTranscript { segments, speakers });
+    }
+    if !processed.snippets.is_empty() {
+        println!("✂️  Expanded {} snippet(s)", processed.snippets.len());
+        let _ = app.emit("snippets_expanded", &processed.snippets);
+    }
+    let corrected = processed.corrected;
+    if !corrected.substitutions.is_empty() {
+        println!("📚 Corrected {} vocabulary word(s)", corrected.substitutions.len());
+        // The UI shows the substitutions and can undo them
+        let _ = app.emit("transcript_corrected", &corrected);
+    }
+
+    Ok(corrected.text)
+}
+
+#[derive(Serialize, Clone)]
+struct LowConfidenceReport {
+    audio_path: String,
+    spans: Vec<LowConfidenceSpan>,
+}
+
+#[derive(Serialize)]
+struct RetranscribedSpan {
+    segments: Vec<Segment>,
+    text: String,
+}
+
+// Re-runs only a flagged span, with the larger review model; the UI swaps the
+// result in for the segment the span came from
+#[tauri::command]
+async fn retranscribe_span(
+    state: State<'_, AppState>,
+    audio_path: String,
+    start: f64,
+    end: f64,
+) -> Result<RetranscribedSpan, String> {
+    let prefs = state.preferences.get_preferences().await;
+    let model_path = pipeline::review_model_path(&state.registry, &prefs)
+        .await
+        .map_err(|e| e.to_string())?;
+    println!("🔁 Re-transcribing {:.1}s-{:.1}s with {}", start, end, model_path.display());
+
+    let prompt = vocabulary_prompt(&prefs.custom_vocabulary);
+    let segments = pipeline::retranscribe_range(model_path, &prefs, &audio_path, start, end, prompt.as_deref())
+        .await
+        .map_err(|e| e.to_string())?;
+    let processed = pipeline::postprocess_segments(&prefs, &prefs.custom_vocabulary, &segments);
+
+    Ok(RetranscribedSpan {
+        segments,
+        text: processed.text,
+    })
+}
+
+#[tauri::command]
+async fn set_confidence(state: State<'_, AppState>, confidence: ConfidencePreferences) -> Result<(), String> {
+    state
+        .preferences
+        .set_confidence(confidence)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+async fn set_hallucination_guards(
+    state: State<'_, AppState>,
+    guards: HallucinationGuardPreferences,
+) -> Result<(), String> {
+    state
+        .preferences
+        .set_hallucination_guards(guards)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+async fn set_word_timestamps(state: State<'_, AppState>, enabled: bool) -> Result<(), String> {
+    state
+        .preferences
+        .set_word_timestamps(enabled)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+// Serves part of a recording as WAV bytes, for click-a-word playback, karaoke
+// highlighting and subtitle editing
+#[tauri::command]
+async fn get_audio_slice(audio_path: String, start: f64, end: f64) -> Result<tauri::ipc::Response, String> {
+    let slice = tokio::task::spawn_blocking(move || audio::read_wav_slice(&audio_path, start, end))
+        .await
+        .map_err(|e| e.to_string())?
+        .map_err(|e| e.to_string())?;
+    Ok(tauri::ipc::Response::new(slice))
+}
+
+#[derive(Serialize, Clone)]
+struct DiarizedTranscript {
+    segments: Vec<Segment>,
+    // Default labels, by speaker index
+    speakers: Vec<String>,
+}
+
+// Re-renders a diarized transcript with user-chosen speaker names
+#[tauri::command]
+async fn label_speakers(
+    state: State<'_, AppState>,
+    segments: Vec<Segment>,
+    names: Vec<String>,
+) -> Result<String, String> {
+    let prefs = state.preferences.get_preferences().await;
+    let processed = pipeline::postprocess_speaker_segments(&prefs, &prefs.custom_vocabulary, &segments, &names);
+    Ok(processed.text)
+}
+
+#[tauri::command]
+async fn set_diarization(state: State<'_, AppState>, diarization: DiarizationPreferences) -> Result<(), String> {
+    state
+        .preferences
+        .set_diarization(diarization)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+// Sends finished text to the configured sink; failures are reported, never returned,
+// since the overlay still has the text
+async fn deliver_output(app: &tauri::AppHandle, mode: OutputMode, text: &str) {
+    if mode == OutputMode::Overlay {
+        return;
+    }
+    if mode.needs_focus() {
+        // Hiding the overlay hands focus back to the app the user was dictating into
+        if let Some(window) = app.get_webview_window("overlay") {
+            let _ = window.hide();
         }
-    } else {
-        // Auto-select: Priority order: whisper-base-en (fastest), small-en, small (multilingual)
-        if let Ok(model) = state.registry.get_model("whisper-base-en").await {
-            if model.path.is_some() {
-                "whisper-base-en".to_string()
-            } else if let Ok(model) = state.registry.get_model("whisper-small-en").await {
-                if model.path.is_some() {
-                    "whisper-small-en".to_string()
-                } else {
-                    "whisper-small".to_string()
-                }
-            } else {
-                "whisper-small".to_string()
-            }
-        } else {
-            "whisper-base-en".to_string()
+        tokio::time::sleep(std::time::Duration::from_millis(150)).await;
+    }
+
+    let text = text.to_string();
+    let delivered = tokio::task::spawn_blocking(move || match output::system_sink(mode)? {
+        Some(sink) => output::deliver(sink.as_ref(), &text),
+        None => Ok(false),
+    })
+    .await
+    .map_err(anyhow::Error::from)
+    .and_then(|result| result);
+
+    match delivered {
+        Ok(true) => {
+            println!("📤 Delivered result via {:?}", mode);
+            let _ = app.emit("output_delivered", mode);
         }
-    };
+        Ok(false) => {}
+        Err(e) => {
+            eprintln!("❌ Output failed: {}", e);
+            let _ = app.emit("output_failed", e.to_string());
+        }
+    }
+}
 
-    let model = state
-        .registry
-        .get_model(&model_id)
+// The UI calls this once with the final text, raw or formatted, so Paste and Type
+// never insert a transcript and then its formatted version too
+#[tauri::command]
+async fn send_output(
+    state: State<'_, AppState>,
+    app: tauri::AppHandle,
+    text: String,
+    template_id: Option<String>,
+) -> Result<(), String> {
+    let prefs = state.preferences.get_preferences().await;
+    deliver_output(&app, prefs.output.mode_for(template_id.as_deref()), &text).await;
+    Ok(())
+}
+
+#[tauri::command]
+async fn set_output_mode(state: State<'_, AppState>, mode: OutputMode) -> Result<(), String> {
+    state
+        .preferences
+        .set_output_mode(mode)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+async fn set_template_output_mode(
+    state: State<'_, AppState>,
+    template_id: String,
+    mode: Option<OutputMode>,
+) -> Result<(), String> {
+    state
+        .preferences
+        .set_template_output_mode(template_id, mode)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+// Files, or folders searched for WAV files
+#[tauri::command]
+async fn enqueue_transcription_jobs(state: State<'_, AppState>, paths: Vec<String>) -> Result<Vec<Job>, String> {
+    let paths: Vec<PathBuf> = paths.into_iter().map(PathBuf::from).collect();
+    state.jobs.enqueue(&paths).map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+async fn list_jobs(state: State<'_, AppState>) -> Result<Vec<Job>, String> {
+    Ok(state.jobs.jobs())
+}
+
+#[tauri::command]
+async fn cancel_job(state: State<'_, AppState>, id: String) -> Result<(), String> {
+    state.jobs.cancel(&id).map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+async fn retry_job(state: State<'_, AppState>, id: String) -> Result<(), String> {
+    state.jobs.retry(&id).map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+async fn remove_job(state: State<'_, AppState>, id: String) -> Result<(), String> {
+    state.jobs.remove(&id).map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+async fn clear_finished_jobs(state: State<'_, AppState>) -> Result<(), String> {
+    state.jobs.clear_finished();
+    Ok(())
+}
+
+// Replaces the running watcher so folder changes take effect
+async fn restart_folder_watcher(state: &AppState) -> Result<(), String> {
+    state.folder_watcher.lock().unwrap().take();
+
+    let prefs = state.preferences.get_preferences().await;
+    if prefs.watch_folders.is_empty() {
+        return Ok(());
+    }
+
+    let watcher = FolderWatcher::start(prefs.watch_folders, state.jobs.clone()).map_err(|e| e.to_string())?;
+    *state.folder_watcher.lock().unwrap() = Some(watcher);
+    Ok(())
+}
+
+#[tauri::command]
+async fn get_watch_folders(state: State<'_, AppState>) -> Result<Vec<WatchFolder>, String> {
+    Ok(state.preferences.get_preferences().await.watch_folders)
+}
+
+// New recordings there are transcribed to `.txt`, plus `.srt` and a formatted `.md`
+// when the folder asks for them
+#[tauri::command]
+async fn add_watch_folder(state: State<'_, AppState>, folder: WatchFolder) -> Result<Vec<WatchFolder>, String> {
+    state
+        .preferences
+        .add_watch_folder(folder)
         .await
         .map_err(|e| e.to_string())?;
 
-    let model_path = model.path.ok_or("Model not installed")?;
+    restart_folder_watcher(&state).await?;
+    Ok(state.preferences.get_preferences().await.watch_folders)
+}
 
-    // Build prompt from custom vocabulary first (before locking cache)
-    let vocabulary = state.preferences.get_vocabulary().await;
-    let prompt = if !vocabulary.is_empty() {
-        let prompt_text = format!("Custom vocabulary: {}", vocabulary.join(", "));
-        println!("📚 Using custom vocabulary: {}", prompt_text);
-        Some(prompt_text)
+#[tauri::command]
+async fn remove_watch_folder(state: State<'_, AppState>, path: String) -> Result<Vec<WatchFolder>, String> {
+    state
+        .preferences
+        .remove_watch_folder(PathBuf::from(path))
+        .await
+        .map_err(|e| e.to_string())?;
+
+    restart_folder_watcher(&state).await?;
+    Ok(state.preferences.get_preferences().await.watch_folders)
+}
+
+#[derive(Serialize)]
+struct ApiServerStatus {
+    enabled: bool,
+    port: u16,
+    // Where clients connect, while the server is running
+    url: Option<String>,
+    token: Option<String>,
+}
+
+// The bearer token is generated once and kept in the OS keyring
+fn api_server_token() -> Result<String, String> {
+    match secrets::get_api_key(secrets::API_SERVER_TOKEN).map_err(|e| e.to_string())? {
+        Some(token) => Ok(token),
+        None => {
+            let token = generate_token();
+            secrets::set_api_key(secrets::API_SERVER_TOKEN, Some(&token)).map_err(|e| e.to_string())?;
+            Ok(token)
+        }
+    }
+}
+
+// Stops any running server and starts a new one if enabled, picking up port and token changes
+async fn restart_api_server(state: &AppState) -> Result<(), String> {
+    if let Some(server) = state.api_server.lock().unwrap().take() {
+        server.stop();
+    }
+
+    let prefs = state.preferences.get_preferences().await;
+    if !prefs.api_server.enabled {
+        return Ok(());
+    }
+
+    let config = ServerConfig {
+        port: prefs.api_server.port,
+        token: api_server_token()?,
+    };
+    let server = ApiServer::start(Arc::new(state.clone()), config)
+        .await
+        .map_err(|e| e.to_string())?;
+    *state.api_server.lock().unwrap() = Some(server);
+    Ok(())
+}
+
+async fn api_server_status(state: &AppState) -> Result<ApiServerStatus, String> {
+    let prefs = state.preferences.get_preferences().await;
+    let url = state
+        .api_server
+        .lock()
+        .unwrap()
+        .as_ref()
+        .map(|server| format!("http://{}", server.addr()));
+    let token = if prefs.api_server.enabled {
+        Some(api_server_token()?)
     } else {
         None
     };
 
-    // Check if model is already cached
-    let mut cache = state.transcriber_cache.lock().unwrap();
+    Ok(ApiServerStatus {
+        enabled: prefs.api_server.enabled,
+        port: prefs.api_server.port,
+        url,
+        token,
+    })
+}
 
-    if cache.is_none() {
-        println!("🔄 Loading model into memory (first time)...");
-        let transcriber = WhisperTranscriber::new(model_path)
-            .map_err(|e| e.to_string())?;
-        *cache = Some(transcriber);
-        println!("✅ Model loaded and cached!");
-    } else {
-        println!("⚡ Using cached model (FAST!)");
+#[tauri::command]
+async fn get_api_server_status(state: State<'_, AppState>) -> Result<ApiServerStatus, String> {
+    api_server_status(&state).await
+}
+
+#[tauri::command]
+async fn set_api_server(
+    state: State<'_, AppState>,
+    enabled: bool,
+    port: u16,
+) -> Result<ApiServerStatus, String> {
+    state
+        .preferences
+        .set_api_server(ApiServerPreferences { enabled, port })
+        .await
+        .map_err(|e| e.to_string())?;
+
+    restart_api_server(&state).await?;
+    api_server_status(&state).await
+}
+
+// Invalidates the old token; clients need the new one
+#[tauri::command]
+async fn regenerate_api_server_token(state: State<'_, AppState>) -> Result<ApiServerStatus, String> {
+    secrets::set_api_key(secrets::API_SERVER_TOKEN, Some(&generate_token())).map_err(|e| e.to_string())?;
+
+    restart_api_server(&state).await?;
+    api_server_status(&state).await
+}
+
+#[tauri::command]
+async fn correct_transcript(
+    state: State<'_, AppState>,
+    transcript: String,
+) -> Result<CorrectedTranscript, String> {
+    let vocabulary = state.preferences.get_vocabulary().await;
+    Ok(VocabularyCorrector::new(&vocabulary).correct(&transcript))
+}
+
+#[tauri::command]
+async fn undo_vocabulary_correction(
+    mut transcript: CorrectedTranscript,
+    index: usize,
+) -> Result<CorrectedTranscript, String> {
+    transcript.undo(index).map_err(|e| e.to_string())?;
+    Ok(transcript)
+}
+
+#[tauri::command]
+async fn get_builtin_dictation_commands(language: String) -> Result<Vec<DictationCommand>, String> {
+    Ok(postprocess::dictation::builtin_commands(&language))
+}
+
+#[tauri::command]
+async fn set_cleanup_level(state: State<'_, AppState>, level: CleanupLevel) -> Result<(), String> {
+    state
+        .preferences
+        .set_cleanup_level(level)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+async fn set_inverse_text_normalization(
+    state: State<'_, AppState>,
+    enabled: bool,
+    language: String,
+) -> Result<(), String> {
+    state
+        .preferences
+        .set_inverse_text_normalization(enabled, language)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+async fn get_shortcuts(state: State<'_, AppState>) -> Result<ShortcutPreferences, String> {
+    Ok(state.preferences.get_preferences().await.shortcuts)
+}
+
+// Shortcuts that failed to register at startup or on the last change
+#[tauri::command]
+fn get_shortcut_conflicts(conflicts: State<'_, ShortcutConflicts>) -> Vec<ShortcutConflict> {
+    conflicts.get()
+}
+
+#[tauri::command]
+async fn set_shortcut(
+    state: State<'_, AppState>,
+    app: tauri::AppHandle,
+    action: ShortcutAction,
+    accelerator: Option<String>,
+) -> Result<Vec<ShortcutConflict>, String> {
+    let accelerator = accelerator.filter(|accelerator| !accelerator.trim().is_empty());
+    if let Some(accelerator) = &accelerator {
+        parse_accelerator(accelerator)?;
     }
 
-    let transcriber = cache.as_ref().unwrap();
+    let mut shortcuts = state.preferences.get_preferences().await.shortcuts;
+    action.bind(&mut shortcuts, accelerator);
+    state
+        .preferences
+        .set_shortcuts(shortcuts.clone())
+        .await
+        .map_err(|e| e.to_string())?;
+
+    // Re-register everything so a freed key combination becomes available to other actions
+    Ok(register_shortcuts(&app, &shortcuts))
+}
+
+#[tauri::command]
+async fn set_dictation_enabled(state: State<'_, AppState>, enabled: bool) -> Result<(), String> {
+    state
+        .preferences
+        .set_dictation_enabled(enabled)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+async fn set_dictation_language(state: State<'_, AppState>, language: String) -> Result<(), String> {
+    state
+        .preferences
+        .set_dictation_language(language)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+async fn set_dictation_commands(
+    state: State<'_, AppState>,
+    language: String,
+    commands: Vec<DictationCommand>,
+) -> Result<(), String> {
+    state
+        .preferences
+        .set_dictation_commands(language, commands)
+        .await
+        .map_err(|e| e.to_string())
+}
 
-    let result = if let Some(prompt_text) = &prompt {
-        transcriber
-            .transcribe_with_prompt(&audio_path, Some(prompt_text))
-            .map_err(|e| e.to_string())?
+#[tauri::command]
+async fn get_snippets(state: State<'_, AppState>) -> Result<Vec<Snippet>, String> {
+    Ok(state.preferences.get_snippets().await)
+}
+
+#[tauri::command]
+async fn add_snippet(state: State<'_, AppState>, snippet: Snippet) -> Result<Snippet, String> {
+    state
+        .preferences
+        .add_snippet(snippet)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+async fn update_snippet(state: State<'_, AppState>, snippet: Snippet) -> Result<Snippet, String> {
+    state
+        .preferences
+        .update_snippet(snippet)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+async fn remove_snippet(state: State<'_, AppState>, id: String) -> Result<(), String> {
+    state
+        .preferences
+        .remove_snippet(id)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+async fn set_formatting_backend(
+    state: State<'_, AppState>,
+    backend: FormattingBackendPreference,
+) -> Result<(), String> {
+    state
+        .preferences
+        .set_formatting_backend(backend)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+async fn set_formatting_api_key(api_key: Option<String>) -> Result<(), String> {
+    secrets::set_api_key(secrets::FORMATTING_API_KEY, api_key.as_deref()).map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+async fn has_formatting_api_key() -> Result<bool, String> {
+    secrets::get_api_key(secrets::FORMATTING_API_KEY)
+        .map(|key| key.is_some())
+        .map_err(|e| e.to_string())
+}
+
+async fn local_formatting_backend(
+    state: &AppState,
+    prefs: &AppPreferences,
+    force_in_process: bool,
+) -> Result<Box<dyn FormattingBackend>, String> {
+    let model_path = pipeline::llm_model_path(&state.registry, prefs)
+        .await
+        .map_err(|e| e.to_string())?;
+
+    // Check if formatter is already cached (just holds binary path, lightweight)
+    // Clone the Arc to avoid holding the lock across await
+    let formatter = if force_in_process {
+        None
     } else {
-        transcriber
-            .transcribe(&audio_path)
-            .map_err(|e| e.to_string())?
+        let mut cache = state.formatter_cache.lock().unwrap();
+
+        if cache.is_none() {
+            println!("🔄 Initializing LLM formatter (locating llama-server binary)...");
+            match LlmFormatter::new() {
+                Ok(formatter) => {
+                    *cache = Some(Arc::new(formatter));
+                    println!("✅ LLM formatter initialized!");
+                }
+                Err(e) => println!("⚠️  llama-server unavailable: {}", e),
+            }
+        }
+
+        cache.clone()
+    }; // Lock is dropped here
+
+    match formatter {
+        Some(formatter) => Ok(Box::new(LocalLlamaBackend::new(formatter, model_path))),
+        None => in_process_formatting_backend(state, model_path),
+    }
+}
+
+#[cfg(feature = "llama-inprocess")]
+fn in_process_formatting_backend(
+    state: &AppState,
+    model_path: PathBuf,
+) -> Result<Box<dyn FormattingBackend>, String> {
+    Ok(Box::new(InProcessLlamaBackend::new(
+        state.inprocess_llama.clone(),
+        model_path,
+    )))
+}
+
+#[cfg(not(feature = "llama-inprocess"))]
+fn in_process_formatting_backend(
+    _state: &AppState,
+    _model_path: PathBuf,
+) -> Result<Box<dyn FormattingBackend>, String> {
+    Err("llama-server is not available and this build does not include in-process llama.cpp (llama-inprocess feature)".to_string())
+}
+
+async fn formatting_backend(
+    state: &AppState,
+    prefs: &AppPreferences,
+) -> Result<Box<dyn FormattingBackend>, String> {
+    let backend: Box<dyn FormattingBackend> = match &prefs.formatting_backend {
+        FormattingBackendPreference::Local => {
+            local_formatting_backend(state, prefs, false).await?
+        }
+        FormattingBackendPreference::InProcess => {
+            local_formatting_backend(state, prefs, true).await?
+        }
+        FormattingBackendPreference::OpenAiCompatible { base_url, model, context_size } => {
+            let api_key = secrets::get_api_key(secrets::FORMATTING_API_KEY)
+                .map_err(|e| e.to_string())?;
+            Box::new(OpenAiCompatibleBackend::new(base_url, api_key, model).with_context_size(*context_size))
+        }
     };
 
-    Ok(result)
+    Ok(backend)
+}
+
+fn format_template(prefs: &AppPreferences, template_id: &str) -> Result<FormatTemplate, String> {
+    pipeline::format_template(prefs, template_id).map_err(|e| e.to_string())
 }
 
 #[tauri::command]
 async fn format_transcript(
     state: State<'_, AppState>,
+    app: tauri::AppHandle,
     transcript: String,
     format_type: String,
+    // Speaker names, in order, when the transcript is labelled by speaker
+    speakers: Option<Vec<String>>,
 ) -> Result<String, String> {
     // Check user preference first
     let prefs = state.preferences.get_preferences().await;
 
-    let model_id = if let Some(preferred_model) = prefs.active_llm_model {
-        // Use user's preferred model if it's installed
-        if let Ok(model) = state.registry.get_model(&preferred_model).await {
-            if model.path.is_some() {
-                preferred_model
-            } else {
-                return Err(format!("Selected LLM model '{}' is not installed", preferred_model));
-            }
-        } else {
-            return Err(format!("Selected LLM model '{}' not found", preferred_model));
-        }
-    } else {
-        // Auto-select: Priority order: gemma-2-2b-instruct > qwen2-1.5b-instruct
-        if let Ok(model) = state.registry.get_model("gemma-2-2b-instruct").await {
-            if model.path.is_some() {
-                "gemma-2-2b-instruct".to_string()
-            } else if let Ok(model) = state.registry.get_model("qwen2-1.5b-instruct").await {
-                if model.path.is_some() {
-                    "qwen2-1.5b-instruct".to_string()
-                } else {
-                    return Err("No LLM model installed. Please install Gemma or Qwen model from Settings.".to_string());
-                }
-            } else {
-                return Err("No LLM model installed. Please install Gemma or Qwen model from Settings.".to_string());
-            }
-        } else {
-            return Err("No LLM model installed. Please install Gemma or Qwen model from Settings.".to_string());
+    let template = format_template(&prefs, &format_type)?.with_speakers(speakers.unwrap_or_default());
+    format_with_preferences(&state, &app, &prefs, &transcript, &template).await
+}
+
+async fn format_with_preferences(
+    state: &State<'_, AppState>,
+    app: &tauri::AppHandle,
+    prefs: &AppPreferences,
+    transcript: &str,
+    template: &FormatTemplate,
+) -> Result<String, String> {
+    let backend = match formatting_backend(state, prefs).await {
+        Ok(backend) => backend,
+        // Without an LLM (e.g. no model installed) text templates still get the
+        // deterministic clean-up instead of an error
+        Err(e) if template.json_schema.is_none() => {
+            println!("⚠️  No formatting backend ({}), returning cleaned transcript", e);
+            let _ = app.emit("formatting_unavailable", &e);
+            let level = prefs.cleanup_level.max(CleanupLevel::Light);
+            return Ok(clean_text(transcript, level));
         }
+        Err(e) => return Err(e),
     };
 
-    let model = state
-        .registry
-        .get_model(&model_id)
+    println!("📝 Formatting as {} with {} backend", template.id, backend.name());
+
+    pipeline::format_transcript(backend.as_ref(), template, transcript)
         .await
-        .map_err(|e| e.to_string())?;
+        .map_err(|e| e.to_string())
+}
 
-    let model_path = model.path.ok_or("Model not installed")?;
+#[tauri::command]
+async fn extract_action_items(
+    state: State<'_, AppState>,
+    transcript: String,
+) -> Result<StructuredNotes, String> {
+    let prefs = state.preferences.get_preferences().await;
 
-    // Check if formatter is already cached (just holds binary path, lightweight)
-    // Clone the Arc to avoid holding the lock across await
-    let formatter = {
-        let mut cache = state.formatter_cache.lock().unwrap();
+    let backend = formatting_backend(&state, &prefs).await?;
+    let template = format_template(&prefs, "action_items")?;
 
-        if cache.is_none() {
-            println!("🔄 Initializing LLM formatter (locating llama-cli binary)...");
-            let formatter = LlmFormatter::new()
-                .map_err(|e| e.to_string())?;
-            *cache = Some(Arc::new(formatter));
-            println!("✅ LLM formatter initialized!");
-        }
+    println!("📋 Extracting action items with {} backend", backend.name());
 
-        cache.as_ref().unwrap().clone()
-    }; // Lock is dropped here
+    let output = formatting::format_with_template(backend.as_ref(), &template, &transcript)
+        .await
+        .map_err(|e| e.to_string())?;
 
-    let result = match format_type.as_str() {
-        "email" => formatter.format_as_email(&model_path, &transcript).await,
-        "notes" => formatter.format_as_notes(&model_path, &transcript).await,
-        _ => Err(anyhow::anyhow!("Unknown format type: {}", format_type)),
-    }
-    .map_err(|e| e.to_string())?;
+    parse_structured_notes(&output).map_err(|e| e.to_string())
+}
 
-    Ok(result)
+#[tauri::command]
+async fn set_template_limits(
+    state: State<'_, AppState>,
+    template_id: String,
+    limits: Option<TemplateLimits>,
+) -> Result<(), String> {
+    state
+        .preferences
+        .set_template_limits(template_id, limits)
+        .await
+        .map_err(|e| e.to_string())
 }
 
 fn main() {
@@ -784,7 +1545,7 @@ fn main() {
     let preferences = Arc::new(PreferencesManager::new().expect("Failed to initialize preferences"));
 
     // Preload Whisper model on startup
-    let transcriber_cache = Arc::new(Mutex::new(None));
+    let transcriber_cache = Arc::new(tokio::sync::Mutex::new(None));
     let registry_clone = registry.clone();
     let cache_clone = transcriber_cache.clone();
 
@@ -811,9 +1572,11 @@ fn main() {
             if let Ok(model) = runtime.block_on(registry_clone.get_model(id)) {
                 if let Some(path) = model.path {
                     println!("📦 Loading model: {}", id);
-                    match WhisperTranscriber::new(path) {
+                    // Held while loading, so a transcription waits for this model
+                    let mut cache = cache_clone.blocking_lock();
+                    match WhisperTranscriber::new(path.clone()) {
                         Ok(transcriber) => {
-                            *cache_clone.lock().unwrap() = Some(transcriber);
+                            *cache = Some((path, Arc::new(transcriber)));
                             println!("✅ Model preloaded successfully!");
                         }
                         Err(e) => println!("⚠️  Failed to preload model: {}", e),
@@ -874,8 +1637,13 @@ fn main() {
         downloader,
         transcriber_cache,
         formatter_cache,
+        #[cfg(feature = "llama-inprocess")]
+        inprocess_llama: Arc::new(InProcessLlama::new()),
         recording: Arc::new(Mutex::new(None)),
         preferences,
+        api_server: Arc::new(Mutex::new(None)),
+        jobs: Arc::new(JobQueue::load().expect("Failed to load job queue")),
+        folder_watcher: Arc::new(Mutex::new(None)),
     };
 
     tauri::Builder::default()
@@ -885,7 +1653,10 @@ fn main() {
                 .add_migrations("sqlite:supavoice.db", migrations)
                 .build()
         )
+        .plugin(shortcuts::plugin())
         .manage(app_state)
+        .manage(ShortcutBindings::default())
+        .manage(ShortcutConflicts::default())
         .setup(|app| {
             // Set activation policy to Accessory on macOS to allow overlay above fullscreen apps
             #[cfg(target_os = "macos")]
@@ -962,8 +1733,48 @@ fn main() {
                 });
             }
 
-            // TODO: Add global hotkey ⌥⌘L (Option+Command+L) - API needs research
-            // For now using tray click to toggle
+            // Global shortcuts from preferences; the overlay fetches any conflicts when it loads
+            let shortcuts = tauri::async_runtime::block_on(
+                app.state::<AppState>().preferences.get_preferences(),
+            )
+            .shortcuts;
+            register_shortcuts(app.handle(), &shortcuts);
+
+            // Batch transcription worker; job changes are forwarded to the UI
+            let state = app.state::<AppState>().inner().clone();
+            tauri::async_runtime::spawn(state.jobs.clone().run(Arc::new(state.clone())));
+            let mut job_events = state.jobs.subscribe();
+            let app_handle = app.handle().clone();
+            tauri::async_runtime::spawn(async move {
+                loop {
+                    match job_events.recv().await {
+                        Ok(JobEvent::Updated(job)) => {
+                            let _ = app_handle.emit("job_updated", &job);
+                        }
+                        Ok(JobEvent::Removed { id }) => {
+                            let _ = app_handle.emit("job_removed", &id);
+                        }
+                        // Missed progress updates are superseded by later ones
+                        Err(RecvError::Lagged(_)) => continue,
+                        Err(RecvError::Closed) => break,
+                    }
+                }
+            });
+
+            // Watch folders feed the same queue
+            let watcher_state = state.clone();
+            tauri::async_runtime::spawn(async move {
+                if let Err(e) = restart_folder_watcher(&watcher_state).await {
+                    println!("⚠️  Failed to watch folders: {}", e);
+                }
+            });
+
+            // Local API server, if enabled in preferences
+            tauri::async_runtime::spawn(async move {
+                if let Err(e) = restart_api_server(&state).await {
+                    println!("⚠️  Failed to start API server: {}", e);
+                }
+            });
 
             Ok(())
         })
@@ -977,14 +1788,63 @@ fn main() {
             get_preferences,
             set_active_whisper_model,
             set_active_llm_model,
-            add_vocabulary_word,
-            remove_vocabulary_word,
+            get_formatter_status,
+            set_formatting_backend,
+            set_formatting_api_key,
+            has_formatting_api_key,
+            set_template_limits,
             get_vocabulary,
+            add_vocabulary_entry,
+            update_vocabulary_entry,
+            remove_vocabulary_entry,
+            import_vocabulary_csv,
+            export_vocabulary_csv,
             start_recording,
             start_recording_toggle,
             stop_recording,
             transcribe_audio,
-            format_transcript
+            correct_transcript,
+            undo_vocabulary_correction,
+            set_cleanup_level,
+            set_diarization,
+            label_speakers,
+            set_confidence,
+            retranscribe_span,
+            set_word_timestamps,
+            set_hallucination_guards,
+            get_audio_slice,
+            set_inverse_text_normalization,
+            get_shortcuts,
+            get_shortcut_conflicts,
+            set_shortcut,
+            send_output,
+            set_output_mode,
+            set_template_output_mode,
+            enqueue_transcription_jobs,
+            list_jobs,
+            cancel_job,
+            retry_job,
+            remove_job,
+            clear_finished_jobs,
+            get_watch_folders,
+            add_watch_folder,
+            remove_watch_folder,
+            get_api_server_status,
+            set_api_server,
+            regenerate_api_server_token,
+            get_builtin_dictation_commands,
+            set_dictation_enabled,
+            set_dictation_language,
+            set_dictation_commands,
+            get_snippets,
+            add_snippet,
+            update_snippet,
+            remove_snippet,
+            set_transcription_backend,
+            set_transcription_api_key,
+            has_transcription_api_key,
+            format_transcript,
+            extract_action_items
         ])
         .run(tauri::generate_context!())
         .expect("error while running tauri application");
@@ -0,0 +1,254 @@
+use crate::preferences::ShortcutPreferences;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::str::FromStr;
+use std::sync::Mutex;
+use tauri::{plugin::TauriPlugin, AppHandle, Emitter, Manager, Wry};
+use tauri_plugin_global_shortcut::{GlobalShortcutExt, Shortcut, ShortcutState};
+
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[serde(rename_all = "snake_case")]
+pub enum ShortcutAction {
+    ToggleRecording,
+    HoldToTalk,
+    ShowOverlay,
+}
+
+impl ShortcutAction {
+    pub const ALL: [ShortcutAction; 3] = [
+        ShortcutAction::ToggleRecording,
+        ShortcutAction::HoldToTalk,
+        ShortcutAction::ShowOverlay,
+    ];
+
+    pub fn binding(self, shortcuts: &ShortcutPreferences) -> Option<&str> {
+        match self {
+            ShortcutAction::ToggleRecording => shortcuts.toggle_recording.as_deref(),
+            ShortcutAction::HoldToTalk => shortcuts.hold_to_talk.as_deref(),
+            ShortcutAction::ShowOverlay => shortcuts.show_overlay.as_deref(),
+        }
+    }
+
+    pub fn bind(self, shortcuts: &mut ShortcutPreferences, accelerator: Option<String>) {
+        let slot = match self {
+            ShortcutAction::ToggleRecording => &mut shortcuts.toggle_recording,
+            ShortcutAction::HoldToTalk => &mut shortcuts.hold_to_talk,
+            ShortcutAction::ShowOverlay => &mut shortcuts.show_overlay,
+        };
+        *slot = accelerator;
+    }
+}
+
+#[derive(Serialize, Clone, Debug)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum ConflictReason {
+    // Not a valid accelerator string
+    Invalid { message: String },
+    // Same keys as another Supavoice action
+    Duplicate { other: ShortcutAction },
+    // The OS refused, usually because another app owns the keys
+    Unavailable { message: String },
+}
+
+#[derive(Serialize, Clone, Debug)]
+pub struct ShortcutConflict {
+    pub action: ShortcutAction,
+    pub accelerator: String,
+    pub reason: ConflictReason,
+}
+
+// Payload of the "shortcut_action" event; `pressed` is false on release
+#[derive(Serialize, Clone, Debug)]
+pub struct ShortcutEventPayload {
+    pub action: ShortcutAction,
+    pub pressed: bool,
+}
+
+// Registered shortcut id -> action, so the plugin handler can route key events
+#[derive(Default)]
+pub struct ShortcutBindings(Mutex<HashMap<u32, ShortcutAction>>);
+
+// Conflicts from the latest registration; the startup ones happen before the UI
+// listens for events, so it fetches them instead
+#[derive(Default)]
+pub struct ShortcutConflicts(Mutex<Vec<ShortcutConflict>>);
+
+impl ShortcutConflicts {
+    pub fn get(&self) -> Vec<ShortcutConflict> {
+        self.0.lock().unwrap().clone()
+    }
+}
+
+pub fn parse_accelerator(accelerator: &str) -> Result<Shortcut, String> {
+    Shortcut::from_str(accelerator).map_err(|e| e.to_string())
+}
+
+// Parses every binding and checks them against each other; the OS-level check happens on register
+pub fn check_shortcuts(
+    shortcuts: &ShortcutPreferences,
+) -> (Vec<(ShortcutAction, Shortcut)>, Vec<ShortcutConflict>) {
+    let mut valid: Vec<(ShortcutAction, Shortcut)> = Vec::new();
+    let mut conflicts = Vec::new();
+
+    for action in ShortcutAction::ALL {
+        let Some(accelerator) = action.binding(shortcuts) else {
+            continue;
+        };
+
+        let reason = match parse_accelerator(accelerator) {
+            Err(message) => ConflictReason::Invalid { message },
+            Ok(shortcut) => match valid.iter().find(|(_, other)| *other == shortcut) {
+                Some((other, _)) => ConflictReason::Duplicate { other: *other },
+                None => {
+                    valid.push((action, shortcut));
+                    continue;
+                }
+            },
+        };
+        conflicts.push(ShortcutConflict {
+            action,
+            accelerator: accelerator.to_string(),
+            reason,
+        });
+    }
+
+    (valid, conflicts)
+}
+
+// Replaces all registered shortcuts with the given bindings and reports the ones that failed
+pub fn register_shortcuts(
+    app: &AppHandle,
+    shortcuts: &ShortcutPreferences,
+) -> Vec<ShortcutConflict> {
+    let global_shortcut = app.global_shortcut();
+    if let Err(e) = global_shortcut.unregister_all() {
+        eprintln!("⚠️  Failed to unregister shortcuts: {}", e);
+    }
+
+    let (valid, mut conflicts) = check_shortcuts(shortcuts);
+    let mut bindings = HashMap::new();
+
+    for (action, shortcut) in valid {
+        match global_shortcut.register(shortcut) {
+            Ok(()) => {
+                println!("⌨️  Registered {} for {:?}", shortcut, action);
+                bindings.insert(shortcut.id(), action);
+            }
+            Err(e) => conflicts.push(ShortcutConflict {
+                action,
+                accelerator: action.binding(shortcuts).unwrap_or_default().to_string(),
+                reason: ConflictReason::Unavailable {
+                    message: e.to_string(),
+                },
+            }),
+        }
+    }
+
+    *app.state::<ShortcutBindings>().0.lock().unwrap() = bindings;
+    *app.state::<ShortcutConflicts>().0.lock().unwrap() = conflicts.clone();
+
+    if !conflicts.is_empty() {
+        println!(
+            "⚠️  {} shortcut(s) could not be registered",
+            conflicts.len()
+        );
+    }
+    conflicts
+}
+
+pub fn plugin() -> TauriPlugin<Wry> {
+    tauri_plugin_global_shortcut::Builder::new()
+        .with_handler(|app, shortcut, event| {
+            let action = app
+                .state::<ShortcutBindings>()
+                .0
+                .lock()
+                .unwrap()
+                .get(&shortcut.id())
+                .copied();
+            if let Some(action) = action {
+                handle_shortcut(app, action, event.state == ShortcutState::Pressed);
+            }
+        })
+        .build()
+}
+
+fn handle_shortcut(app: &AppHandle, action: ShortcutAction, pressed: bool) {
+    let Some(window) = app.get_webview_window("overlay") else {
+        return;
+    };
+
+    match action {
+        ShortcutAction::ShowOverlay => {
+            if !pressed {
+                return;
+            }
+            if window.is_visible().unwrap_or(false) {
+                let _ = window.hide();
+            } else {
+                let _ = window.show();
+                let _ = window.set_focus();
+            }
+        }
+        // Only hold-to-talk cares about the release
+        ShortcutAction::ToggleRecording if !pressed => {}
+        ShortcutAction::ToggleRecording | ShortcutAction::HoldToTalk => {
+            // The overlay drives recording and transcription, so it has to be visible to show progress
+            if pressed {
+                let _ = window.show();
+            }
+            let _ = app.emit("shortcut_action", ShortcutEventPayload { action, pressed });
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn shortcuts(toggle: Option<&str>, hold: Option<&str>, overlay: Option<&str>) -> ShortcutPreferences {
+        ShortcutPreferences {
+            toggle_recording: toggle.map(str::to_string),
+            hold_to_talk: hold.map(str::to_string),
+            show_overlay: overlay.map(str::to_string),
+        }
+    }
+
+    fn actions(valid: &[(ShortcutAction, Shortcut)]) -> Vec<ShortcutAction> {
+        valid.iter().map(|(action, _)| *action).collect()
+    }
+
+    #[test]
+    fn valid_and_unbound() {
+        let (valid, conflicts) = check_shortcuts(&shortcuts(Some("CmdOrCtrl+Shift+Space"), None, Some("Alt+L")));
+        assert_eq!(actions(&valid), [ShortcutAction::ToggleRecording, ShortcutAction::ShowOverlay]);
+        assert!(conflicts.is_empty());
+
+        let (valid, conflicts) = check_shortcuts(&shortcuts(None, None, None));
+        assert!(valid.is_empty());
+        assert!(conflicts.is_empty());
+    }
+
+    #[test]
+    fn invalid_accelerator() {
+        let (valid, conflicts) = check_shortcuts(&shortcuts(Some("Ctrl+NotAKey"), Some("Alt+H"), None));
+        assert_eq!(actions(&valid), [ShortcutAction::HoldToTalk]);
+        assert_eq!(conflicts.len(), 1);
+        assert_eq!(conflicts[0].action, ShortcutAction::ToggleRecording);
+        assert_eq!(conflicts[0].accelerator, "Ctrl+NotAKey");
+        assert!(matches!(conflicts[0].reason, ConflictReason::Invalid { .. }));
+    }
+
+    #[test]
+    fn duplicate_binding() {
+        // Same keys written differently
+        let (valid, conflicts) = check_shortcuts(&shortcuts(Some("Ctrl+Shift+K"), Some("shift+ctrl+k"), None));
+        assert_eq!(actions(&valid), [ShortcutAction::ToggleRecording]);
+        assert_eq!(conflicts.len(), 1);
+        assert_eq!(conflicts[0].action, ShortcutAction::HoldToTalk);
+        assert!(matches!(
+            conflicts[0].reason,
+            ConflictReason::Duplicate { other: ShortcutAction::ToggleRecording }
+        ));
+    }
+}
@@ -1,3 +0,0 @@
-pub mod whisper;
-
-pub use whisper::WhisperTranscriber;
@@ -1,10 +1,11 @@
-import { useEffect, useState } from "react";
+import { useEffect, useRef, useState } from "react";
 import { invoke } from "@tauri-apps/api/core";
+import { listen } from "@tauri-apps/api/event";
 import { Titlebar } from "@/components/Titlebar";
 import { Layout } from "@/components/Layout";
 import Settings from "@/pages/Settings";
 import { configureOverlayWindow } from "@/lib/window";
-import { Mic, Settings as SettingsIcon, Copy, Check, Loader2 } from "lucide-react";
+import { Mic, Settings as SettingsIcon, Copy, Check, Loader2, Send } from "lucide-react";
 import { Button } from "@/components/ui/button";
 
 interface TranscriptionResult {
@@ -26,6 +27,11 @@ function App() {
   const [error, setError] = useState<string | null>(null);
   const [isFormatting, setIsFormatting] = useState(false);
   const [formattedText, setFormattedText] = useState<string>("");
+  // Template the formatted text came from, which picks its output mode
+  const [formatType, setFormatType] = useState<string | null>(null);
+  // Whether the hold-to-talk key is down; set and cleared with the key itself,
+  // since a quick release can arrive before isRecording re-renders
+  const heldRef = useRef(false);
 
   useEffect(() => {
     // Configure window for overlay behavior when app starts
@@ -45,36 +51,96 @@ function App() {
     };
   }, [isRecording]);
 
-  const handleRecord = async () => {
-    try {
-      if (isRecording) {
-        // Stop recording
-        const audioPath = await invoke<string>("stop_recording");
-        setIsRecording(false);
-        setIsTranscribing(true);
-
-        // Transcribe the audio
-        const result = await invoke<string>("transcribe_audio", {
-          audioPath,
-        });
+  const handleRecord = (start: boolean = !isRecording) => {
+    return start ? startRecording() : stopRecording();
+  };
 
-        setTranscript(result);
-        setIsTranscribing(false);
-      } else {
-        // Start recording
-        setIsRecording(true);
-        setTranscript("");
-        setError(null);
-        await invoke("start_recording_toggle");
-      }
+  const startRecording = async () => {
+    try {
+      setIsRecording(true);
+      setTranscript("");
+      setFormattedText("");
+      setFormatType(null);
+      setError(null);
+      await invoke("start_recording_toggle");
     } catch (error) {
-      console.error("Recording/transcription failed:", error);
+      handleRecordError(error);
+    }
+  };
+
+  const stopRecording = async () => {
+    try {
+      const audioPath = await invoke<string>("stop_recording");
       setIsRecording(false);
+      setIsTranscribing(true);
+
+      // Transcribe the audio
+      const result = await invoke<string>("transcribe_audio", {
+        audioPath,
+      });
+
+      setTranscript(result);
       setIsTranscribing(false);
-      setError(error as string);
+    } catch (error) {
+      handleRecordError(error);
     }
   };
 
+  const handleRecordError = (error: unknown) => {
+    console.error("Recording/transcription failed:", error);
+    heldRef.current = false;
+    setIsRecording(false);
+    setIsTranscribing(false);
+    setError(error as string);
+  };
+
+  // Latest state for the global shortcut listener, which is only registered once
+  const recordRef = useRef({ isTranscribing, handleRecord });
+  recordRef.current = { isTranscribing, handleRecord };
+
+  useEffect(() => {
+    // Toggle recording on press, or record while hold-to-talk is held
+    const actionUnlisten = listen<{ action: string; pressed: boolean }>('shortcut_action', (event) => {
+      const { isTranscribing, handleRecord } = recordRef.current;
+      const { action, pressed } = event.payload;
+      if (action === 'toggle_recording') {
+        if (!isTranscribing) handleRecord();
+      } else if (action === 'hold_to_talk') {
+        // Ignore key auto-repeat and releases without a matching press
+        if (pressed === heldRef.current) return;
+        if (pressed && isTranscribing) return;
+        heldRef.current = pressed;
+        handleRecord(pressed);
+      }
+    });
+
+    // Shortcuts are registered before this window listens, so ask for any conflicts
+    invoke<Array<{ accelerator: string }>>('get_shortcut_conflicts')
+      .then((conflicts) => {
+        if (conflicts.length > 0) {
+          const keys = conflicts.map((conflict) => conflict.accelerator).join(', ');
+          setError(`Shortcut unavailable: ${keys}`);
+        }
+      })
+      .catch((error) => console.error("Failed to load shortcut conflicts:", error));
+
+    const outputUnlisten = listen<string>('output_failed', (event) => {
+      setError(`Output failed: ${event.payload}`);
+    });
+
+    // The recorder stopped by itself; clear the backend's recording, whose error is this one
+    const recordingUnlisten = listen<string>('recording_failed', (event) => {
+      invoke("stop_recording").catch(() => {});
+      handleRecordError(`Recording failed: ${event.payload}`);
+    });
+
+    return () => {
+      actionUnlisten.then((unlisten) => unlisten());
+      outputUnlisten.then((unlisten) => unlisten());
+      recordingUnlisten.then((unlisten) => unlisten());
+    };
+  }, []);
+
   const handleCopy = async () => {
     const textToCopy = formattedText || transcript;
     if (textToCopy) {
@@ -95,6 +161,7 @@ function App() {
         formatType,
       });
       setFormattedText(result);
+      setFormatType(formatType);
     } catch (error) {
       console.error("Formatting failed:", error);
       setError(error as string);
@@ -103,6 +170,23 @@ function App() {
     }
   };
 
+  // Sends only what's on screen, once, so a formatted result isn't inserted after the raw one
+  const handleInsert = async () => {
+    const text = formattedText || transcript;
+    if (!text) return;
+
+    try {
+      setError(null);
+      await invoke("send_output", {
+        text,
+        templateId: formattedText ? formatType : null,
+      });
+    } catch (error) {
+      console.error("Output failed:", error);
+      setError(error as string);
+    }
+  };
+
   return (
     <div className="h-screen bg-transparent-light window-with-stroke overflow-hidden flex flex-col">
       <Titlebar />
@@ -124,7 +208,7 @@ function App() {
                 {/* <h1 className="text-2xl font-bold mb-6">Supavoice</h1> */}
 
                 <button
-                  onClick={handleRecord}
+                  onClick={() => handleRecord()}
                   disabled={isTranscribing}
                   className={`w-24 h-24 rounded-full flex items-center justify-center transition-all ${
                     isRecording
@@ -161,24 +245,36 @@ function App() {
                       <h3 className="text-sm font-semibold">
                         {formattedText ? "Formatted:" : "Transcript:"}
                       </h3>
-                      <Button
-                        variant="outline"
-                        size="sm"
-                        onClick={handleCopy}
-                        className="gap-2"
-                      >
-                        {copied ? (
-                          <>
-                            <Check className="h-3 w-3" />
-                            Copied!
-                          </>
-                        ) : (
-                          <>
-                            <Copy className="h-3 w-3" />
-                            Copy
-                          </>
-                        )}
-                      </Button>
+                      <div className="flex gap-2">
+                        <Button
+                          variant="outline"
+                          size="sm"
+                          onClick={handleInsert}
+                          disabled={isFormatting}
+                          className="gap-2"
+                        >
+                          <Send className="h-3 w-3" />
+                          Insert
+                        </Button>
+                        <Button
+                          variant="outline"
+                          size="sm"
+                          onClick={handleCopy}
+                          className="gap-2"
+                        >
+                          {copied ? (
+                            <>
+                              <Check className="h-3 w-3" />
+                              Copied!
+                            </>
+                          ) : (
+                            <>
+                              <Copy className="h-3 w-3" />
+                              Copy
+                            </>
+                          )}
+                        </Button>
+                      </div>
                     </div>
                     <div className="p-4 bg-muted rounded-lg">
                       <p className="text-sm whitespace-pre-wrap">
@@ -192,7 +288,10 @@ function App() {
                         <Button
                           variant="ghost"
                           size="sm"
-                          onClick={() => setFormattedText("")}
+                          onClick={() => {
+                            setFormattedText("");
+                            setFormatType(null);
+                          }}
                           className="text-xs h-6"
                         >
                           Show Original
@@ -30,6 +30,15 @@ type ModelStatus =
   | 'Installed'
   | { Failed: { error: string } };
 
+interface VocabularyEntry {
+  id: string;
+  canonical: string;
+  aliases: string[];
+  category: string | null;
+  case_sensitive: boolean;
+  weight: number;
+}
+
 interface AppPreferences {
   active_whisper_model: string | null;
   active_llm_model: string | null;
@@ -42,7 +51,7 @@ export default function Settings() {
     active_whisper_model: null,
     active_llm_model: null,
   });
-  const [vocabulary, setVocabulary] = useState<string[]>([]);
+  const [vocabulary, setVocabulary] = useState<VocabularyEntry[]>([]);
   const [newWord, setNewWord] = useState<string>('');
 
   useEffect(() => {
@@ -137,7 +146,7 @@ export default function Settings() {
 
   const loadVocabulary = async () => {
     try {
-      const vocab = await invoke<string[]>('get_vocabulary');
+      const vocab = await invoke<VocabularyEntry[]>('get_vocabulary');
       setVocabulary(vocab);
     } catch (error) {
       console.error('Failed to load vocabulary:', error);
@@ -148,7 +157,7 @@ export default function Settings() {
     if (!newWord.trim()) return;
 
     try {
-      await invoke('add_vocabulary_word', { word: newWord.trim() });
+      await invoke('add_vocabulary_entry', { entry: { canonical: newWord.trim() } });
       await loadVocabulary();
       setNewWord('');
     } catch (error) {
@@ -156,9 +165,9 @@ export default function Settings() {
     }
   };
 
-  const handleRemoveWord = async (word: string) => {
+  const handleRemoveWord = async (id: string) => {
     try {
-      await invoke('remove_vocabulary_word', { word });
+      await invoke('remove_vocabulary_entry', { id });
       await loadVocabulary();
     } catch (error) {
       console.error('Failed to remove word:', error);
@@ -337,16 +346,23 @@ export default function Settings() {
                 </p>
               ) : (
                 <div className="space-y-2">
-                  {vocabulary.map((word) => (
+                  {vocabulary.map((entry) => (
                     <div
-                      key={word}
+                      key={entry.id}
                       className="flex items-center justify-between rounded-lg border p-3"
                     >
-                      <span className="font-medium">{word}</span>
+                      <div>
+                        <span className="font-medium">{entry.canonical}</span>
+                        {entry.aliases.length > 0 && (
+                          <p className="text-xs text-muted-foreground">
+                            Heard as: {entry.aliases.join(', ')}
+                          </p>
+                        )}
+                      </div>
                       <Button
                         variant="ghost"
                         size="sm"
-                        onClick={() => handleRemoveWord(word)}
+                        onClick={() => handleRemoveWord(entry.id)}
                       >
                         Remove
                       </Button>